@@ -8,10 +8,11 @@ use crate::types::{Account, TransferFromArgs, TransferFromError, TransferArg, Tr
 
 use crate::{MEMORY_MANAGER, Memory};
 use super::liquidity_pool;
-use super::types::{PendingWithdrawal, WithdrawalType, AuditEntry, AuditEvent};
+use super::types::{PendingWithdrawal, WithdrawalType, AuditEntry, AuditEvent, PlayerPnl};
 
 use super::memory_ids::{
     USER_BALANCES_MEMORY_ID,
+    PLAYER_PNL_MEMORY_ID,
     PENDING_WITHDRAWALS_MEMORY_ID,
     AUDIT_LOG_MAP_MEMORY_ID,
     AUDIT_LOG_COUNTER_MEMORY_ID,
@@ -32,6 +33,13 @@ thread_local! {
         )
     );
 
+    // Lifetime wagered/won per player, updated on every settled bet
+    static PLAYER_PNL: RefCell<StableBTreeMap<Principal, PlayerPnl, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(PLAYER_PNL_MEMORY_ID))),
+        )
+    );
+
     pub(crate) static PENDING_WITHDRAWALS: RefCell<StableBTreeMap<Principal, PendingWithdrawal, Memory>> = RefCell::new(
         StableBTreeMap::init(
             MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(PENDING_WITHDRAWALS_MEMORY_ID)))
@@ -91,6 +99,17 @@ pub(crate) enum TransferResult {
 // HELPER FUNCTIONS
 // =============================================================================
 
+// The system clock only exists inside a canister
+#[cfg(not(test))]
+fn audit_timestamp() -> u64 {
+    ic_cdk::api::time()
+}
+
+#[cfg(test)]
+fn audit_timestamp() -> u64 {
+    0
+}
+
 pub(crate) fn log_audit(event: AuditEvent) {
     // Get next counter value and increment (saturating_add prevents overflow)
     let idx = AUDIT_LOG_COUNTER.with(|counter| {
@@ -102,7 +121,7 @@ pub(crate) fn log_audit(event: AuditEvent) {
 
     // Create and insert entry
     let entry = AuditEntry {
-        timestamp: ic_cdk::api::time(),
+        timestamp: audit_timestamp(),
         event,
     };
 
@@ -438,6 +457,9 @@ pub fn start_balance_reconciliation_timer() {
             // refresh_canister_balance() queries the ledger and updates the cache
             // This automatically corrects any drift
             let _ = refresh_canister_balance().await;
+
+            // Top up a low pool from the treasury, if an admin enabled it
+            liquidity_pool::run_treasury_sweep();
         });
         *t.borrow_mut() = Some(timer_id);
     });
@@ -572,6 +594,22 @@ pub(crate) fn get_balance_internal(user: Principal) -> u64 {
     })
 }
 
+/// Lifetime totals for `user`; players with no entry yet read as zero.
+pub(crate) fn get_pnl_internal(user: Principal) -> PlayerPnl {
+    PLAYER_PNL.with(|pnl| pnl.borrow().get(&user).unwrap_or_default())
+}
+
+/// Add a settled bet to the player's lifetime totals.
+pub fn record_player_pnl(user: Principal, wagered: u64, won: u64) {
+    PLAYER_PNL.with(|pnl| {
+        let mut pnl = pnl.borrow_mut();
+        let mut entry = pnl.get(&user).unwrap_or_default();
+        entry.total_wagered = entry.total_wagered.saturating_add(wagered);
+        entry.total_won = entry.total_won.saturating_add(won);
+        pnl.insert(user, entry);
+    });
+}
+
 pub(crate) fn get_max_allowed_payout_internal() -> u64 {
     let house_balance = liquidity_pool::get_pool_reserve();
     // Backend allows 15%, frontend shows 10% - creates 50% safety buffer for max bet race conditions
@@ -148,4 +148,27 @@ pub fn get_audit_log(limit: u64, offset: u64) -> Result<Vec<AuditEntry>, String>
 pub fn get_audit_log_count() -> Result<u64, String> {
     require_admin()?;
     Ok(accounting::get_audit_count())
-}
\ No newline at end of file
+}
+
+/// Set the crash cap (highest crash point and cash-out target).
+pub fn set_max_crash(cap: f64) -> Result<(), String> {
+    require_admin()?;
+    crate::game::set_max_crash(cap)?;
+    accounting::log_audit(AuditEvent::SystemInfo {
+        message: sanitize_error(&format!("Max crash set to {}x", cap))
+    });
+    Ok(())
+}
+
+/// Configure the treasury auto-sweep into the pool reserve.
+pub fn set_treasury_sweep_config(config: TreasurySweepConfig) -> Result<(), String> {
+    require_admin()?;
+    liquidity_pool::set_treasury_sweep_config(config.clone());
+    accounting::log_audit(AuditEvent::SystemInfo {
+        message: sanitize_error(&format!(
+            "Treasury sweep config: enabled={} max_per_run={}",
+            config.enabled, config.max_sweep_per_run
+        ))
+    });
+    Ok(())
+}
@@ -7,7 +7,8 @@ use num_traits::ToPrimitive;
 
 use crate::types::{Account, TransferFromArgs, TransferFromError, CKUSDT_CANISTER_ID, CKUSDT_TRANSFER_FEE};
 use super::accounting;
-use super::memory_ids::{LP_SHARES_MEMORY_ID, POOL_STATE_MEMORY_ID};
+use super::memory_ids::{LP_SHARES_MEMORY_ID, POOL_STATE_MEMORY_ID, TREASURY_SWEEP_MEMORY_ID};
+use super::types::{AuditEvent, TreasurySweepConfig};
 
 // =============================================================================
 // CONSTANTS
@@ -16,7 +17,7 @@ use super::memory_ids::{LP_SHARES_MEMORY_ID, POOL_STATE_MEMORY_ID};
 const MINIMUM_LIQUIDITY: u64 = 1000;
 const MIN_DEPOSIT: u64 = 10_000_000; // 10 USDT minimum for LP (higher barrier than user deposits)
 const MIN_WITHDRAWAL: u64 = 100_000; // 0.1 USDT
-const MIN_OPERATING_BALANCE: u64 = 100_000_000; // 100 USDT to operate games
+pub(crate) const MIN_OPERATING_BALANCE: u64 = 100_000_000; // 100 USDT to operate games
 
 /// Maximum LP deposit: 100M USDT. Stricter than user limit (1B) because LP deposits
 /// affect share ratios and pool stability. Still ~700x total USDT supply.
@@ -108,6 +109,17 @@ thread_local! {
             }
         ))
     };
+
+    // Treasury auto-sweep rule (disabled until an admin configures it)
+    static TREASURY_SWEEP: RefCell<StableCell<TreasurySweepConfig, VirtualMemory<DefaultMemoryImpl>>> = {
+        RefCell::new(StableCell::init(
+            crate::MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(TREASURY_SWEEP_MEMORY_ID))),
+            TreasurySweepConfig {
+                enabled: false,
+                max_sweep_per_run: MIN_OPERATING_BALANCE,
+            }
+        ))
+    };
 }
 
 // Types
@@ -564,6 +576,64 @@ pub fn can_accept_bets() -> bool {
     pool_reserve >= MIN_OPERATING_BALANCE
 }
 
+// =============================================================================
+// TREASURY AUTO-SWEEP
+// =============================================================================
+
+pub fn get_treasury_sweep_config() -> TreasurySweepConfig {
+    TREASURY_SWEEP.with(|c| c.borrow().get().clone())
+}
+
+pub(crate) fn set_treasury_sweep_config(config: TreasurySweepConfig) {
+    TREASURY_SWEEP.with(|c| c.borrow_mut().set(config));
+}
+
+/// Amount to move from the treasury into the pool this run.
+///
+/// Tops the reserve up to MIN_OPERATING_BALANCE and no further, limited by
+/// what the treasury holds and the per-run cap. Zero once the pool is healthy.
+pub(crate) fn plan_treasury_sweep(reserve: u64, treasury_balance: u64, max_sweep_per_run: u64) -> u64 {
+    MIN_OPERATING_BALANCE
+        .saturating_sub(reserve)
+        .min(treasury_balance)
+        .min(max_sweep_per_run)
+}
+
+/// Sweep house treasury funds into a low pool. Called from the reconciliation timer.
+///
+/// The treasury is the parent staker's user balance. The funds are already
+/// held by the canister, so moving them into the reserve leaves total
+/// obligations unchanged. Returns the amount swept.
+pub(crate) fn run_treasury_sweep() -> u64 {
+    let config = get_treasury_sweep_config();
+    if !config.enabled {
+        return 0;
+    }
+
+    let treasury = get_parent_principal();
+    let treasury_balance = accounting::get_balance_internal(treasury);
+    let amount = plan_treasury_sweep(get_pool_reserve(), treasury_balance, config.max_sweep_per_run);
+    if amount == 0 {
+        return 0;
+    }
+
+    // Fails harmlessly if the treasury has a withdrawal in flight
+    if let Err(e) = accounting::try_deduct_balance(treasury, amount) {
+        accounting::log_audit(AuditEvent::SystemError {
+            error: super::types::sanitize_error(&format!("Treasury sweep skipped: {}", e))
+        });
+        return 0;
+    }
+
+    add_to_reserve(amount);
+    accounting::log_audit(AuditEvent::TreasurySwept {
+        treasury,
+        amount,
+        new_reserve: get_pool_reserve(),
+    });
+    amount
+}
+
 // Game integration (internal use only - called by game logic)
 
 pub(crate) fn update_pool_on_win(payout: u64) {
@@ -4,14 +4,20 @@
 //! Run `cargo test` to verify no collisions exist.
 //!
 //! Allocation strategy:
+//! - 1-9: Game configuration
 //! - 10-19: User accounting (balances, LP shares, pool state)
 //! - 20-29: Withdrawal & audit (pending, audit log)
 //! - 30-39: Statistics (snapshots, accumulator)
 
+// Game configuration (1-9)
+pub const MAX_CRASH_MEMORY_ID: u8 = 1;
+
 // User accounting (10-19)
 pub const USER_BALANCES_MEMORY_ID: u8 = 10;
 pub const LP_SHARES_MEMORY_ID: u8 = 11;
+pub const PLAYER_PNL_MEMORY_ID: u8 = 12;
 pub const POOL_STATE_MEMORY_ID: u8 = 13;
+pub const TREASURY_SWEEP_MEMORY_ID: u8 = 14;
 
 // Withdrawals & audit (20-29)
 pub const PENDING_WITHDRAWALS_MEMORY_ID: u8 = 20;
@@ -29,9 +35,12 @@ mod tests {
     #[test]
     fn memory_ids_are_unique() {
         let ids = [
+            MAX_CRASH_MEMORY_ID,
             USER_BALANCES_MEMORY_ID,
             LP_SHARES_MEMORY_ID,
+            PLAYER_PNL_MEMORY_ID,
             POOL_STATE_MEMORY_ID,
+            TREASURY_SWEEP_MEMORY_ID,
             PENDING_WITHDRAWALS_MEMORY_ID,
             AUDIT_LOG_MAP_MEMORY_ID,
             AUDIT_LOG_COUNTER_MEMORY_ID,
@@ -14,6 +14,16 @@ pub fn get_my_balance() -> u64 {
     get_balance(ic_cdk::api::msg_caller())
 }
 
+/// (total_wagered, total_won, net_profit) for `user`
+pub fn get_pnl(user: Principal) -> (u64, u64, i64) {
+    let pnl = accounting::get_pnl_internal(user);
+    (pnl.total_wagered, pnl.total_won, pnl.net_profit())
+}
+
+pub fn get_my_pnl() -> (u64, u64, i64) {
+    get_pnl(ic_cdk::api::msg_caller())
+}
+
 pub fn get_max_allowed_payout() -> u64 {
     accounting::get_max_allowed_payout_internal()
 }
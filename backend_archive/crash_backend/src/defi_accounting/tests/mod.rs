@@ -1,4 +1,6 @@
 pub mod test_serialization;
 pub mod test_slippage_audit;
+pub mod test_treasury_sweep;
+pub mod test_player_pnl;
 mod stress_tests;
 mod adversarial;
\ No newline at end of file
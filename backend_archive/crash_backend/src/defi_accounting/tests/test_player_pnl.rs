@@ -0,0 +1,47 @@
+// Test: Player P&L Tracking
+//
+// Verifies that lifetime totals:
+// 1. Read as zero for players with no recorded bets
+// 2. Accumulate wagered and won across settlements
+// 3. Report net profit with the correct sign, per player
+
+use candid::Principal;
+use crate::defi_accounting::accounting::{get_pnl_internal, record_player_pnl};
+use crate::defi_accounting::query::get_pnl;
+use crate::defi_accounting::types::PlayerPnl;
+
+const USDT: u64 = 1_000_000;
+
+fn player(id: u8) -> Principal {
+    Principal::from_slice(&[id; 29])
+}
+
+#[test]
+fn test_unknown_player_reads_zero() {
+    assert_eq!(get_pnl_internal(player(1)), PlayerPnl::default());
+    assert_eq!(get_pnl(player(1)), (0, 0, 0));
+}
+
+#[test]
+fn test_settlements_accumulate() {
+    // Lost 5, won 2x on 10, lost 3 across three rounds
+    record_player_pnl(player(2), 5 * USDT, 0);
+    record_player_pnl(player(2), 10 * USDT, 20 * USDT);
+    record_player_pnl(player(2), 3 * USDT, 0);
+
+    assert_eq!(get_pnl(player(2)), (18 * USDT, 20 * USDT, 2 * USDT as i64));
+    // Other players are unaffected
+    assert_eq!(get_pnl(player(3)), (0, 0, 0));
+}
+
+#[test]
+fn test_net_loss_is_negative() {
+    record_player_pnl(player(4), 7 * USDT, USDT);
+    assert_eq!(get_pnl(player(4)).2, -6 * USDT as i64);
+}
+
+#[test]
+fn test_net_profit_saturates() {
+    let pnl = PlayerPnl { total_wagered: 0, total_won: u64::MAX };
+    assert_eq!(pnl.net_profit(), i64::MAX);
+}
@@ -0,0 +1,84 @@
+// Test: Treasury Auto-Sweep Planning
+//
+// Verifies that the reconciliation sweep:
+// 1. Moves treasury funds into the pool while the reserve is below MIN_OPERATING_BALANCE
+// 2. Never overshoots the operating balance, the treasury balance or the per-run cap
+// 3. Stops once the operating balance is restored
+// 4. Only ever draws on the house treasury, never on a player's balance
+
+use candid::Principal;
+
+use crate::defi_accounting::accounting::{get_balance_internal, update_balance};
+use crate::defi_accounting::liquidity_pool::{
+    get_parent_principal, get_pool_reserve, plan_treasury_sweep, run_treasury_sweep,
+    set_treasury_sweep_config, MIN_OPERATING_BALANCE,
+};
+use crate::defi_accounting::types::TreasurySweepConfig;
+
+const USDT: u64 = 1_000_000;
+
+#[test]
+fn test_low_pool_triggers_sweep() {
+    let amount = plan_treasury_sweep(40 * USDT, 1_000 * USDT, 1_000 * USDT);
+    assert_eq!(amount, MIN_OPERATING_BALANCE - 40 * USDT);
+}
+
+#[test]
+fn test_healthy_pool_is_not_swept() {
+    assert_eq!(plan_treasury_sweep(MIN_OPERATING_BALANCE, 1_000 * USDT, 1_000 * USDT), 0);
+    assert_eq!(plan_treasury_sweep(MIN_OPERATING_BALANCE * 5, 1_000 * USDT, 1_000 * USDT), 0);
+}
+
+#[test]
+fn test_sweep_limited_by_treasury_and_cap() {
+    // Treasury only holds 10 USDT
+    assert_eq!(plan_treasury_sweep(0, 10 * USDT, 1_000 * USDT), 10 * USDT);
+    // Cap of 25 USDT per run
+    assert_eq!(plan_treasury_sweep(0, 1_000 * USDT, 25 * USDT), 25 * USDT);
+    // Empty treasury
+    assert_eq!(plan_treasury_sweep(0, 0, 1_000 * USDT), 0);
+}
+
+#[test]
+fn test_repeated_runs_stop_at_operating_balance() {
+    let mut reserve = 5 * USDT;
+    let mut treasury = 500 * USDT;
+    let cap = 30 * USDT;
+    let mut runs = 0;
+
+    loop {
+        let amount = plan_treasury_sweep(reserve, treasury, cap);
+        if amount == 0 {
+            break;
+        }
+        treasury -= amount;
+        reserve += amount;
+        runs += 1;
+        assert!(runs <= 10, "sweep never converged");
+    }
+
+    assert_eq!(reserve, MIN_OPERATING_BALANCE);
+    assert_eq!(treasury, 500 * USDT - (MIN_OPERATING_BALANCE - 5 * USDT));
+    assert_eq!(runs, 4); // 30 + 30 + 30 + 5
+}
+
+#[test]
+fn test_run_sweep_moves_house_treasury_only() {
+    let house = get_parent_principal();
+    let player = Principal::from_slice(&[7]);
+    update_balance(house, 30 * USDT).unwrap();
+    update_balance(player, 500 * USDT).unwrap();
+
+    // Disabled by default
+    assert_eq!(run_treasury_sweep(), 0);
+
+    set_treasury_sweep_config(TreasurySweepConfig { enabled: true, max_sweep_per_run: 1_000 * USDT });
+    assert_eq!(run_treasury_sweep(), 30 * USDT);
+    assert_eq!(get_pool_reserve(), 30 * USDT);
+    assert_eq!(get_balance_internal(house), 0);
+
+    // Pool still low, but the house treasury is empty and players are untouched
+    assert_eq!(run_treasury_sweep(), 0);
+    assert_eq!(get_pool_reserve(), 30 * USDT);
+    assert_eq!(get_balance_internal(player), 500 * USDT);
+}
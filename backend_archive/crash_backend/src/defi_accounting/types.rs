@@ -101,6 +101,12 @@ pub enum AuditEvent {
         amount: u64,
         new_balance: u64,
     },
+    /// Treasury balance moved into the pool reserve by the auto-sweep.
+    TreasurySwept {
+        treasury: Principal,
+        amount: u64,
+        new_reserve: u64,
+    },
 }
 
 /// Health check result for admin monitoring.
@@ -187,3 +193,65 @@ impl Storable for AuditEntry {
 
     const BOUND: Bound = Bound::Unbounded;
 }
+
+/// Lifetime betting totals for one player.
+///
+/// Players who have not bet since this was introduced have no entry and
+/// read as all zeros.
+#[derive(CandidType, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct PlayerPnl {
+    pub total_wagered: u64,
+    pub total_won: u64,
+}
+
+impl PlayerPnl {
+    /// Net profit (won - wagered), saturating at the i64 range
+    pub fn net_profit(&self) -> i64 {
+        (self.total_won as i128 - self.total_wagered as i128)
+            .clamp(i64::MIN as i128, i64::MAX as i128) as i64
+    }
+}
+
+impl Storable for PlayerPnl {
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Owned(candid::encode_one(self).expect("Failed to encode PlayerPnl"))
+    }
+
+    fn into_bytes(self) -> Vec<u8> {
+        self.to_bytes().into_owned()
+    }
+
+    fn from_bytes(bytes: Cow<'_, [u8]>) -> Self {
+        candid::decode_one(&bytes).expect("Failed to decode PlayerPnl")
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+/// Admin-configured rule for topping up a low pool from the house treasury.
+///
+/// When enabled, each reconciliation run moves up to `max_sweep_per_run`
+/// from the parent staker's user balance into the pool reserve while the
+/// reserve is below the minimum operating balance. The source is fixed so
+/// the sweep can never draw on a player's balance.
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq)]
+pub struct TreasurySweepConfig {
+    pub enabled: bool,
+    pub max_sweep_per_run: u64,
+}
+
+impl Storable for TreasurySweepConfig {
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Owned(candid::encode_one(self).expect("Failed to encode TreasurySweepConfig"))
+    }
+
+    fn into_bytes(self) -> Vec<u8> {
+        self.to_bytes().into_owned()
+    }
+
+    fn from_bytes(bytes: Cow<'_, [u8]>) -> Self {
+        candid::decode_one(&bytes).expect("Failed to decode TreasurySweepConfig")
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
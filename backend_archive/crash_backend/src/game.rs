@@ -4,14 +4,23 @@ use crate::types::MIN_BET;
 use crate::defi_accounting::{self as accounting, liquidity_pool};
 use serde::Serialize;
 use sha2::{Sha256, Digest};
+use ic_stable_structures::{StableCell, memory_manager::MemoryId};
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use crate::{MEMORY_MANAGER, Memory};
+use crate::defi_accounting::memory_ids::MAX_CRASH_MEMORY_ID;
 
 // Constants
-const MAX_CRASH: f64 = 100.0;
+pub const DEFAULT_MAX_CRASH: f64 = 100.0;
+pub const MIN_MAX_CRASH: f64 = 10.0;
+pub const MAX_MAX_CRASH: f64 = 10_000.0;
 const MAX_ROCKETS: u8 = 10;
 
-// Max multiplier for bet validation (100x max crash)
-// This must match MAX_CRASH
-const MAX_MULTIPLIER_SCALE: u64 = 100_000_000; // 100.0 * 1_000_000 (6 decimal precision)
+pub const COMBINED_PAYOUT_ERROR: &str = "Combined payout exceeds pool limit";
+
+/// Rounds kept for the recent-crashes feed
+pub const MAX_ROUND_HISTORY: usize = 200;
+
 const MULTIPLIER_SCALE: u64 = 1_000_000; // 6 decimal precision for multiplier
 
 // =============================================================================
@@ -33,6 +42,7 @@ pub struct PlayCrashResult {
 pub struct SingleRocketResult {
     pub rocket_index: u8,
     pub crash_point: f64,
+    pub target_multiplier: f64,
     pub reached_target: bool,
     pub payout: u64,
 }
@@ -40,6 +50,7 @@ pub struct SingleRocketResult {
 #[derive(CandidType, Deserialize, Serialize, Clone, Debug)]
 pub struct MultiCrashResult {
     pub rockets: Vec<SingleRocketResult>,
+    /// Shared target; with per-rocket targets, the highest one
     pub target_multiplier: f64,
     pub rocket_count: u8,
     pub rockets_succeeded: u8,
@@ -50,6 +61,63 @@ pub struct MultiCrashResult {
     pub master_randomness_hash: String,
 }
 
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct RoundRecord {
+    pub crash_point: f64,
+    pub timestamp: u64,
+}
+
+// =============================================================================
+// CRASH CAP
+// =============================================================================
+
+thread_local! {
+    // Highest crash point (and cash-out target); admin-settable, survives upgrades
+    static MAX_CRASH: RefCell<StableCell<f64, Memory>> = RefCell::new(
+        StableCell::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(MAX_CRASH_MEMORY_ID))),
+            DEFAULT_MAX_CRASH
+        )
+    );
+}
+
+pub fn max_crash() -> f64 {
+    MAX_CRASH.with(|c| *c.borrow().get())
+}
+
+pub fn set_max_crash(cap: f64) -> Result<(), String> {
+    if !cap.is_finite() || !(MIN_MAX_CRASH..=MAX_MAX_CRASH).contains(&cap) {
+        return Err(format!("Invalid crash cap: must be {}-{}", MIN_MAX_CRASH, MAX_MAX_CRASH));
+    }
+    MAX_CRASH.with(|c| c.borrow_mut().set(cap));
+    Ok(())
+}
+
+// =============================================================================
+// ROUND HISTORY
+// =============================================================================
+
+thread_local! {
+    // Transient feed for the history strip; cleared on upgrade
+    static ROUND_HISTORY: RefCell<VecDeque<RoundRecord>> = RefCell::new(VecDeque::with_capacity(MAX_ROUND_HISTORY));
+}
+
+fn record_round(crash_point: f64, timestamp: u64) {
+    ROUND_HISTORY.with(|h| {
+        let mut history = h.borrow_mut();
+        if history.len() == MAX_ROUND_HISTORY {
+            history.pop_front();
+        }
+        history.push_back(RoundRecord { crash_point, timestamp });
+    });
+}
+
+/// Most recent settled single-rocket rounds, newest first
+pub fn get_recent_crashes(limit: u32) -> Vec<RoundRecord> {
+    let limit = (limit as usize).min(MAX_ROUND_HISTORY);
+    ROUND_HISTORY.with(|h| h.borrow().iter().rev().take(limit).cloned().collect())
+}
+
 // =============================================================================
 // HELPER FUNCTIONS
 // =============================================================================
@@ -60,10 +128,11 @@ pub fn calculate_max_bet() -> u64 {
     if max_allowed == 0 {
         return 0;
     }
-    // max_bet = max_allowed / max_multiplier (100x)
+    // max_bet = max_allowed / max_multiplier (the crash cap, 100x by default)
     // Use u128 to prevent overflow during calculation
+    let max_multiplier_scaled = (max_crash() * MULTIPLIER_SCALE as f64) as u128;
     let numerator = (max_allowed as u128) * (MULTIPLIER_SCALE as u128);
-    let max_bet = numerator / (MAX_MULTIPLIER_SCALE as u128);
+    let max_bet = numerator / max_multiplier_scaled;
 
     max_bet as u64
 }
@@ -153,7 +222,7 @@ fn derive_rocket_random(vrf_bytes: &[u8], rocket_index: u8) -> Result<f64, Strin
 pub fn calculate_crash_point(random: f64) -> f64 {
     let random = random.max(0.0).min(0.99999);
     let crash = 0.99 / (1.0 - random);
-    crash.min(MAX_CRASH)
+    crash.min(max_crash())
 }
 
 /// Create SHA256 hash of IC randomness bytes for audit/display
@@ -179,15 +248,7 @@ pub async fn play_crash(bet_amount: u64, target_multiplier: f64, caller: Princip
     }
 
     // 2. Validate target multiplier
-    if target_multiplier < 1.01 {
-        return Err("Target must be at least 1.01x".to_string());
-    }
-    if target_multiplier > MAX_CRASH {
-        return Err(format!("Target cannot exceed {}x", MAX_CRASH));
-    }
-    if !target_multiplier.is_finite() {
-        return Err("Target must be a finite number".to_string());
-    }
+    validate_target(target_multiplier)?;
 
     // 3. Check max payout against house limit
     let max_potential_payout = calculate_payout(bet_amount, target_multiplier)?;
@@ -240,6 +301,8 @@ pub async fn play_crash(bet_amount: u64, target_multiplier: f64, caller: Princip
         ic_cdk::println!("CRITICAL: Crash payout failure. Refunded {} to {}", bet_amount, caller);
         return Err(format!("House settlement failed. Bet refunded. Error: {}", e));
     }
+    accounting::record_player_pnl(caller, bet_amount, payout);
+    record_round(crash_point, ic_cdk::api::time());
 
     // 12. Create randomness hash
     let randomness_hash = create_randomness_hash(&random_bytes);
@@ -256,41 +319,80 @@ pub async fn play_crash(bet_amount: u64, target_multiplier: f64, caller: Princip
 }
 
 pub async fn play_crash_multi(bet_per_rocket: u64, target_multiplier: f64, rocket_count: u8, caller: Principal) -> Result<MultiCrashResult, String> {
-    // 1. Validate inputs
     if rocket_count < 1 {
         return Err("Must launch at least 1 rocket".to_string());
     }
     if rocket_count > MAX_ROCKETS {
         return Err(format!("Maximum {} rockets allowed", MAX_ROCKETS));
     }
-    if bet_per_rocket < MIN_BET {
-        return Err("Invalid bet: minimum is 0.01 USDT per rocket".to_string());
-    }
+    let targets = vec![target_multiplier; rocket_count as usize];
+    play_rockets(bet_per_rocket, &targets, caller).await
+}
 
-    // Validate target multiplier
+/// Multi-rocket game where each rocket cashes out at its own target.
+/// The rocket count is `targets.len()`.
+pub async fn play_crash_multi_targets(bet_per_rocket: u64, targets: Vec<f64>, caller: Principal) -> Result<MultiCrashResult, String> {
+    play_rockets(bet_per_rocket, &targets, caller).await
+}
+
+pub(crate) fn validate_target(target_multiplier: f64) -> Result<(), String> {
     if target_multiplier < 1.01 {
         return Err("Target must be at least 1.01x".to_string());
     }
-    if target_multiplier > MAX_CRASH {
-        return Err(format!("Target cannot exceed {}x", MAX_CRASH));
+    let cap = max_crash();
+    if target_multiplier > cap {
+        return Err(format!("Target cannot exceed {}x", cap));
     }
     if !target_multiplier.is_finite() {
         return Err("Target must be a finite number".to_string());
     }
+    Ok(())
+}
+
+/// Validate a per-rocket target list; returns the rocket count
+pub(crate) fn validate_targets(targets: &[f64]) -> Result<u8, String> {
+    if targets.is_empty() {
+        return Err("Must launch at least 1 rocket".to_string());
+    }
+    if targets.len() > MAX_ROCKETS as usize {
+        return Err(format!("Maximum {} rockets allowed", MAX_ROCKETS));
+    }
+    for (i, &target) in targets.iter().enumerate() {
+        validate_target(target).map_err(|e| format!("Rocket {}: {}", i, e))?;
+    }
+    Ok(targets.len() as u8)
+}
+
+/// Worst-case payout: every rocket reaches its target
+fn max_potential_payout(bet_per_rocket: u64, targets: &[f64]) -> Result<u64, String> {
+    targets.iter().try_fold(0u64, |total, &target| {
+        total.checked_add(calculate_payout(bet_per_rocket, target)?)
+            .ok_or_else(|| "Max payout calculation overflow".to_string())
+    })
+}
+
+/// Reject a rocket batch whose all-win payout exceeds the pool limit, so no
+/// batch can be partly paid before the pool runs dry
+pub(crate) fn check_combined_payout(bet_per_rocket: u64, targets: &[f64], max_allowed: u64) -> Result<(), String> {
+    if max_potential_payout(bet_per_rocket, targets)? > max_allowed {
+        return Err(COMBINED_PAYOUT_ERROR.to_string());
+    }
+    Ok(())
+}
+
+async fn play_rockets(bet_per_rocket: u64, targets: &[f64], caller: Principal) -> Result<MultiCrashResult, String> {
+    // 1. Validate inputs (targets first so empty/oversized lists report clearly)
+    let rocket_count = validate_targets(targets)?;
+    if bet_per_rocket < MIN_BET {
+        return Err("Invalid bet: minimum is 0.01 USDT per rocket".to_string());
+    }
 
     let total_bet = bet_per_rocket.checked_mul(rocket_count as u64)
         .ok_or("Total bet calculation overflow")?;
 
     // 2. Check max payout against house limit
-    // Worst case: all rockets win at target multiplier
-    let max_payout_per_rocket = calculate_payout(bet_per_rocket, target_multiplier)?;
-    let max_potential_payout = max_payout_per_rocket.checked_mul(rocket_count as u64)
-        .ok_or("Max payout calculation overflow")?;
-
-    let max_allowed = accounting::get_max_allowed_payout();
-    if max_potential_payout > max_allowed {
-        return Err("Invalid bet: exceeds house limit for total payout".to_string());
-    }
+    // Worst case: all rockets win at their targets
+    check_combined_payout(bet_per_rocket, targets, accounting::get_max_allowed_payout())?;
 
     // 3. Get VRF randomness (async call - execution may suspend here)
     let random_bytes = raw_rand().await
@@ -300,7 +402,9 @@ pub async fn play_crash_multi(bet_per_rocket: u64, target_multiplier: f64, rocke
         return Err("Insufficient randomness".to_string());
     }
 
-    // 4. Atomically deduct total bet AFTER await to prevent TOCTOU race condition
+    // 4. Re-check the limit (the pool may have shrunk during the await), then
+    // atomically deduct total bet AFTER await to prevent TOCTOU race condition
+    check_combined_payout(bet_per_rocket, targets, accounting::get_max_allowed_payout())?;
     let _balance_after_bet = accounting::try_deduct_balance(caller, total_bet)?;
 
     // 5. Record volume
@@ -311,7 +415,7 @@ pub async fn play_crash_multi(bet_per_rocket: u64, target_multiplier: f64, rocke
     let mut rockets_succeeded: u8 = 0;
     let mut total_payout: u64 = 0;
 
-    for i in 0..rocket_count {
+    for (i, &target_multiplier) in (0..rocket_count).zip(targets) {
         let random = derive_rocket_random(&random_bytes, i)?;
         let crash_point = calculate_crash_point(random);
         let reached_target = crash_point >= target_multiplier;
@@ -331,6 +435,7 @@ pub async fn play_crash_multi(bet_per_rocket: u64, target_multiplier: f64, rocke
         rockets.push(SingleRocketResult {
             rocket_index: i,
             crash_point,
+            target_multiplier,
             reached_target,
             payout,
         });
@@ -352,10 +457,12 @@ pub async fn play_crash_multi(bet_per_rocket: u64, target_multiplier: f64, rocke
         ic_cdk::println!("CRITICAL: Multi-rocket payout failure. Refunded {} to {}", total_bet, caller);
         return Err(format!("House settlement failed. Bet refunded. Error: {}", e));
     }
+    accounting::record_player_pnl(caller, total_bet, total_payout);
 
     // 10. Aggregate results
     let net_profit = (total_payout as i64) - (total_bet as i64);
     let master_randomness_hash = create_randomness_hash(&random_bytes);
+    let target_multiplier = targets.iter().copied().fold(f64::MIN, f64::max);
 
     Ok(MultiCrashResult {
         rockets,
@@ -385,8 +492,8 @@ pub fn get_max_bet_per_rocket(rocket_count: u8, target_multiplier: f64) -> Resul
     // Validate and clamp target multiplier
     let target = if !target_multiplier.is_finite() || target_multiplier < 1.01 {
         1.01 // Default to minimum if invalid
-    } else if target_multiplier > MAX_CRASH {
-        MAX_CRASH
+    } else if target_multiplier > max_crash() {
+        max_crash()
     } else {
         target_multiplier
     };
@@ -421,3 +528,53 @@ pub fn get_max_bet_per_rocket(rocket_count: u8, target_multiplier: f64) -> Resul
 
     Ok(max_bet as u64)
 }
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_max_rockets_all_win_against_small_pool() {
+        const USDT: u64 = 1_000_000;
+        // 100 USDT pool: 15% max payout = 15 USDT
+        let max_allowed = 100 * USDT * 15 / 100;
+
+        // Each rocket alone (1 USDT at 10x = 10 USDT) fits under the cap
+        assert_eq!(check_combined_payout(USDT, &[10.0], max_allowed), Ok(()));
+
+        // All ten winning would pay 100 USDT: the whole batch is rejected
+        let targets = [10.0; MAX_ROCKETS as usize];
+        assert_eq!(
+            check_combined_payout(USDT, &targets, max_allowed),
+            Err(COMBINED_PAYOUT_ERROR.to_string())
+        );
+
+        // Mixed targets are summed: 1.5 × 8 + 3 = 15 USDT is exactly at the cap
+        let mut mixed = [1.5; 9];
+        mixed[8] = 3.0;
+        assert_eq!(check_combined_payout(USDT, &mixed, max_allowed), Ok(()));
+        assert!(check_combined_payout(USDT + 1, &mixed, max_allowed).is_err());
+    }
+
+    #[test]
+    fn test_recent_crashes_newest_first_and_bounded() {
+        assert!(get_recent_crashes(50).is_empty());
+
+        for i in 0..(MAX_ROUND_HISTORY as u64 + 5) {
+            record_round(1.0 + i as f64, i);
+        }
+
+        let recent = get_recent_crashes(3);
+        let timestamps: Vec<u64> = recent.iter().map(|r| r.timestamp).collect();
+        assert_eq!(timestamps, vec![204, 203, 202]);
+
+        // Oldest five rounds were evicted; the limit is capped at the buffer size
+        let all = get_recent_crashes(u32::MAX);
+        assert_eq!(all.len(), MAX_ROUND_HISTORY);
+        assert_eq!(all.last().map(|r| r.timestamp), Some(5));
+    }
+}
@@ -45,9 +45,6 @@ thread_local! {
         RefCell::new(MemoryManager::init(DefaultMemoryImpl::default()));
 }
 
-// Constants
-const MAX_CRASH: f64 = 100.0;
-
 // Legacy result types (for non-betting pure game endpoints)
 #[derive(CandidType, Deserialize, Clone, Debug)]
 pub struct CrashResult {
@@ -141,6 +138,21 @@ async fn play_crash_multi(bet_per_rocket: u64, target_multiplier: f64, rocket_co
     game::play_crash_multi(bet_per_rocket, target_multiplier, rocket_count, ic_cdk::api::msg_caller()).await
 }
 
+/// Play multiple rockets, each with its own cash-out target
+#[update]
+async fn play_crash_multi_targets(bet_per_rocket: u64, targets: Vec<f64>) -> Result<MultiCrashResult, String> {
+    if !is_canister_solvent() {
+        return Err("Game temporarily paused - insufficient funds.".to_string());
+    }
+    game::play_crash_multi_targets(bet_per_rocket, targets, ic_cdk::api::msg_caller()).await
+}
+
+/// Recent single-rocket crash points for the history strip (newest first, max 200)
+#[query]
+fn get_recent_crashes(limit: u32) -> Vec<game::RoundRecord> {
+    game::get_recent_crashes(limit)
+}
+
 #[query]
 fn get_max_bet() -> u64 {
     game::get_max_bet()
@@ -190,6 +202,12 @@ fn get_my_balance() -> u64 {
     defi_accounting::query::get_my_balance()
 }
 
+/// Caller's lifetime (total_wagered, total_won, net_profit)
+#[query]
+fn get_my_pnl() -> (u64, u64, i64) {
+    defi_accounting::query::get_my_pnl()
+}
+
 #[query]
 fn get_house_balance() -> u64 {
     defi_accounting::query::get_house_balance()
@@ -298,6 +316,16 @@ fn admin_get_audit_log_count() -> Result<u64, String> {
     defi_accounting::admin_query::get_audit_log_count()
 }
 
+#[update]
+fn admin_set_treasury_sweep_config(config: defi_accounting::types::TreasurySweepConfig) -> Result<(), String> {
+    defi_accounting::admin_query::set_treasury_sweep_config(config)
+}
+
+#[query]
+fn get_treasury_sweep_config() -> defi_accounting::types::TreasurySweepConfig {
+    defi_accounting::liquidity_pool::get_treasury_sweep_config()
+}
+
 // =============================================================================
 // STATISTICS ENDPOINTS
 // =============================================================================
@@ -348,21 +376,50 @@ fn get_win_probability(target: f64) -> Result<f64, String> {
     if target < 1.0 {
         return Ok(1.0);
     }
-    if target > MAX_CRASH {
+    if target > game::max_crash() {
         return Ok(0.0);
     }
     Ok((0.99 / target).min(1.0))
 }
 
-/// Get example crash probabilities for common targets
+/// Get example crash probabilities for common targets, ending at the crash cap
 #[query]
 fn get_probability_table() -> Vec<(f64, f64)> {
-    const TARGETS: [f64; 8] = [1.1, 1.5, 2.0, 3.0, 5.0, 10.0, 50.0, 100.0];
+    const TARGETS: [f64; 7] = [1.1, 1.5, 2.0, 3.0, 5.0, 10.0, 50.0];
+    let cap = game::max_crash();
     TARGETS.iter()
-        .map(|&t| (t, get_win_probability(t).unwrap_or(0.0)))
+        .copied()
+        .filter(|&t| t < cap)
+        .chain(std::iter::once(cap))
+        .map(|t| (t, get_win_probability(t).unwrap_or(0.0)))
         .collect()
 }
 
+/// Admin-only: set the crash cap (10x-10000x, default 100x).
+/// Changes the win probabilities and probability table, so clients should
+/// re-query get_probability_table and get_max_crash afterwards.
+#[update]
+fn admin_set_max_crash(cap: f64) -> Result<(), String> {
+    defi_accounting::admin_query::set_max_crash(cap)
+}
+
+#[query]
+fn get_max_crash() -> f64 {
+    game::max_crash()
+}
+
+#[query]
+fn get_game_info() -> types::GameInfo {
+    types::GameInfo {
+        name: "Crash".to_string(),
+        min_bet: types::MIN_BET,
+        max_bet: game::get_max_bet(),
+        token_symbol: types::TOKEN_SYMBOL.to_string(),
+        house_edge_bps: types::HOUSE_EDGE_BPS,
+        modes: vec!["single".to_string(), "multi_rocket".to_string()],
+    }
+}
+
 #[query]
 fn greet(name: String) -> String {
     format!("Crash Game with DeFi: {} can now bet with real USDT!", name)
@@ -375,9 +432,7 @@ fn greet(name: String) -> String {
 /// Calculate crash point using the formula
 /// Exposed for testing only - actual game uses game::calculate_crash_point
 pub fn calculate_crash_point(random: f64) -> f64 {
-    let random = random.max(0.0).min(0.99999);
-    let crash = 0.99 / (1.0 - random);
-    crash.min(MAX_CRASH)
+    game::calculate_crash_point(random)
 }
 
 // ============================================================================
@@ -388,6 +443,18 @@ pub fn calculate_crash_point(random: f64) -> f64 {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_game_info_matches_config() {
+        let info = get_game_info();
+        assert_eq!(info.name, "Crash");
+        assert_eq!(info.min_bet, types::MIN_BET);
+        assert_eq!(info.max_bet, game::get_max_bet());
+        assert_eq!(info.token_symbol, "ckUSDT");
+        assert_eq!(info.house_edge_bps, 100);
+        assert!((get_expected_value() - (1.0 - info.house_edge_bps as f64 / 10_000.0)).abs() < 1e-9);
+        assert_eq!(info.modes, vec!["single", "multi_rocket"]);
+    }
+
     #[test]
     fn test_crash_formula_at_boundaries() {
         assert!((calculate_crash_point(0.0) - 0.99).abs() < 0.01);
@@ -395,7 +462,7 @@ mod tests {
         assert!((calculate_crash_point(0.9) - 9.9).abs() < 0.1);
         let high_crash = calculate_crash_point(0.99);
         assert!((high_crash - 99.0).abs() < 1.0);
-        assert!(high_crash <= MAX_CRASH);
+        assert!(high_crash <= game::DEFAULT_MAX_CRASH);
     }
 
     #[test]
@@ -435,6 +502,40 @@ mod tests {
         assert!(get_win_probability(f64::INFINITY).is_err());
     }
 
+    #[test]
+    fn test_per_rocket_targets_validated() {
+        assert_eq!(game::validate_targets(&[1.5, 10.0]), Ok(2));
+        assert_eq!(game::validate_targets(&[2.0; 10]), Ok(10));
+
+        assert!(game::validate_targets(&[]).is_err());
+        assert!(game::validate_targets(&[2.0; 11]).is_err());
+        for bad in [1.0, 100.5, f64::NAN, f64::INFINITY] {
+            let err = game::validate_targets(&[1.5, bad]).unwrap_err();
+            assert!(err.starts_with("Rocket 1:"), "{}", err);
+        }
+    }
+
+    #[test]
+    fn test_raised_crash_cap_extends_curve() {
+        let (top, top_probability) = *get_probability_table().last().expect("non-empty table");
+        assert_eq!(top, 100.0);
+        assert!((top_probability - 0.0099).abs() < 1e-12);
+
+        game::set_max_crash(1_000.0).expect("valid cap");
+        assert!((calculate_crash_point(0.999) - 990.0).abs() < 0.5);
+        assert!((get_win_probability(500.0).unwrap() - 0.00198).abs() < 1e-9);
+        assert_eq!(get_win_probability(1_001.0).unwrap(), 0.0);
+
+        let table = get_probability_table();
+        assert_eq!(table.len(), 8);
+        assert_eq!(table.last().map(|&(t, _)| t), Some(1_000.0));
+
+        for bad in [9.9, 10_000.1, f64::NAN, f64::INFINITY] {
+            assert!(game::set_max_crash(bad).is_err());
+        }
+        assert_eq!(get_max_crash(), 1_000.0);
+    }
+
     #[test]
     fn test_game_calculate_crash_point() {
         // Test using game module's function
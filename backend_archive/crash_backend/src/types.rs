@@ -7,9 +7,27 @@ use serde::Serialize;
 
 pub const DECIMALS_PER_CKUSDT: u64 = 1_000_000; // 1 ckUSDT = 1,000,000 decimals (6 decimals)
 pub const MIN_BET: u64 = 10_000; // 0.01 USDT (matches plinko)
+pub const TOKEN_SYMBOL: &str = "ckUSDT";
+pub const HOUSE_EDGE_BPS: u64 = 100; // crash = 0.99 / (1 - random) returns 99%
 pub const CKUSDT_CANISTER_ID: &str = "cngnf-vqaaa-aaaar-qag4q-cai";
 pub const CKUSDT_TRANSFER_FEE: u64 = 10_000; // 0.01 USDT
 
+// =============================================================================
+// GAME INFO
+// =============================================================================
+
+/// Self-description for frontends that integrate several game canisters
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug)]
+pub struct GameInfo {
+    pub name: String,
+    pub min_bet: u64,
+    /// Largest bet the pool currently accepts at the game's top multiplier
+    pub max_bet: u64,
+    pub token_symbol: String,
+    pub house_edge_bps: u64,
+    pub modes: Vec<String>,
+}
+
 // =============================================================================
 // ICRC-2 TYPES
 // =============================================================================
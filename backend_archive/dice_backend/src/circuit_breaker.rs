@@ -0,0 +1,281 @@
+//! Pool protection circuit breaker.
+//!
+//! The per-bet house limit caps a single payout, not a run of them. This
+//! tracks the house's net loss (payouts minus bets) over a rolling window and
+//! refuses new bets once it exceeds `threshold_bps` of the pool reserve, until
+//! enough of the window ages out. Disabled by default so an upgrade never
+//! changes live betting; an admin opts in via the config. Settlements live in
+//! a heap thread_local (a reset on upgrade is harmless); the config is stable.
+
+use candid::{CandidType, Deserialize};
+use ic_stable_structures::{StableCell, Storable, memory_manager::MemoryId, storable::Bound};
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use crate::{MEMORY_MANAGER, Memory};
+use crate::defi_accounting::memory_ids::CIRCUIT_BREAKER_CONFIG_MEMORY_ID;
+
+const NANOS_PER_SEC: u64 = 1_000_000_000;
+const BPS_SCALE: u64 = 10_000;
+
+pub const MIN_WINDOW_SECS: u64 = 60;
+pub const MAX_WINDOW_SECS: u64 = 86_400;
+
+pub const ENGAGED_ERROR: &str = "Pool protection engaged, try again shortly";
+
+// =============================================================================
+// TYPES
+// =============================================================================
+
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq)]
+pub struct CircuitBreakerConfig {
+    /// Bets are only ever refused while enabled
+    #[serde(default)]
+    pub enabled: bool,
+    /// Length of the rolling window
+    pub window_secs: u64,
+    /// Net house loss over the window, in basis points of the pool reserve,
+    /// that trips the breaker
+    pub threshold_bps: u64,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            window_secs: 3_600,
+            threshold_bps: 1_000,
+        }
+    }
+}
+
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq)]
+pub struct CircuitBreakerStatus {
+    pub enabled: bool,
+    pub window_secs: u64,
+    pub threshold_bps: u64,
+    /// Net house loss within the current window (0 while the house is ahead)
+    pub net_house_loss: u64,
+    /// Loss at which the breaker trips, from the current pool reserve
+    pub loss_limit: u64,
+    pub engaged: bool,
+}
+
+impl Storable for CircuitBreakerConfig {
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Owned(candid::encode_one(self).expect("Failed to encode CircuitBreakerConfig"))
+    }
+
+    fn into_bytes(self) -> Vec<u8> {
+        self.to_bytes().into_owned()
+    }
+
+    fn from_bytes(bytes: Cow<'_, [u8]>) -> Self {
+        candid::decode_one(&bytes).expect("Failed to decode CircuitBreakerConfig")
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+/// Settlements inside the window and their running sum
+#[derive(Default)]
+struct LossWindow {
+    /// (settled_at_ns, payout - bet)
+    entries: VecDeque<(u64, i128)>,
+    net_loss: i128,
+}
+
+impl LossWindow {
+    fn prune(&mut self, now_ns: u64, window_ns: u64) {
+        while let Some(&(at, delta)) = self.entries.front() {
+            if now_ns.saturating_sub(at) < window_ns {
+                break;
+            }
+            self.net_loss -= delta;
+            self.entries.pop_front();
+        }
+    }
+}
+
+// =============================================================================
+// STORAGE
+// =============================================================================
+
+thread_local! {
+    static CONFIG: RefCell<StableCell<CircuitBreakerConfig, Memory>> = RefCell::new(
+        StableCell::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(CIRCUIT_BREAKER_CONFIG_MEMORY_ID))),
+            CircuitBreakerConfig::default()
+        )
+    );
+
+    static LOSS_WINDOW: RefCell<LossWindow> = RefCell::new(LossWindow::default());
+}
+
+// =============================================================================
+// CONFIG
+// =============================================================================
+
+pub fn get_config() -> CircuitBreakerConfig {
+    CONFIG.with(|c| c.borrow().get().clone())
+}
+
+pub fn set_config(config: CircuitBreakerConfig) -> Result<(), String> {
+    if !(MIN_WINDOW_SECS..=MAX_WINDOW_SECS).contains(&config.window_secs) {
+        return Err(format!("Invalid config: window_secs must be {}-{}", MIN_WINDOW_SECS, MAX_WINDOW_SECS));
+    }
+    if config.threshold_bps == 0 || config.threshold_bps > BPS_SCALE {
+        return Err(format!("Invalid config: threshold_bps must be 1-{}", BPS_SCALE));
+    }
+    CONFIG.with(|c| c.borrow_mut().set(config));
+    Ok(())
+}
+
+// =============================================================================
+// BREAKER LOGIC
+// =============================================================================
+
+fn window_ns() -> u64 {
+    get_config().window_secs.saturating_mul(NANOS_PER_SEC)
+}
+
+/// Net house loss within the window ending at `now_ns`
+fn window_loss(now_ns: u64) -> u64 {
+    let window_ns = window_ns();
+    LOSS_WINDOW.with(|w| {
+        let mut w = w.borrow_mut();
+        w.prune(now_ns, window_ns);
+        w.net_loss.clamp(0, u64::MAX as i128) as u64
+    })
+}
+
+fn loss_limit(pool_reserve: u64) -> u64 {
+    (pool_reserve as u128 * get_config().threshold_bps as u128 / BPS_SCALE as u128) as u64
+}
+
+/// Reject new bets while enabled and the window's net loss exceeds the limit.
+pub fn check(pool_reserve: u64, now_ns: u64) -> Result<(), String> {
+    if get_config().enabled && window_loss(now_ns) > loss_limit(pool_reserve) {
+        return Err(ENGAGED_ERROR.to_string());
+    }
+    Ok(())
+}
+
+/// Record a settled bet (or multi-dice round). Pruned here as well as on
+/// check, so the window stays bounded while the breaker is disabled.
+pub fn record_settlement(total_bet: u64, total_payout: u64, now_ns: u64) {
+    let delta = total_payout as i128 - total_bet as i128;
+    let window_ns = window_ns();
+    LOSS_WINDOW.with(|w| {
+        let mut w = w.borrow_mut();
+        w.prune(now_ns, window_ns);
+        w.entries.push_back((now_ns, delta));
+        w.net_loss += delta;
+    });
+}
+
+/// Clear the window (run from the daily stats timer).
+pub fn reset() {
+    LOSS_WINDOW.with(|w| *w.borrow_mut() = LossWindow::default());
+}
+
+pub fn status(pool_reserve: u64, now_ns: u64) -> CircuitBreakerStatus {
+    let config = get_config();
+    let net_house_loss = window_loss(now_ns);
+    let loss_limit = loss_limit(pool_reserve);
+    CircuitBreakerStatus {
+        enabled: config.enabled,
+        window_secs: config.window_secs,
+        threshold_bps: config.threshold_bps,
+        net_house_loss,
+        loss_limit,
+        engaged: config.enabled && net_house_loss > loss_limit,
+    }
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const T0: u64 = 1_000 * NANOS_PER_SEC;
+    const RESERVE: u64 = 1_000_000;
+
+    fn configure(window_secs: u64, threshold_bps: u64) {
+        set_config(CircuitBreakerConfig { enabled: true, window_secs, threshold_bps }).expect("valid config");
+    }
+
+    #[test]
+    fn test_breaker_is_off_by_default() {
+        assert!(!get_config().enabled);
+        record_settlement(0, 500_000, T0);
+        assert_eq!(check(RESERVE, T0), Ok(()));
+
+        let s = status(RESERVE, T0);
+        assert_eq!((s.enabled, s.net_house_loss, s.engaged), (false, 500_000, false));
+
+        // Opting in applies to losses already in the window
+        configure(600, 1_000);
+        assert_eq!(check(RESERVE, T0), Err(ENGAGED_ERROR.to_string()));
+    }
+
+    #[test]
+    fn test_losses_over_threshold_engage_breaker() {
+        configure(600, 1_000);
+
+        // House loses 90k, then wins 10k back: 80k < 100k limit
+        record_settlement(10_000, 100_000, T0);
+        record_settlement(10_000, 0, T0);
+        assert_eq!(check(RESERVE, T0), Ok(()));
+
+        // Another 30k loss pushes it to 110k
+        record_settlement(10_000, 40_000, T0 + NANOS_PER_SEC);
+        assert_eq!(check(RESERVE, T0 + NANOS_PER_SEC), Err(ENGAGED_ERROR.to_string()));
+
+        let s = status(RESERVE, T0 + NANOS_PER_SEC);
+        assert_eq!((s.net_house_loss, s.loss_limit, s.engaged), (110_000, 100_000, true));
+    }
+
+    #[test]
+    fn test_losses_age_out_of_window() {
+        configure(600, 1_000);
+        record_settlement(0, 150_000, T0);
+        assert!(check(RESERVE, T0).is_err());
+
+        let later = T0 + 600 * NANOS_PER_SEC;
+        assert_eq!(check(RESERVE, later), Ok(()));
+        assert_eq!(status(RESERVE, later).net_house_loss, 0);
+    }
+
+    #[test]
+    fn test_window_stays_bounded_while_disabled() {
+        // Default window is an hour; settle one bet a minute for a day
+        for minute in 0..1_440 {
+            record_settlement(10_000, 0, T0 + minute * 60 * NANOS_PER_SEC);
+        }
+        assert_eq!(LOSS_WINDOW.with(|w| w.borrow().entries.len()), 60);
+    }
+
+    #[test]
+    fn test_reset_clears_window() {
+        configure(600, 1_000);
+        record_settlement(0, 150_000, T0);
+        reset();
+        assert_eq!(check(RESERVE, T0), Ok(()));
+    }
+
+    #[test]
+    fn test_config_validation() {
+        let short_window = CircuitBreakerConfig { window_secs: MIN_WINDOW_SECS - 1, ..CircuitBreakerConfig::default() };
+        assert!(set_config(short_window).is_err());
+
+        let zero_threshold = CircuitBreakerConfig { threshold_bps: 0, ..CircuitBreakerConfig::default() };
+        assert!(set_config(zero_threshold).is_err());
+
+        let over_pool = CircuitBreakerConfig { threshold_bps: BPS_SCALE + 1, ..CircuitBreakerConfig::default() };
+        assert!(set_config(over_pool).is_err());
+    }
+}
@@ -2,6 +2,7 @@ use candid::{Principal, Nat};
 use ic_stable_structures::memory_manager::MemoryId;
 use ic_stable_structures::{StableBTreeMap, StableCell};
 use std::cell::RefCell;
+use std::collections::BTreeSet;
 use std::time::Duration;
 // Note: This module now uses ckUSDT (ICRC-2), not ICP ledger
 // ckUSDT types defined in types.rs
@@ -9,13 +10,14 @@ use crate::types::{Account, TransferFromArgs, TransferFromError, TransferArg, Tr
 
 use crate::{MEMORY_MANAGER, Memory};
 use super::liquidity_pool;
-use super::types::{PendingWithdrawal, WithdrawalType, AuditEntry, AuditEvent};
+use super::types::{PendingWithdrawal, WithdrawalType, AuditEntry, AuditEvent, DepositNonce};
 
 use super::memory_ids::{
     USER_BALANCES_MEMORY_ID,
     PENDING_WITHDRAWALS_MEMORY_ID,
     AUDIT_LOG_MAP_MEMORY_ID,
     AUDIT_LOG_COUNTER_MEMORY_ID,
+    DEPOSIT_NONCES_MEMORY_ID,
 };
 
 // Constants
@@ -25,6 +27,12 @@ const MAX_AUDIT_ENTRIES: u64 = 1000; // Retention limit
 /// Minimum balance before triggering automatic weekly withdrawal to parent canister.
 /// Set to 10 USDT to minimize gas costs while ensuring timely fee collection.
 const PARENT_AUTO_WITHDRAW_THRESHOLD: u64 = 10_000_000; // 10 USDT
+/// How long a credited deposit nonce is remembered (replays of it are no-ops).
+const DEPOSIT_NONCE_WINDOW_NS: u64 = 3_600_000_000_000; // 1 hour
+/// How long an unresolved nonce keeps its created_at_time. Just under the
+/// ledger's 24h dedup window, so a retry either lands once or is reported as
+/// a duplicate of the attempt that already landed.
+const UNRESOLVED_NONCE_WINDOW_NS: u64 = 23 * 3_600_000_000_000; // 23 hours
 
 thread_local! {
     static USER_BALANCES_STABLE: RefCell<StableBTreeMap<Principal, u64, Memory>> = RefCell::new(
@@ -55,11 +63,36 @@ thread_local! {
         )
     );
 
+    // Recent nonce-keyed deposits, pruned per principal once their window passes
+    static DEPOSIT_NONCES: RefCell<StableBTreeMap<(Principal, u64), DepositNonce, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(DEPOSIT_NONCES_MEMORY_ID)))
+        )
+    );
+
+    // Nonce-keyed deposits awaiting the ledger (heap: no call survives an upgrade)
+    static DEPOSITS_IN_FLIGHT: RefCell<BTreeSet<(Principal, u64)>> = const { RefCell::new(BTreeSet::new()) };
+
     static CACHED_CANISTER_BALANCE: RefCell<u64> = const { RefCell::new(0) };
     static PARENT_TIMER: RefCell<Option<ic_cdk_timers::TimerId>> = const { RefCell::new(None) };
     static RECONCILIATION_TIMER: RefCell<Option<ic_cdk_timers::TimerId>> = const { RefCell::new(None) };
 }
 
+pub(crate) enum DepositAttempt {
+    /// Already credited or still in flight: report the balance, charge nothing
+    Replay,
+    /// Call the ledger with this created_at_time
+    Send { created_at: u64 },
+}
+
+pub(crate) enum NonceOutcome {
+    Credited,
+    /// Ledger definitively refused the transfer; the nonce may be retried fresh
+    Rejected,
+    /// Call failed without an answer; a retry resends the same created_at_time
+    Unknown,
+}
+
 pub(crate) enum TransferResult {
     Success(u64),
     DefiniteError(String),
@@ -145,13 +178,98 @@ fn calculate_total_deposits() -> u64 {
 // DEPOSIT FUNCTION (ICRC-2)
 // =============================================================================
 
+/// Start a nonce-keyed deposit. A retry of an unresolved attempt reuses its
+/// created_at so the ledger deduplicates it; reusing a nonce for a different
+/// amount within the window is an error.
+pub(crate) fn begin_nonce_deposit(user: Principal, nonce: u64, amount: u64, now: u64) -> Result<DepositAttempt, String> {
+    prune_deposit_nonces(user, now);
+
+    let key = (user, nonce);
+    if DEPOSITS_IN_FLIGHT.with(|f| f.borrow().contains(&key)) {
+        return Ok(DepositAttempt::Replay);
+    }
+
+    let created_at = match DEPOSIT_NONCES.with(|n| n.borrow().get(&key)) {
+        Some(record) if record.credited => return Ok(DepositAttempt::Replay),
+        Some(record) if record.amount != amount => {
+            return Err("Deposit nonce already used for a different amount".to_string());
+        }
+        Some(record) => record.created_at,
+        None => {
+            DEPOSIT_NONCES.with(|n| n.borrow_mut().insert(key, DepositNonce { amount, created_at: now, credited: false }));
+            now
+        }
+    };
+
+    DEPOSITS_IN_FLIGHT.with(|f| f.borrow_mut().insert(key));
+    Ok(DepositAttempt::Send { created_at })
+}
+
+pub(crate) fn finish_nonce_deposit(user: Principal, nonce: u64, outcome: NonceOutcome) {
+    let key = (user, nonce);
+    DEPOSITS_IN_FLIGHT.with(|f| f.borrow_mut().remove(&key));
+    DEPOSIT_NONCES.with(|n| {
+        let mut nonces = n.borrow_mut();
+        match outcome {
+            NonceOutcome::Credited => {
+                if let Some(mut record) = nonces.get(&key) {
+                    record.credited = true;
+                    nonces.insert(key, record);
+                }
+            }
+            NonceOutcome::Rejected => {
+                nonces.remove(&key);
+            }
+            NonceOutcome::Unknown => {}
+        }
+    });
+}
+
+/// Forget the user's credited nonces older than DEPOSIT_NONCE_WINDOW_NS and
+/// unresolved ones older than UNRESOLVED_NONCE_WINDOW_NS
+fn prune_deposit_nonces(user: Principal, now: u64) {
+    DEPOSIT_NONCES.with(|n| {
+        let mut nonces = n.borrow_mut();
+        let expired: Vec<(Principal, u64)> = nonces
+            .range((user, 0)..=(user, u64::MAX))
+            .filter(|entry| {
+                let record = entry.value();
+                let window = if record.credited { DEPOSIT_NONCE_WINDOW_NS } else { UNRESOLVED_NONCE_WINDOW_NS };
+                now.saturating_sub(record.created_at) >= window
+            })
+            .map(|entry| *entry.key())
+            .filter(|key| !DEPOSITS_IN_FLIGHT.with(|f| f.borrow().contains(key)))
+            .collect();
+        for key in expired {
+            nonces.remove(&key);
+        }
+    });
+}
+
+/// Deposit via ICRC-2 transfer_from.
+/// With `client_nonce`, retries are safe: a replay of a credited (or in-flight)
+/// nonce returns the current balance unchanged instead of charging again.
 #[allow(deprecated)]
-pub async fn deposit(amount: u64) -> Result<u64, String> {
+pub async fn deposit(amount: u64, client_nonce: Option<u64>) -> Result<u64, String> {
     if amount < MIN_DEPOSIT {
         return Err(format!("Minimum deposit is {} USDT", MIN_DEPOSIT / 1_000_000));
     }
 
     let caller = ic_cdk::api::msg_caller();
+
+    let created_at_time = match client_nonce {
+        None => None,
+        Some(nonce) => match begin_nonce_deposit(caller, nonce, amount, ic_cdk::api::time())? {
+            DepositAttempt::Replay => return Ok(get_balance_internal(caller)),
+            DepositAttempt::Send { created_at } => Some(created_at),
+        },
+    };
+    let finish = |outcome: NonceOutcome| {
+        if let Some(nonce) = client_nonce {
+            finish_nonce_deposit(caller, nonce, outcome);
+        }
+    };
+
     let ck_usdt_principal = Principal::from_text(CKUSDT_CANISTER_ID).expect("Invalid principal constant");
 
     let args = TransferFromArgs {
@@ -164,13 +282,24 @@ pub async fn deposit(amount: u64) -> Result<u64, String> {
         // If the ledger creates a surplus from this, it is Protocol Profit (safe).
         fee: Some(Nat::from(CKUSDT_TRANSFER_FEE)), 
         memo: None,
-        created_at_time: None,
+        created_at_time,
     };
 
     let (result,): (Result<Nat, TransferFromError>,) =
-        ic_cdk::api::call::call(ck_usdt_principal, "icrc2_transfer_from", (args,))
-        .await
-        .map_err(|(code, msg)| format!("Call failed: {:?} {}", code, msg))?;
+        match ic_cdk::api::call::call(ck_usdt_principal, "icrc2_transfer_from", (args,)).await {
+            Ok(response) => response,
+            Err((code, msg)) => {
+                finish(NonceOutcome::Unknown);
+                return Err(format!("Call failed: {:?} {}", code, msg));
+            }
+        };
+
+    // A duplicate means an earlier attempt with this nonce landed but was
+    // never credited (its call failed without an answer): credit it now
+    let result = match result {
+        Err(TransferFromError::Duplicate { duplicate_of }) if client_nonce.is_some() => Ok(duplicate_of),
+        other => other,
+    };
 
     match result {
         Ok(block_index) => {
@@ -194,10 +323,15 @@ pub async fn deposit(amount: u64) -> Result<u64, String> {
             // Update cached canister balance (canister received `amount`)
             increment_cached_balance(amount);
 
+            finish(NonceOutcome::Credited);
+
             ic_cdk::println!("Deposit successful: {} deposited {} decimals at block {}", caller, amount, block_index);
             Ok(new_balance)
         }
-        Err(e) => Err(format!("Transfer failed: {:?}", e)),
+        Err(e) => {
+            finish(NonceOutcome::Rejected);
+            Err(format!("Transfer failed: {:?}", e))
+        }
     }
 }
 
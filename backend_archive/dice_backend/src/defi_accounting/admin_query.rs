@@ -8,7 +8,7 @@ const WASM_PAGE_SIZE_BYTES: u64 = 65536;
 // const MAX_PAGINATION_LIMIT: u64 = 100; // Historical limit - removed to allow unlimited admin queries
 const REASONABLE_MAX_LIMIT: usize = 10_000; // Safety net to prevent abuse
 
-fn require_admin() -> Result<(), String> {
+pub(crate) fn require_admin() -> Result<(), String> {
     let caller = ic_cdk::api::msg_caller();
     let admin = Principal::from_text(ADMIN_PRINCIPAL)
         .map_err(|e| format!("Invalid admin principal: {:?}", e))?;
@@ -152,3 +152,14 @@ pub fn get_audit_log_count() -> Result<u64, String> {
     require_admin()?;
     Ok(accounting::get_audit_count())
 }
+
+/// Distribute `amount` from the house treasury to LPs via the pool reserve.
+pub fn distribute_rewards(amount: u64) -> Result<RewardDistribution, String> {
+    require_admin()?;
+    let distribution = liquidity_pool::distribute_rewards_internal(amount, ic_cdk::api::time())?;
+    accounting::log_audit(AuditEvent::RewardsDistributed {
+        amount,
+        new_reserve: distribution.reserve_after,
+    });
+    Ok(distribution)
+}
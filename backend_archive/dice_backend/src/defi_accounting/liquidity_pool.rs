@@ -7,7 +7,8 @@ use num_traits::ToPrimitive;
 
 use crate::types::{Account, TransferFromArgs, TransferFromError, CKUSDT_CANISTER_ID, CKUSDT_TRANSFER_FEE};
 use super::accounting;
-use super::memory_ids::{LP_SHARES_MEMORY_ID, POOL_STATE_MEMORY_ID};
+use super::memory_ids::{LP_SHARES_MEMORY_ID, POOL_STATE_MEMORY_ID, LAST_DISTRIBUTION_MEMORY_ID};
+use super::types::RewardDistribution;
 
 // Constants
 
@@ -102,6 +103,14 @@ thread_local! {
             }
         ))
     };
+
+    // Most recent explicit LP reward distribution
+    static LAST_DISTRIBUTION: RefCell<StableCell<Option<RewardDistribution>, VirtualMemory<DefaultMemoryImpl>>> = {
+        RefCell::new(StableCell::init(
+            crate::MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(LAST_DISTRIBUTION_MEMORY_ID))),
+            None
+        ))
+    };
 }
 
 // Types
@@ -285,25 +294,9 @@ pub async fn deposit_liquidity(amount: u64, min_shares_expected: Option<Nat>) ->
 // 3. Transfer the Fee (Best Effort). If this fails, we DO NOT rollback.
 //    The fee remains in the canister as a protocol buffer.
 //    This ensures the Reserve is always solvent (Reserve <= Balance).
-async fn withdraw_liquidity(shares_to_burn: Nat) -> Result<u64, String> {
-    let caller = ic_cdk::api::msg_caller();
-
-    // Prevent anonymous principal from withdrawing burned shares
-    if caller == Principal::anonymous() {
-        return Err("Anonymous principal cannot withdraw".to_string());
-    }
-
-    // Validate shares
-    if shares_to_burn == 0u64 {
-        return Err("Cannot withdraw zero shares".to_string());
-    }
-
-    let user_shares = LP_SHARES.with(|s| s.borrow().get(&caller).map_or(Nat::from(0u64), |sn| sn.0));
-    if user_shares < shares_to_burn {
-        return Err("Insufficient shares".to_string());
-    }
-
-    // Calculate payout
+/// Gross payout (as Nat and u64) and fee for burning `shares_to_burn` at the
+/// current share price. Enforces MIN_WITHDRAWAL.
+fn calculate_withdrawal(shares_to_burn: &Nat) -> Result<(Nat, u64, u64), String> {
     let payout_nat = POOL_STATE.with(|state| {
         let pool_state = state.borrow().get().clone();
         let current_reserve = pool_state.reserve;
@@ -313,10 +306,9 @@ async fn withdraw_liquidity(shares_to_burn: Nat) -> Result<u64, String> {
             return Err("No shares in circulation".to_string());
         }
 
-        // payout = (shares_to_burn * current_reserve) / total_shares
         // payout = (shares_to_burn * current_reserve) / total_shares
         let numerator = shares_to_burn.clone() * current_reserve.clone();
-        // SAFETY: total_shares checked for zero above (line 207)
+        // SAFETY: total_shares checked for zero above
         let payout = numerator / total_shares;
 
         // Check reserve sufficiency (read-only check)
@@ -335,6 +327,30 @@ async fn withdraw_liquidity(shares_to_burn: Nat) -> Result<u64, String> {
 
     // Calculate fee (1% using basis points for precision)
     let fee_amount = (payout_u64 * LP_WITHDRAWAL_FEE_BPS) / 10_000;
+
+    Ok((payout_nat, payout_u64, fee_amount))
+}
+
+async fn withdraw_liquidity(shares_to_burn: Nat) -> Result<u64, String> {
+    let caller = ic_cdk::api::msg_caller();
+
+    // Prevent anonymous principal from withdrawing burned shares
+    if caller == Principal::anonymous() {
+        return Err("Anonymous principal cannot withdraw".to_string());
+    }
+
+    // Validate shares
+    if shares_to_burn == 0u64 {
+        return Err("Cannot withdraw zero shares".to_string());
+    }
+
+    let user_shares = LP_SHARES.with(|s| s.borrow().get(&caller).map_or(Nat::from(0u64), |sn| sn.0));
+    if user_shares < shares_to_burn {
+        return Err("Insufficient shares".to_string());
+    }
+
+    // Calculate payout and fee
+    let (payout_nat, payout_u64, fee_amount) = calculate_withdrawal(&shares_to_burn)?;
     let lp_amount = payout_u64 - fee_amount;
 
     // Update shares BEFORE transfer (reentrancy protection)
@@ -427,6 +443,12 @@ pub async fn withdraw_all_liquidity() -> Result<u64, String> {
     withdraw_liquidity(shares).await
 }
 
+/// Partial exit: burn `shares` of the caller's position and keep the rest.
+/// Same validation, fee and pending-withdrawal rollback as withdraw_all_liquidity.
+pub async fn withdraw_liquidity_shares(shares: Nat) -> Result<u64, String> {
+    withdraw_liquidity(shares).await
+}
+
 // Query functions
 
 pub(crate) fn get_lp_position_internal(user: Principal) -> LPPosition {
@@ -535,6 +557,51 @@ pub fn can_accept_bets() -> bool {
     pool_reserve >= MIN_OPERATING_BALANCE
 }
 
+// =============================================================================
+// REWARD DISTRIBUTION
+// =============================================================================
+
+/// Move `amount` from the house treasury (the parent's accumulated fee
+/// balance) into the pool reserve, raising the share price for all LPs.
+///
+/// The funds already sit in the canister, so total obligations are unchanged.
+/// Audit logging is left to the caller.
+pub(crate) fn distribute_rewards_internal(amount: u64, now: u64) -> Result<RewardDistribution, String> {
+    if amount == 0 {
+        return Err("Reward amount must be greater than 0".to_string());
+    }
+
+    let stats_before = get_pool_stats_internal();
+    if stats_before.total_liquidity_providers == 0 {
+        return Err("No liquidity providers to reward".to_string());
+    }
+
+    accounting::try_deduct_balance(get_parent_principal(), amount)
+        .map_err(|e| format!("Treasury cannot fund rewards: {}", e))?;
+    add_to_reserve(amount);
+
+    let stats_after = get_pool_stats_internal();
+    let distribution = RewardDistribution {
+        amount,
+        timestamp: now,
+        reserve_after: get_pool_reserve(),
+        share_price_before: stats_before.share_price,
+        share_price_after: stats_after.share_price,
+    };
+
+    LAST_DISTRIBUTION.with(|d| d.borrow_mut().set(Some(distribution.clone())));
+    Ok(distribution)
+}
+
+pub fn get_last_distribution() -> Option<RewardDistribution> {
+    LAST_DISTRIBUTION.with(|d| d.borrow().get().clone())
+}
+
+#[cfg(test)]
+pub(crate) fn set_lp_shares_for_test(user: Principal, shares: Nat) {
+    LP_SHARES.with(|s| s.borrow_mut().insert(user, StorableNat(shares)));
+}
+
 // Game integration (internal use only - called by game logic)
 
 pub(crate) fn update_pool_on_win(payout: u64) {
@@ -687,6 +754,19 @@ pub fn calculate_shares_preview(amount: u64) -> Result<Nat, String> {
     calculate_shares_for_deposit(&Nat::from(amount))
 }
 
+/// Preview burning `shares`: (gross payout, withdrawal fee). The LP receives
+/// gross - fee. Query-time estimate; the share price moves with every bet.
+pub fn calculate_withdrawal_preview(shares: Nat) -> Result<(u64, u64), String> {
+    if shares == 0u64 {
+        return Err("Cannot withdraw zero shares".to_string());
+    }
+    if shares > calculate_total_supply() {
+        return Err("Insufficient shares".to_string());
+    }
+    let (_, payout, fee) = calculate_withdrawal(&shares)?;
+    Ok((payout, fee))
+}
+
 // =============================================================================
 // ADMIN QUERY HELPERS
 // =============================================================================
@@ -4,18 +4,25 @@
 //! Run `cargo test` to verify no collisions exist.
 //!
 //! Allocation strategy:
-//! - 0-9: Core game state (seed, nonce)
-//! - 10-19: User accounting (balances, LP shares, pool state)
+//! - 0-9: Core game state (server seed, per-player nonces, loss streaks, circuit breaker)
+//! - 10-19: User accounting (balances, LP shares, pool state, deposit nonces)
 //! - 20-29: Withdrawal & audit (pending, audit log)
 //! - 30-39: Statistics (snapshots, accumulator)
 
 // Core game state (0-9)
-// DEPRECATED/RETIRED: 1 (Seed State), 2 (Nonce Counter) - Moved to per-game VRF (no persistence)
+// DEPRECATED/RETIRED: 1 (Seed State), 2 (Nonce Counter) - old layout, superseded by 5 and 6
+pub const STREAK_CONFIG_MEMORY_ID: u8 = 3;
+pub const PLAYER_STREAKS_MEMORY_ID: u8 = 4;
+pub const SERVER_SEED_MEMORY_ID: u8 = 5;
+pub const PLAYER_NONCES_MEMORY_ID: u8 = 6;
+pub const CIRCUIT_BREAKER_CONFIG_MEMORY_ID: u8 = 7;
 
 // User accounting (10-19)
 pub const USER_BALANCES_MEMORY_ID: u8 = 10;
 pub const LP_SHARES_MEMORY_ID: u8 = 11;
 pub const POOL_STATE_MEMORY_ID: u8 = 13;
+pub const LAST_DISTRIBUTION_MEMORY_ID: u8 = 14;
+pub const DEPOSIT_NONCES_MEMORY_ID: u8 = 15;
 
 // Withdrawals & audit (20-29)
 pub const PENDING_WITHDRAWALS_MEMORY_ID: u8 = 20;
@@ -35,9 +42,16 @@ mod tests {
     #[test]
     fn memory_ids_are_unique() {
         let ids = [
+            STREAK_CONFIG_MEMORY_ID,
+            PLAYER_STREAKS_MEMORY_ID,
+            SERVER_SEED_MEMORY_ID,
+            PLAYER_NONCES_MEMORY_ID,
+            CIRCUIT_BREAKER_CONFIG_MEMORY_ID,
             USER_BALANCES_MEMORY_ID,
             LP_SHARES_MEMORY_ID,
             POOL_STATE_MEMORY_ID,
+            LAST_DISTRIBUTION_MEMORY_ID,
+            DEPOSIT_NONCES_MEMORY_ID,
             PENDING_WITHDRAWALS_MEMORY_ID,
             AUDIT_LOG_MAP_MEMORY_ID,
             AUDIT_LOG_COUNTER_MEMORY_ID,
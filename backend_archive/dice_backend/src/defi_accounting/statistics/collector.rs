@@ -125,9 +125,11 @@ pub fn take_daily_snapshot() {
 
 /// Start backup timer (runs daily in case no bets trigger snapshot)
 /// This ensures we get a snapshot even on days with no activity
+/// Also clears the circuit breaker's loss window
 pub fn start_stats_timer() {
     ic_cdk_timers::set_timer_interval(Duration::from_secs(86_400), || async {
         take_daily_snapshot();
+        crate::circuit_breaker::reset();
     });
 }
 
@@ -1,3 +1,6 @@
 pub mod test_serialization;
 pub mod test_slippage_audit;
+pub mod test_reward_distribution;
+pub mod test_partial_withdrawal;
+pub mod test_deposit_nonce;
 mod stress_tests;
\ No newline at end of file
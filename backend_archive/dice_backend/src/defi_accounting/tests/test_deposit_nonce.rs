@@ -0,0 +1,95 @@
+// Test: Idempotent Nonce-Keyed Deposits
+//
+// Verifies the bookkeeping that makes deposit(amount, Some(nonce)) safe to retry:
+// 1. A credited nonce is a replay (no second ledger call)
+// 2. A nonce still in flight is a replay
+// 3. An unresolved attempt is resent with the same created_at_time
+// 4. A rejected attempt frees the nonce; expired nonces are forgotten
+// 5. An unresolved attempt keeps its timestamp for most of the ledger's 24h window
+//
+// The ledger call itself needs a canister runtime, so only the nonce state
+// machine around it runs here.
+
+use candid::Principal;
+use crate::defi_accounting::accounting::{begin_nonce_deposit, finish_nonce_deposit, DepositAttempt, NonceOutcome};
+
+const T0: u64 = 1_700_000_000_000_000_000;
+const HOUR: u64 = 3_600_000_000_000;
+
+fn user(id: u8) -> Principal {
+    Principal::from_slice(&[id; 29])
+}
+
+fn sent_at(attempt: Result<DepositAttempt, String>) -> Option<u64> {
+    match attempt {
+        Ok(DepositAttempt::Send { created_at }) => Some(created_at),
+        _ => None,
+    }
+}
+
+fn is_replay(attempt: Result<DepositAttempt, String>) -> bool {
+    matches!(attempt, Ok(DepositAttempt::Replay))
+}
+
+#[test]
+fn test_credited_and_in_flight_nonces_replay() {
+    let u = user(1);
+
+    assert_eq!(sent_at(begin_nonce_deposit(u, 7, 5_000_000, T0)), Some(T0));
+    // Retry while the first call is still awaiting the ledger
+    assert!(is_replay(begin_nonce_deposit(u, 7, 5_000_000, T0 + 1)));
+
+    finish_nonce_deposit(u, 7, NonceOutcome::Credited);
+    assert!(is_replay(begin_nonce_deposit(u, 7, 5_000_000, T0 + 2)));
+
+    // Other nonces and other users are independent
+    assert_eq!(sent_at(begin_nonce_deposit(u, 8, 5_000_000, T0 + 3)), Some(T0 + 3));
+    assert_eq!(sent_at(begin_nonce_deposit(user(2), 7, 5_000_000, T0 + 4)), Some(T0 + 4));
+}
+
+#[test]
+fn test_unresolved_attempt_resends_same_timestamp() {
+    let u = user(3);
+
+    assert_eq!(sent_at(begin_nonce_deposit(u, 1, 2_000_000, T0)), Some(T0));
+    finish_nonce_deposit(u, 1, NonceOutcome::Unknown);
+
+    // Same created_at_time, so the ledger deduplicates if the first one landed
+    assert_eq!(sent_at(begin_nonce_deposit(u, 1, 2_000_000, T0 + 500)), Some(T0));
+    finish_nonce_deposit(u, 1, NonceOutcome::Unknown);
+
+    // The nonce can't be reused for a different amount meanwhile
+    assert!(begin_nonce_deposit(u, 1, 3_000_000, T0 + 600).is_err());
+}
+
+#[test]
+fn test_unresolved_attempt_survives_past_the_hour() {
+    let u = user(5);
+
+    assert_eq!(sent_at(begin_nonce_deposit(u, 1, 2_000_000, T0)), Some(T0));
+    finish_nonce_deposit(u, 1, NonceOutcome::Unknown);
+
+    // Hours later the retry still resends T0, so a landed first transfer is
+    // reported as a duplicate and credited instead of charged twice
+    assert_eq!(sent_at(begin_nonce_deposit(u, 1, 2_000_000, T0 + 5 * HOUR)), Some(T0));
+    finish_nonce_deposit(u, 1, NonceOutcome::Unknown);
+    assert_eq!(sent_at(begin_nonce_deposit(u, 1, 2_000_000, T0 + 22 * HOUR)), Some(T0));
+    finish_nonce_deposit(u, 1, NonceOutcome::Unknown);
+
+    // Before the ledger would refuse T0 as too old, the nonce starts fresh
+    assert_eq!(sent_at(begin_nonce_deposit(u, 1, 2_000_000, T0 + 23 * HOUR)), Some(T0 + 23 * HOUR));
+}
+
+#[test]
+fn test_rejected_and_expired_nonces_start_fresh() {
+    let u = user(4);
+
+    assert_eq!(sent_at(begin_nonce_deposit(u, 1, 2_000_000, T0)), Some(T0));
+    finish_nonce_deposit(u, 1, NonceOutcome::Rejected);
+    assert_eq!(sent_at(begin_nonce_deposit(u, 1, 2_000_000, T0 + 10)), Some(T0 + 10));
+    finish_nonce_deposit(u, 1, NonceOutcome::Credited);
+
+    // Outside the window the nonce is forgotten and counts as a new deposit
+    assert!(is_replay(begin_nonce_deposit(u, 1, 2_000_000, T0 + HOUR)));
+    assert_eq!(sent_at(begin_nonce_deposit(u, 1, 2_000_000, T0 + 10 + HOUR)), Some(T0 + 10 + HOUR));
+}
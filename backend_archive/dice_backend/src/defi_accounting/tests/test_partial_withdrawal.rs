@@ -0,0 +1,46 @@
+// Test: Partial LP Withdrawal Preview
+//
+// Verifies that calculate_withdrawal_preview() prices a partial exit with the
+// same share math, 1% fee and minimum as the withdrawal itself:
+// 1. Gross payout is proportional to the shares burned
+// 2. The fee is 1% of the gross payout
+// 3. Zero, oversized and dust withdrawals are rejected
+//
+// The withdrawal itself needs a caller and ledger, so only the preview runs here.
+
+use candid::{Nat, Principal};
+use crate::defi_accounting::liquidity_pool;
+
+const USDT: u64 = 1_000_000;
+
+fn lp(id: u8) -> Principal {
+    Principal::from_slice(&[id; 29])
+}
+
+#[test]
+fn test_preview_prices_partial_exit() {
+    // One LP owns the whole 100 USDT pool
+    liquidity_pool::set_lp_shares_for_test(lp(1), Nat::from(4_000_000u64));
+    liquidity_pool::add_to_reserve(100 * USDT);
+
+    // A quarter of the shares redeems a quarter of the reserve
+    let (gross, fee) = liquidity_pool::calculate_withdrawal_preview(Nat::from(1_000_000u64)).unwrap();
+    assert_eq!(gross, 25 * USDT);
+    assert_eq!(fee, 25 * USDT / 100);
+
+    // Previewing does not touch the pool
+    assert_eq!(liquidity_pool::get_pool_reserve(), 100 * USDT);
+}
+
+#[test]
+fn test_preview_rejects_invalid_amounts() {
+    liquidity_pool::set_lp_shares_for_test(lp(2), Nat::from(1_000_000u64));
+    liquidity_pool::add_to_reserve(10 * USDT);
+
+    assert!(liquidity_pool::calculate_withdrawal_preview(Nat::from(0u64)).is_err());
+    assert!(liquidity_pool::calculate_withdrawal_preview(Nat::from(1_000_001u64)).is_err());
+
+    // 1000 shares of a 10 USDT pool is 0.01 USDT, below the 0.1 USDT minimum
+    let dust = liquidity_pool::calculate_withdrawal_preview(Nat::from(1_000u64));
+    assert!(matches!(dust, Err(e) if e.starts_with("Minimum withdrawal")));
+}
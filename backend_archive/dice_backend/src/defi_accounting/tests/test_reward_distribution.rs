@@ -0,0 +1,66 @@
+// Test: Explicit LP Reward Distribution
+//
+// Verifies that distributing rewards from the house treasury:
+// 1. Debits the treasury (parent fee balance) by exactly the reward
+// 2. Raises the pool reserve and share price
+// 3. Raises every LP's redeemable value in proportion to their shares
+//
+// Drives distribute_rewards_internal() directly; the admin wrapper adds a
+// caller check and audit log entry, both of which need a canister runtime.
+
+use candid::{Nat, Principal};
+use crate::defi_accounting::{accounting, liquidity_pool};
+
+const USDT: u64 = 1_000_000;
+
+fn lp(id: u8) -> Principal {
+    Principal::from_slice(&[id; 29])
+}
+
+#[test]
+fn test_distribution_raises_share_price_proportionally() {
+    let (alice, bob) = (lp(1), lp(2));
+    let treasury = liquidity_pool::get_parent_principal();
+
+    // 3:1 split of a 100 USDT pool
+    liquidity_pool::set_lp_shares_for_test(alice, Nat::from(3_000_000u64));
+    liquidity_pool::set_lp_shares_for_test(bob, Nat::from(1_000_000u64));
+    liquidity_pool::add_to_reserve(100 * USDT);
+    accounting::update_balance(treasury, 50 * USDT).unwrap();
+
+    let alice_before = liquidity_pool::get_lp_position_internal(alice).redeemable_usdt;
+    let bob_before = liquidity_pool::get_lp_position_internal(bob).redeemable_usdt;
+    assert_eq!(alice_before, Nat::from(75 * USDT));
+    assert_eq!(bob_before, Nat::from(25 * USDT));
+
+    let distribution = liquidity_pool::distribute_rewards_internal(20 * USDT, 42).unwrap();
+
+    assert_eq!(distribution.reserve_after, 120 * USDT);
+    assert!(distribution.share_price_after > distribution.share_price_before);
+    assert_eq!(accounting::get_balance_internal(treasury), 30 * USDT);
+
+    // Each LP gains 20% (120/100), preserving the 3:1 split
+    assert_eq!(liquidity_pool::get_lp_position_internal(alice).redeemable_usdt, Nat::from(90 * USDT));
+    assert_eq!(liquidity_pool::get_lp_position_internal(bob).redeemable_usdt, Nat::from(30 * USDT));
+
+    let last = liquidity_pool::get_last_distribution().expect("distribution recorded");
+    assert_eq!(last.amount, 20 * USDT);
+    assert_eq!(last.timestamp, 42);
+}
+
+#[test]
+fn test_distribution_rejects_unfunded_or_empty() {
+    assert!(liquidity_pool::get_last_distribution().is_none());
+
+    // No LPs yet
+    assert!(liquidity_pool::distribute_rewards_internal(USDT, 1).is_err());
+
+    liquidity_pool::set_lp_shares_for_test(lp(3), Nat::from(1_000_000u64));
+    liquidity_pool::add_to_reserve(10 * USDT);
+
+    // Treasury is empty
+    assert!(liquidity_pool::distribute_rewards_internal(USDT, 1).is_err());
+    assert!(liquidity_pool::distribute_rewards_internal(0, 1).is_err());
+    assert_eq!(liquidity_pool::get_pool_reserve(), 10 * USDT);
+    assert!(liquidity_pool::get_last_distribution().is_none());
+}
@@ -48,6 +48,7 @@ fn test_pending_withdrawal_unbounded_serialization() {
             shares: huge_nat.clone(),
             reserve: huge_nat.clone(),
             amount: u64::MAX,
+            fee: 0,
         },
         created_at: u64::MAX,
     };
@@ -61,7 +62,7 @@ fn test_pending_withdrawal_unbounded_serialization() {
     // Verify round-trip integrity
     let decoded = PendingWithdrawal::from_bytes(bytes);
     match decoded.withdrawal_type {
-        WithdrawalType::LP { shares, reserve, amount } => {
+        WithdrawalType::LP { shares, reserve, amount, fee: _ } => {
             assert_eq!(shares, huge_nat, "Shares should survive round-trip");
             assert_eq!(reserve, huge_nat, "Reserve should survive round-trip");
             assert_eq!(amount, u64::MAX, "Amount should survive round-trip");
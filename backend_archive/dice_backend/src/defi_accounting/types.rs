@@ -63,6 +63,33 @@ impl Storable for PendingWithdrawal {
     const BOUND: Bound = Bound::Unbounded;
 }
 
+/// Idempotency record for a deposit made with a client nonce.
+/// Every retry reuses `created_at` as the ledger's created_at_time, so the
+/// ledger rejects a second transfer as a duplicate.
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq)]
+pub struct DepositNonce {
+    pub amount: u64,
+    pub created_at: u64,
+    /// Set once the deposit is credited; later calls with the nonce are replays
+    pub credited: bool,
+}
+
+impl Storable for DepositNonce {
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Owned(candid::encode_one(self).expect("Failed to encode DepositNonce"))
+    }
+
+    fn into_bytes(self) -> Vec<u8> {
+        self.to_bytes().into_owned()
+    }
+
+    fn from_bytes(bytes: Cow<'_, [u8]>) -> Self {
+        candid::decode_one(&bytes).expect("Failed to decode DepositNonce")
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
 #[derive(CandidType, Deserialize, Clone, Debug)]
 pub struct AuditEntry {
     pub timestamp: u64,
@@ -103,6 +130,11 @@ pub enum AuditEvent {
         amount: u64,
         new_balance: u64,
     },
+    /// House treasury funds added to the pool reserve as an explicit LP reward.
+    RewardsDistributed {
+        amount: u64,
+        new_reserve: u64,
+    },
 }
 
 /// Health check result for admin monitoring.
@@ -189,3 +221,32 @@ impl Storable for AuditEntry {
 
     const BOUND: Bound = Bound::Unbounded;
 }
+
+/// Record of an explicit LP reward distribution.
+///
+/// Rewards are added straight to the pool reserve, so every LP's redeemable
+/// value rises in proportion to their shares.
+#[derive(CandidType, Deserialize, Clone, Debug)]
+pub struct RewardDistribution {
+    pub amount: u64,
+    pub timestamp: u64,
+    pub reserve_after: u64,
+    pub share_price_before: Nat,
+    pub share_price_after: Nat,
+}
+
+impl Storable for RewardDistribution {
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Owned(candid::encode_one(self).expect("Failed to encode RewardDistribution"))
+    }
+
+    fn into_bytes(self) -> Vec<u8> {
+        self.to_bytes().into_owned()
+    }
+
+    fn from_bytes(bytes: Cow<'_, [u8]>) -> Self {
+        candid::decode_one(&bytes).expect("Failed to decode RewardDistribution")
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
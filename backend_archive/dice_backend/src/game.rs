@@ -91,8 +91,10 @@ pub async fn play_dice(
     // 2. Validate target number (P3: uses shared helper)
     validate_target_number(target_number, &direction)?;
 
-    // 3. Calculate multiplier for this specific bet
-    let multiplier = calculate_multiplier_direct(target_number, &direction);
+    // 3. Calculate multiplier for this specific bet (plus loss-streak boost, if any)
+    let now = ic_cdk::api::time();
+    let boost_bps = crate::streak::boost_bps_for(caller, now);
+    let multiplier = crate::streak::apply_boost(calculate_multiplier_direct(target_number, &direction), boost_bps);
 
     // 4. Check house limit (P0: uses shared payout calculator)
     let max_payout = calculate_payout(bet_amount, multiplier);
@@ -108,14 +110,16 @@ pub async fn play_dice(
         ));
     }
 
+    // 4b. Refuse bets while recent house losses trip the pool circuit breaker
+    crate::circuit_breaker::check(liquidity_pool::get_pool_reserve(), now)?;
+
     // 5. Validate client seed length (DoS protection)
     if client_seed.len() > 256 {
         return Err("Invalid seed: max 256 characters".to_string());
     }
 
-    // 6. Generate roll using per-game VRF (async call - execution may suspend here)
-    let (rolled_number, server_seed, nonce) = crate::seed::generate_dice_roll_vrf(&client_seed).await?;
-    let server_seed_hash = crate::seed::hash_server_seed(&server_seed);
+    // 6. Derive roll from the committed server seed (async only on first use)
+    let (rolled_number, server_seed_hash, nonce) = crate::seed::generate_dice_roll(caller, &client_seed).await?;
 
     // 7. Atomically deduct bet AFTER await to prevent TOCTOU race condition
     let _balance_after_bet = accounting::try_deduct_balance(caller, bet_amount)?;
@@ -169,11 +173,14 @@ pub async fn play_dice(
         ));
     }
 
+    crate::circuit_breaker::record_settlement(bet_amount, payout, now);
+    crate::streak::record_outcome(caller, is_win, boost_bps > 0, now);
+
     Ok(MinimalGameResult {
         rolled_number,
         is_win,
         payout,
-        server_seed,
+        server_seed: None,
         server_seed_hash,
         nonce,
         client_seed: client_seed.clone(),
@@ -235,15 +242,17 @@ pub async fn play_multi_dice(
         ));
     }
 
+    // 5b. Refuse bets while recent house losses trip the pool circuit breaker
+    crate::circuit_breaker::check(liquidity_pool::get_pool_reserve(), ic_cdk::api::time())?;
+
     // 6. Validate client seed
     if client_seed.len() > 256 {
         return Err("Invalid seed: max 256 characters".to_string());
     }
 
-    // 7. VRF generation (async call - execution may suspend here)
-    let (rolled_numbers, server_seed, nonce) =
-        crate::seed::generate_multi_dice_roll_vrf(dice_count, &client_seed).await?;
-    let server_seed_hash = crate::seed::hash_server_seed(&server_seed);
+    // 7. Derive rolls from the committed server seed (async only on first use)
+    let (rolled_numbers, server_seed_hash, nonce) =
+        crate::seed::generate_multi_dice_roll(caller, dice_count, &client_seed).await?;
 
     // 8. Atomically deduct total bet AFTER await to prevent TOCTOU race condition
     let _balance_after_bet = accounting::try_deduct_balance(caller, total_bet)?;
@@ -306,6 +315,8 @@ pub async fn play_multi_dice(
         ));
     }
 
+    crate::circuit_breaker::record_settlement(total_bet, total_payout, ic_cdk::api::time());
+
     let net_result = (total_payout as i64) - (total_bet as i64);
 
     Ok(MultiDiceGameResult {
@@ -315,13 +326,19 @@ pub async fn play_multi_dice(
         total_payout,
         total_bet,
         net_result,
-        server_seed,
+        server_seed: None,
         server_seed_hash,
         nonce,
         client_seed,
     })
 }
 
+/// Calculate the max single-dice bet that is accepted at every target.
+/// Sized for the top payout (one winning number = 100x).
+pub fn calculate_max_bet() -> u64 {
+    accounting::get_max_allowed_payout() / MAX_NUMBER as u64
+}
+
 /// Calculate max bet per dice considering aggregate payout
 pub fn calculate_max_bet_per_dice(
     dice_count: u8,
@@ -10,6 +10,8 @@ use std::cell::RefCell;
 mod defi_accounting;
 pub mod types;
 pub mod seed;
+pub mod streak;
+pub mod circuit_breaker;
 pub mod game;
 
 // =============================================================================
@@ -52,6 +54,11 @@ fn init() {
     ic_cdk_timers::set_timer(std::time::Duration::ZERO, async {
         defi_accounting::accounting::refresh_canister_balance().await;
         ic_cdk::println!("Init: balance cache initialized");
+
+        // Commit to a server seed before the first game (no-op if one is stored)
+        if let Err(e) = seed::ensure_server_seed().await {
+            ic_cdk::println!("Server seed not initialized: {}", e);
+        }
     });
 }
 
@@ -79,6 +86,11 @@ fn post_upgrade() {
     ic_cdk_timers::set_timer(std::time::Duration::ZERO, async {
         defi_accounting::accounting::refresh_canister_balance().await;
         ic_cdk::println!("Post-upgrade: balance cache initialized");
+
+        // Commit to a server seed before the first game (no-op if one is stored)
+        if let Err(e) = seed::ensure_server_seed().await {
+            ic_cdk::println!("Server seed not initialized: {}", e);
+        }
     });
 
     ic_cdk::println!("Post-upgrade: timers restarted");
@@ -119,11 +131,38 @@ fn verify_game_result(server_seed: [u8; 32], client_seed: String, nonce: u64, ex
     seed::verify_game_result(server_seed, client_seed, nonce, expected_roll)
 }
 
+/// SHA256 of the server seed current rolls are derived from
+#[query]
+fn get_current_seed_hash() -> String {
+    seed::get_current_seed_hash()
+}
+
+/// Admin-only: retire the current seed and reveal it. There is no scheduled
+/// rotation; past rolls become verifiable when an admin calls this.
+/// Returns (old seed as hex, hash of the new seed)
+#[update]
+async fn rotate_and_reveal_seed() -> Result<(String, String), String> {
+    defi_accounting::admin_query::require_admin()?;
+    seed::rotate_and_reveal_seed().await
+}
+
 #[query]
 fn calculate_payout_info(target_number: u8, direction: RollDirection) -> Result<(f64, f64), String> {
     game::calculate_payout_info(target_number, direction)
 }
 
+#[query]
+fn get_game_info() -> types::GameInfo {
+    types::GameInfo {
+        name: "Dice".to_string(),
+        min_bet: types::MIN_BET,
+        max_bet: game::calculate_max_bet(),
+        token_symbol: types::TOKEN_SYMBOL.to_string(),
+        house_edge_bps: types::HOUSE_EDGE_BPS,
+        modes: vec!["over".to_string(), "under".to_string(), "multi_dice".to_string()],
+    }
+}
+
 #[query]
 fn greet(name: String) -> String {
     format!("Welcome to OpenHouse Dice, {}! Roll the dice and test your luck!", name)
@@ -175,8 +214,8 @@ fn get_max_bet_per_dice(
 // =============================================================================
 
 #[update]
-async fn deposit(amount: u64) -> Result<u64, String> {
-    defi_accounting::accounting::deposit(amount).await
+async fn deposit(amount: u64, client_nonce: Option<u64>) -> Result<u64, String> {
+    defi_accounting::accounting::deposit(amount, client_nonce).await
 }
 
 #[update]
@@ -273,6 +312,33 @@ fn admin_get_audit_log_count() -> Result<u64, String> {
     defi_accounting::admin_query::get_audit_log_count()
 }
 
+#[update]
+fn admin_distribute_rewards(amount: u64) -> Result<defi_accounting::types::RewardDistribution, String> {
+    defi_accounting::admin_query::distribute_rewards(amount)
+}
+
+#[update]
+fn admin_set_streak_boost_config(config: streak::StreakBoostConfig) -> Result<(), String> {
+    defi_accounting::admin_query::require_admin()?;
+    streak::set_config(config)
+}
+
+#[query]
+fn get_streak_boost_config() -> streak::StreakBoostConfig {
+    streak::get_config()
+}
+
+#[update]
+fn admin_set_circuit_breaker_config(config: circuit_breaker::CircuitBreakerConfig) -> Result<(), String> {
+    defi_accounting::admin_query::require_admin()?;
+    circuit_breaker::set_config(config)
+}
+
+#[query]
+fn get_circuit_breaker_status() -> circuit_breaker::CircuitBreakerStatus {
+    circuit_breaker::status(defi_accounting::liquidity_pool::get_pool_reserve(), ic_cdk::api::time())
+}
+
 // =============================================================================
 // LIQUIDITY POOL ENDPOINTS
 // =============================================================================
@@ -287,6 +353,16 @@ async fn withdraw_all_liquidity() -> Result<u64, String> {
     defi_accounting::liquidity_pool::withdraw_all_liquidity().await
 }
 
+#[update]
+async fn withdraw_liquidity_shares(shares: candid::Nat) -> Result<u64, String> {
+    defi_accounting::liquidity_pool::withdraw_liquidity_shares(shares).await
+}
+
+#[query]
+fn calculate_withdrawal_preview(shares: candid::Nat) -> Result<(u64, u64), String> {
+    defi_accounting::liquidity_pool::calculate_withdrawal_preview(shares)
+}
+
 #[query]
 fn calculate_shares_preview(amount: u64) -> Result<candid::Nat, String> {
     defi_accounting::liquidity_pool::calculate_shares_preview(amount)
@@ -317,6 +393,11 @@ fn can_accept_bets() -> bool {
     defi_accounting::liquidity_pool::can_accept_bets()
 }
 
+#[query]
+fn get_last_distribution() -> Option<defi_accounting::types::RewardDistribution> {
+    defi_accounting::liquidity_pool::get_last_distribution()
+}
+
 // =============================================================================
 // DAILY STATISTICS ENDPOINTS
 // =============================================================================
@@ -341,3 +422,22 @@ fn get_pool_apy(days: Option<u32>) -> defi_accounting::ApyInfo {
     defi_accounting::get_apy_info(days)
 }
 
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_game_info_matches_config() {
+        let info = get_game_info();
+        assert_eq!(info.name, "Dice");
+        assert_eq!(info.min_bet, types::MIN_BET);
+        assert_eq!(info.max_bet, game::calculate_max_bet());
+        assert_eq!(info.token_symbol, "ckUSDT");
+        assert_eq!(info.house_edge_bps, 99);
+        assert_eq!(info.modes, vec!["over", "under", "multi_dice"]);
+    }
+}
@@ -1,62 +1,129 @@
+//! Provably fair rolls via server seed commit/reveal.
+//!
+//! The canister holds one secret server seed and publishes its SHA256 hash
+//! (`get_current_seed_hash`). Every roll is derived from
+//! `HMAC-SHA256(server_seed, client_seed || nonce)` where the nonce is a
+//! per-player counter. When the seed is rotated the old seed is revealed, so
+//! players can check it against the hash they were shown and re-derive every
+//! roll made under it with `verify_game_result` / `verify_multi_dice_result`.
+//!
+//! Rotation is admin-triggered only (each one costs a raw_rand call), so
+//! rolls under the current seed can't be verified until an admin rotates it.
+
+use candid::{CandidType, Deserialize, Principal};
 use ic_cdk::management_canister::raw_rand;
+use ic_stable_structures::{StableBTreeMap, StableCell, Storable, memory_manager::MemoryId, storable::Bound};
 use sha2::{Digest, Sha256};
+use std::borrow::Cow;
+use std::cell::RefCell;
 use crate::types::MAX_NUMBER;
+use crate::{MEMORY_MANAGER, Memory};
+use crate::defi_accounting::memory_ids::{SERVER_SEED_MEMORY_ID, PLAYER_NONCES_MEMORY_ID};
+
+const HMAC_BLOCK_SIZE: usize = 64;
 
 // =============================================================================
-// PUBLIC FUNCTIONS
+// STORAGE
 // =============================================================================
 
-/// Generate dice roll using per-game VRF
-/// Returns: (rolled_number, server_seed, nonce) for verification
-pub async fn generate_dice_roll_vrf(client_seed: &str) -> Result<(u8, [u8; 32], u64), String> {
-    // Get fresh VRF randomness (async call to IC consensus)
+#[derive(CandidType, Deserialize, Clone, Debug, Default)]
+struct SeedState {
+    /// None until the first seed has been drawn from raw_rand
+    current: Option<[u8; 32]>,
+}
+
+impl Storable for SeedState {
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Owned(candid::encode_one(self).expect("Failed to encode SeedState"))
+    }
+
+    fn into_bytes(self) -> Vec<u8> {
+        self.to_bytes().into_owned()
+    }
+
+    fn from_bytes(bytes: Cow<'_, [u8]>) -> Self {
+        candid::decode_one(&bytes).expect("Failed to decode SeedState")
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+thread_local! {
+    static SERVER_SEED: RefCell<StableCell<SeedState, Memory>> = RefCell::new(
+        StableCell::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(SERVER_SEED_MEMORY_ID))),
+            SeedState::default()
+        )
+    );
+
+    /// Next nonce per player (starts at 0, never reused across seeds)
+    static PLAYER_NONCES: RefCell<StableBTreeMap<Principal, u64, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(PLAYER_NONCES_MEMORY_ID)))
+        )
+    );
+}
+
+fn current_seed() -> Option<[u8; 32]> {
+    SERVER_SEED.with(|s| s.borrow().get().current)
+}
+
+async fn fresh_seed() -> Result<[u8; 32], String> {
     let random_bytes = raw_rand().await
         .map_err(|e| format!("VRF unavailable: {:?}. Please retry.", e))?;
-
-    // Use first 32 bytes as server seed
-    let server_seed: [u8; 32] = random_bytes[0..32]
+    random_bytes[0..32]
         .try_into()
-        .map_err(|_| "Insufficient randomness")?;
+        .map_err(|_| "Insufficient randomness".to_string())
+}
 
-    // Generate unique nonce from timestamp
-    let nonce = ic_cdk::api::time();
+/// Draw the first server seed if none exists yet (no-op afterwards).
+pub async fn ensure_server_seed() -> Result<[u8; 32], String> {
+    if let Some(seed) = current_seed() {
+        return Ok(seed);
+    }
+    let seed = fresh_seed().await?;
+    // Another call may have initialized the seed while we awaited; keep theirs
+    Ok(*SERVER_SEED.with(|s| {
+        let mut cell = s.borrow_mut();
+        if cell.get().current.is_none() {
+            cell.set(SeedState { current: Some(seed) });
+        }
+        cell.get().current.as_ref().copied()
+    }).get_or_insert(seed))
+}
 
-    // Combine server_seed + client_seed + nonce
-    let mut hasher = Sha256::new();
-    hasher.update(server_seed);
-    hasher.update(client_seed.as_bytes());
-    hasher.update(nonce.to_be_bytes());
-    let hash = hasher.finalize();
+/// Hand out the caller's next nonce.
+fn take_nonce(player: Principal) -> u64 {
+    PLAYER_NONCES.with(|n| {
+        let mut nonces = n.borrow_mut();
+        let nonce = nonces.get(&player).unwrap_or(0);
+        nonces.insert(player, nonce + 1);
+        nonce
+    })
+}
 
-    // Convert to 0-100 range
-    let rand_u64 = u64::from_be_bytes(
-        hash[0..8].try_into().expect("SHA256 always produces 32 bytes, slice [0..8] is always valid")
-    );
-    let roll = (rand_u64 % (MAX_NUMBER as u64 + 1)) as u8;
+// =============================================================================
+// PUBLIC FUNCTIONS
+// =============================================================================
 
-    Ok((roll, server_seed, nonce))
+/// Roll one die for `player` under the committed server seed.
+/// Returns: (rolled_number, server_seed_hash, nonce) for verification
+pub async fn generate_dice_roll(player: Principal, client_seed: &str) -> Result<(u8, String, u64), String> {
+    let server_seed = ensure_server_seed().await?;
+    let nonce = take_nonce(player);
+    let roll = derive_roll(&server_seed, client_seed, nonce, None);
+    Ok((roll, hash_server_seed(&server_seed), nonce))
 }
 
 /// Verify game result for provable fairness
-/// Players can call this with the server_seed revealed after game
+/// Players can call this with the server_seed revealed at rotation
 pub fn verify_game_result(
     server_seed: [u8; 32],
     client_seed: String,
     nonce: u64,
     expected_roll: u8
 ) -> Result<bool, String> {
-    let mut hasher = Sha256::new();
-    hasher.update(server_seed);
-    hasher.update(client_seed.as_bytes());
-    hasher.update(nonce.to_be_bytes());
-    let hash = hasher.finalize();
-
-    let rand_u64 = u64::from_be_bytes(
-        hash[0..8].try_into().expect("SHA256 always produces 32 bytes, slice [0..8] is always valid")
-    );
-    let calculated_roll = (rand_u64 % (MAX_NUMBER as u64 + 1)) as u8;
-
-    Ok(calculated_roll == expected_roll)
+    Ok(derive_roll(&server_seed, &client_seed, nonce, None) == expected_roll)
 }
 
 /// Get hash of server seed for pre-game commitment (provable fairness)
@@ -66,61 +133,98 @@ pub fn hash_server_seed(server_seed: &[u8; 32]) -> String {
     format!("{:x}", hasher.finalize())
 }
 
+/// Commitment to the seed future rolls will use (empty before the first seed)
+pub fn get_current_seed_hash() -> String {
+    current_seed().map(|seed| hash_server_seed(&seed)).unwrap_or_default()
+}
+
+/// Draw a new seed and retire the current one.
+/// Returns: (revealed old seed as hex, hash of the new seed)
+pub async fn rotate_and_reveal_seed() -> Result<(String, String), String> {
+    let new_seed = fresh_seed().await?;
+    Ok(rotate_seed(new_seed))
+}
+
+fn rotate_seed(new_seed: [u8; 32]) -> (String, String) {
+    let old = SERVER_SEED.with(|s| {
+        let mut cell = s.borrow_mut();
+        let old = cell.get().current;
+        cell.set(SeedState { current: Some(new_seed) });
+        old
+    });
+    let revealed = old.map(|seed| to_hex(&seed)).unwrap_or_default();
+    (revealed, hash_server_seed(&new_seed))
+}
+
 // =============================================================================
-// MULTI-DICE VRF FUNCTIONS
+// ROLL DERIVATION
 // =============================================================================
 
-/// Derive a single roll from server_seed + client_seed + nonce + dice_index
-/// This is deterministic and verifiable by players
-fn derive_single_roll(
-    server_seed: &[u8; 32],
-    client_seed: &str,
-    nonce: u64,
-    dice_index: u8,
-) -> u8 {
-    let mut hasher = Sha256::new();
-    hasher.update(server_seed);
-    hasher.update(client_seed.as_bytes());
-    hasher.update(nonce.to_be_bytes());
-    hasher.update([dice_index]); // Critical: include dice index for independence
-    let hash = hasher.finalize();
+/// HMAC-SHA256(server_seed, client_seed || nonce [|| dice_index]) reduced to 0-100.
+/// Single-dice games omit the index; multi-dice games append it per die.
+fn derive_roll(server_seed: &[u8; 32], client_seed: &str, nonce: u64, dice_index: Option<u8>) -> u8 {
+    let mut message = Vec::with_capacity(client_seed.len() + 9);
+    message.extend_from_slice(client_seed.as_bytes());
+    message.extend_from_slice(&nonce.to_be_bytes());
+    if let Some(index) = dice_index {
+        message.push(index);
+    }
+    let mac = hmac_sha256(server_seed, &message);
 
     let rand_u64 = u64::from_be_bytes(
-        hash[0..8].try_into().expect("SHA256 always produces 32 bytes, slice [0..8] is always valid")
+        mac[0..8].try_into().expect("SHA256 always produces 32 bytes, slice [0..8] is always valid")
     );
     (rand_u64 % (MAX_NUMBER as u64 + 1)) as u8
 }
 
-/// Generate multiple dice rolls using per-game VRF with deterministic derivation
-/// Single raw_rand() call, derive N independent rolls
-pub async fn generate_multi_dice_roll_vrf(
+/// RFC 2104 HMAC over SHA256
+fn hmac_sha256(key: &[u8], message: &[u8]) -> [u8; 32] {
+    let mut block = [0u8; HMAC_BLOCK_SIZE];
+    if key.len() > HMAC_BLOCK_SIZE {
+        block[..32].copy_from_slice(&Sha256::digest(key));
+    } else {
+        block[..key.len()].copy_from_slice(key);
+    }
+
+    let mut inner = Sha256::new();
+    inner.update(block.map(|b| b ^ 0x36));
+    inner.update(message);
+    let inner_hash = inner.finalize();
+
+    let mut outer = Sha256::new();
+    outer.update(block.map(|b| b ^ 0x5c));
+    outer.update(inner_hash);
+    outer.finalize().into()
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+// =============================================================================
+// MULTI-DICE FUNCTIONS
+// =============================================================================
+
+/// Roll `dice_count` dice for `player` from a single nonce; each die is
+/// derived independently by appending its index
+pub async fn generate_multi_dice_roll(
+    player: Principal,
     dice_count: u8,
     client_seed: &str,
-) -> Result<(Vec<u8>, [u8; 32], u64), String> {
+) -> Result<(Vec<u8>, String, u64), String> {
     use crate::types::MAX_DICE_COUNT;
 
     if dice_count == 0 || dice_count > MAX_DICE_COUNT {
         return Err(format!("Dice count must be 1-{}", MAX_DICE_COUNT));
     }
 
-    // Get fresh VRF randomness (single async call to IC consensus)
-    let random_bytes = raw_rand().await
-        .map_err(|e| format!("VRF unavailable: {:?}. Please retry.", e))?;
-
-    let server_seed: [u8; 32] = random_bytes[0..32]
-        .try_into()
-        .map_err(|_| "Insufficient randomness")?;
-
-    let nonce = ic_cdk::api::time();
+    let server_seed = ensure_server_seed().await?;
+    let nonce = take_nonce(player);
+    let rolls = (0..dice_count)
+        .map(|i| derive_roll(&server_seed, client_seed, nonce, Some(i)))
+        .collect();
 
-    // Derive each dice roll independently using index-based hashing
-    let mut rolls = Vec::with_capacity(dice_count as usize);
-    for i in 0..dice_count {
-        let roll = derive_single_roll(&server_seed, client_seed, nonce, i);
-        rolls.push(roll);
-    }
-
-    Ok((rolls, server_seed, nonce))
+    Ok((rolls, hash_server_seed(&server_seed), nonce))
 }
 
 /// Verify multi-dice game result for provable fairness
@@ -138,10 +242,67 @@ pub fn verify_multi_dice_result(
     }
 
     for (i, &expected_roll) in expected_rolls.iter().enumerate() {
-        let calculated_roll = derive_single_roll(&server_seed, &client_seed, nonce, i as u8);
+        let calculated_roll = derive_roll(&server_seed, &client_seed, nonce, Some(i as u8));
         if calculated_roll != expected_roll {
             return Ok(false);
         }
     }
     Ok(true)
-}
\ No newline at end of file
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn player(id: u8) -> Principal {
+        Principal::from_slice(&[id; 29])
+    }
+
+    #[test]
+    fn test_hmac_matches_rfc4231_vector() {
+        // RFC 4231 test case 2
+        let mac = hmac_sha256(b"Jefe", b"what do ya want for nothing?");
+        assert_eq!(to_hex(&mac), "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843");
+    }
+
+    #[test]
+    fn test_nonces_count_up_per_player() {
+        assert_eq!(take_nonce(player(1)), 0);
+        assert_eq!(take_nonce(player(1)), 1);
+        assert_eq!(take_nonce(player(2)), 0);
+        assert_eq!(take_nonce(player(1)), 2);
+    }
+
+    #[test]
+    fn test_rotation_reveals_committed_seed() {
+        assert_eq!(get_current_seed_hash(), "");
+
+        let (revealed, first_hash) = rotate_seed([7u8; 32]);
+        assert_eq!(revealed, "");
+        assert_eq!(get_current_seed_hash(), first_hash);
+
+        // Rolls made under the first seed verify once it is revealed
+        let roll = derive_roll(&[7u8; 32], "lucky", 3, None);
+        let (revealed, second_hash) = rotate_seed([9u8; 32]);
+        assert_eq!(revealed, to_hex(&[7u8; 32]));
+        assert_ne!(second_hash, first_hash);
+        assert_eq!(hash_server_seed(&[7u8; 32]), first_hash);
+        assert_eq!(verify_game_result([7u8; 32], "lucky".to_string(), 3, roll), Ok(true));
+    }
+
+    #[test]
+    fn test_multi_dice_rolls_are_independent_and_verifiable() {
+        let seed = [42u8; 32];
+        let rolls: Vec<u8> = (0..3).map(|i| derive_roll(&seed, "abc", 11, Some(i))).collect();
+        assert!(rolls.iter().all(|&r| r <= MAX_NUMBER));
+        assert_eq!(verify_multi_dice_result(seed, "abc".to_string(), 11, rolls.clone()), Ok(true));
+
+        let mut tampered = rolls;
+        tampered[2] = (tampered[2] + 1) % (MAX_NUMBER + 1);
+        assert_eq!(verify_multi_dice_result(seed, "abc".to_string(), 11, tampered), Ok(false));
+    }
+}
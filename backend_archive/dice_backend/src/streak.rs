@@ -0,0 +1,260 @@
+//! Loss-streak boost ("cool streak").
+//!
+//! After `loss_threshold` consecutive single-dice losses, the player's next
+//! single-dice bet pays a multiplier raised by `boost_bps`. The boost is
+//! funded by the house and capped at `max_boosts_per_day` per player per UTC
+//! day. Disabled by default; streaks are only tracked while enabled.
+//! Multi-dice games neither use nor affect streaks.
+
+use candid::{CandidType, Deserialize, Principal};
+use ic_stable_structures::{StableBTreeMap, StableCell, Storable, memory_manager::MemoryId, storable::Bound};
+use std::borrow::Cow;
+use std::cell::RefCell;
+use crate::{MEMORY_MANAGER, Memory};
+use crate::defi_accounting::memory_ids::{STREAK_CONFIG_MEMORY_ID, PLAYER_STREAKS_MEMORY_ID};
+
+const NANOS_PER_DAY: u64 = 86_400_000_000_000;
+const BPS_SCALE: u64 = 10_000;
+
+/// Hard ceiling on the configurable boost (10%) so a typo can't hand out 2x payouts
+pub const MAX_BOOST_BPS: u64 = 1_000;
+
+// =============================================================================
+// TYPES
+// =============================================================================
+
+#[derive(CandidType, Deserialize, Clone, Debug, PartialEq)]
+pub struct StreakBoostConfig {
+    pub enabled: bool,
+    /// Consecutive losses required before the next bet is boosted
+    pub loss_threshold: u32,
+    /// Multiplier boost in basis points (100 = multiplier × 1.01)
+    pub boost_bps: u64,
+    /// Boosted bets allowed per player per UTC day
+    pub max_boosts_per_day: u32,
+}
+
+impl Default for StreakBoostConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            loss_threshold: 5,
+            boost_bps: 100,
+            max_boosts_per_day: 3,
+        }
+    }
+}
+
+#[derive(CandidType, Deserialize, Clone, Debug, Default)]
+struct PlayerStreak {
+    consecutive_losses: u32,
+    /// Day index (time / NANOS_PER_DAY) that `boosts_today` refers to
+    boost_day: u64,
+    boosts_today: u32,
+}
+
+impl Storable for StreakBoostConfig {
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Owned(candid::encode_one(self).expect("Failed to encode StreakBoostConfig"))
+    }
+
+    fn into_bytes(self) -> Vec<u8> {
+        self.to_bytes().into_owned()
+    }
+
+    fn from_bytes(bytes: Cow<'_, [u8]>) -> Self {
+        candid::decode_one(&bytes).expect("Failed to decode StreakBoostConfig")
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+impl Storable for PlayerStreak {
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Owned(candid::encode_one(self).expect("Failed to encode PlayerStreak"))
+    }
+
+    fn into_bytes(self) -> Vec<u8> {
+        self.to_bytes().into_owned()
+    }
+
+    fn from_bytes(bytes: Cow<'_, [u8]>) -> Self {
+        candid::decode_one(&bytes).expect("Failed to decode PlayerStreak")
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+// =============================================================================
+// STORAGE
+// =============================================================================
+
+thread_local! {
+    static STREAK_CONFIG: RefCell<StableCell<StreakBoostConfig, Memory>> = RefCell::new(
+        StableCell::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(STREAK_CONFIG_MEMORY_ID))),
+            StreakBoostConfig::default()
+        )
+    );
+
+    static PLAYER_STREAKS: RefCell<StableBTreeMap<Principal, PlayerStreak, Memory>> = RefCell::new(
+        StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(PLAYER_STREAKS_MEMORY_ID)))
+        )
+    );
+}
+
+// =============================================================================
+// CONFIG
+// =============================================================================
+
+pub fn get_config() -> StreakBoostConfig {
+    STREAK_CONFIG.with(|c| c.borrow().get().clone())
+}
+
+pub fn set_config(config: StreakBoostConfig) -> Result<(), String> {
+    if config.loss_threshold == 0 {
+        return Err("Invalid config: loss_threshold must be at least 1".to_string());
+    }
+    if config.boost_bps > MAX_BOOST_BPS {
+        return Err(format!("Invalid config: boost_bps must be at most {}", MAX_BOOST_BPS));
+    }
+    STREAK_CONFIG.with(|c| c.borrow_mut().set(config));
+    Ok(())
+}
+
+// =============================================================================
+// STREAK LOGIC
+// =============================================================================
+
+/// Boost (in basis points) the player's next bet qualifies for, or 0.
+pub fn boost_bps_for(player: Principal, now_ns: u64) -> u64 {
+    let config = get_config();
+    if !config.enabled || config.boost_bps == 0 {
+        return 0;
+    }
+
+    let streak = PLAYER_STREAKS.with(|s| s.borrow().get(&player)).unwrap_or_default();
+    let boosts_today = if streak.boost_day == now_ns / NANOS_PER_DAY { streak.boosts_today } else { 0 };
+
+    if streak.consecutive_losses >= config.loss_threshold && boosts_today < config.max_boosts_per_day {
+        config.boost_bps
+    } else {
+        0
+    }
+}
+
+/// Apply a basis-point boost to a payout multiplier.
+pub fn apply_boost(multiplier: f64, boost_bps: u64) -> f64 {
+    multiplier * (BPS_SCALE + boost_bps) as f64 / BPS_SCALE as f64
+}
+
+/// Record a settled single-dice bet. A boosted bet consumes the streak and
+/// one of today's boosts regardless of outcome.
+pub fn record_outcome(player: Principal, is_win: bool, boosted: bool, now_ns: u64) {
+    if !get_config().enabled {
+        return;
+    }
+
+    let today = now_ns / NANOS_PER_DAY;
+    PLAYER_STREAKS.with(|s| {
+        let mut streaks = s.borrow_mut();
+        let mut streak = streaks.get(&player).unwrap_or_default();
+
+        if streak.boost_day != today {
+            streak.boost_day = today;
+            streak.boosts_today = 0;
+        }
+
+        if boosted {
+            streak.boosts_today = streak.boosts_today.saturating_add(1);
+            streak.consecutive_losses = 0;
+        } else if is_win {
+            streak.consecutive_losses = 0;
+        } else {
+            streak.consecutive_losses = streak.consecutive_losses.saturating_add(1);
+        }
+
+        streaks.insert(player, streak);
+    });
+}
+
+// =============================================================================
+// TESTS
+// =============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY_ONE: u64 = 20_000 * NANOS_PER_DAY;
+
+    fn player(id: u8) -> Principal {
+        Principal::from_slice(&[id; 29])
+    }
+
+    fn enable(loss_threshold: u32, boost_bps: u64, max_boosts_per_day: u32) {
+        set_config(StreakBoostConfig { enabled: true, loss_threshold, boost_bps, max_boosts_per_day })
+            .expect("valid config");
+    }
+
+    fn lose(p: Principal, times: u32, now_ns: u64) {
+        for _ in 0..times {
+            record_outcome(p, false, false, now_ns);
+        }
+    }
+
+    #[test]
+    fn test_disabled_by_default() {
+        let p = player(1);
+        assert!(!get_config().enabled);
+        lose(p, 50, DAY_ONE);
+        assert_eq!(boost_bps_for(p, DAY_ONE), 0);
+    }
+
+    #[test]
+    fn test_threshold_grants_boosted_multiplier() {
+        enable(3, 100, 2);
+        let p = player(2);
+
+        lose(p, 2, DAY_ONE);
+        assert_eq!(boost_bps_for(p, DAY_ONE), 0);
+
+        lose(p, 1, DAY_ONE);
+        let boost = boost_bps_for(p, DAY_ONE);
+        assert_eq!(boost, 100);
+        assert!((apply_boost(2.0, boost) - 2.02).abs() < 1e-12);
+
+        // A win resets the streak
+        record_outcome(p, true, false, DAY_ONE);
+        assert_eq!(boost_bps_for(p, DAY_ONE), 0);
+    }
+
+    #[test]
+    fn test_boost_is_capped_per_day() {
+        enable(2, 100, 2);
+        let p = player(3);
+
+        for _ in 0..2 {
+            lose(p, 2, DAY_ONE);
+            assert_eq!(boost_bps_for(p, DAY_ONE), 100);
+            record_outcome(p, false, true, DAY_ONE);
+        }
+
+        // Cap reached: streak qualifies again but no boost is granted
+        lose(p, 2, DAY_ONE);
+        assert_eq!(boost_bps_for(p, DAY_ONE), 0);
+
+        // Allowance resets on the next day
+        assert_eq!(boost_bps_for(p, DAY_ONE + NANOS_PER_DAY), 100);
+    }
+
+    #[test]
+    fn test_config_validation() {
+        let zero_threshold = StreakBoostConfig { loss_threshold: 0, ..StreakBoostConfig::default() };
+        assert!(set_config(zero_threshold).is_err());
+
+        let huge_boost = StreakBoostConfig { boost_bps: MAX_BOOST_BPS + 1, ..StreakBoostConfig::default() };
+        assert!(set_config(huge_boost).is_err());
+    }
+}
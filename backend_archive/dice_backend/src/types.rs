@@ -9,6 +9,8 @@ pub const DECIMALS_PER_CKUSDT: u64 = 1_000_000; // 1 ckUSDT = 1,000,000 decimals
 pub const MIN_BET: u64 = 10_000; // 0.01 USDT
 pub const MAX_NUMBER: u8 = 100; // Dice rolls 0-100
 pub const MAX_DICE_COUNT: u8 = 3; // Maximum dice per multi-dice game
+pub const TOKEN_SYMBOL: &str = "ckUSDT";
+pub const HOUSE_EDGE_BPS: u64 = 99; // Exact hit loses: EV = 100/101, edge ~0.99%
 pub const CKUSDT_CANISTER_ID: &str = "cngnf-vqaaa-aaaar-qag4q-cai";
 pub const CKUSDT_TRANSFER_FEE: u64 = 10_000;
 
@@ -33,7 +35,9 @@ pub struct DiceGameResult {
     pub is_win: bool,
     pub payout: u64,
     // Provably fair verification data
-    pub server_seed: [u8; 32],
+    /// Always None: the seed stays secret until rotate_and_reveal_seed
+    /// reveals it. Verify against server_seed_hash once it does.
+    pub server_seed: Option<[u8; 32]>,
     pub server_seed_hash: String,
     pub nonce: u64,
     pub client_seed: String,
@@ -70,12 +74,30 @@ pub struct MultiDiceGameResult {
     /// Net profit/loss (total_payout - total_bet)
     pub net_result: i64,
     // Provably fair verification data
-    pub server_seed: [u8; 32],
+    /// Always None: the seed stays secret until rotate_and_reveal_seed
+    /// reveals it. Verify against server_seed_hash once it does.
+    pub server_seed: Option<[u8; 32]>,
     pub server_seed_hash: String,
     pub nonce: u64,
     pub client_seed: String,
 }
 
+// =============================================================================
+// GAME INFO
+// =============================================================================
+
+/// Self-description for frontends that integrate several game canisters
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug)]
+pub struct GameInfo {
+    pub name: String,
+    pub min_bet: u64,
+    /// Largest bet the pool currently accepts at the game's top multiplier
+    pub max_bet: u64,
+    pub token_symbol: String,
+    pub house_edge_bps: u64,
+    pub modes: Vec<String>,
+}
+
 // =============================================================================
 // ICRC-2 TYPES
 // =============================================================================
@@ -273,7 +273,7 @@ pub fn get_benchmark_report() -> BenchmarkReport {
 
     BENCHMARKS.with(|b| {
         let b = b.borrow();
-        let now = ic_cdk::api::time();
+        let now = crate::now_ns();
         let duration_ns = now.saturating_sub(b.last_reset_ns);
         let duration_hours = duration_ns as f64 / 3_600_000_000_000.0;
 
@@ -318,7 +318,7 @@ pub fn get_benchmark_report() -> BenchmarkReport {
 #[ic_cdk::update]
 pub fn reset_benchmarks() {
     BENCHMARKS.with(|b| {
-        b.borrow_mut().reset(ic_cdk::api::time());
+        b.borrow_mut().reset(crate::now_ns());
     });
 }
 
@@ -4,16 +4,19 @@
 //! with base-centric territory control.
 
 mod benchmarks;
+mod sandbox;
+mod snapshot;
 
 // Re-export benchmark types for candid export
 pub use benchmarks::{BenchmarkData, BenchmarkReport, CycleBreakdown, IdleBurnInfo, OperationStats};
+pub use sandbox::SandboxState;
 
 use arrayvec::ArrayVec;
 use candid::{CandidType, Deserialize, Principal};
 use ic_cdk_timers::TimerId;
 use serde::Serialize;
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
 use std::time::Duration;
 
 // =============================================================================
@@ -34,27 +37,59 @@ const TOTAL_CHUNKS: usize = 64; // 8 * 8
 /// Quadrants for wipe (128x128 cells each)
 const QUADRANT_SIZE: u16 = 128;
 const QUADRANTS_PER_ROW: usize = 4; // 512 / 128
+const SPAWN_STRIDE: u8 = 7; // Coprime with TOTAL_QUADRANTS: request_spawn visits every quadrant, far apart
 const TOTAL_QUADRANTS: u8 = 16; // 4 * 4
 
 /// Player limits
 const MAX_PLAYERS: usize = 8;
+const MAX_SPECTATORS: usize = 10_000; // Bounds the persisted spectator set
+const MAX_DISPLAY_NAME_LEN: usize = 24; // Characters
+const MAX_PROFILE_COLOR: u32 = 0xFF_FFFF; // Packed 0xRRGGBB
 
 /// Economy
 const FAUCET_AMOUNT: u64 = 1000;
 const BASE_COST: u64 = 100;
+const MAX_STARTING_TREASURY: u64 = 100_000; // Default starting treasury is BASE_COST
 const PLACEMENT_COST: u64 = 1;
 const SIEGE_DAMAGE: u64 = 10;  // Coins stolen per blocked birth (10x placement cost = high ROI for reaching walls)
+const SIEGE_EVENT_LIMIT: usize = 512; // Siege hits kept for attack attribution
+const ELIMINATION_LOG_LIMIT: usize = 256; // Eliminations kept for the kill feed
 const MAX_PLACE_CELLS: usize = 1000;
+const DEFAULT_QUADRANT_DENSITY_LIMIT: u32 = 4_096; // A quarter of a quadrant's cells, per player
+const MAX_PREVIEW_GENERATIONS: u8 = 10; // Bounds preview_after_placement's cycles
+const MAX_SEED_DENSITY_PCT: u8 = 40; // Denser random soups are too costly to simulate
+const BPS_DENOMINATOR: u64 = 10_000;
 
 /// Timing
 const GENERATIONS_PER_TICK: u32 = 8;   // 8 gen/sec - matches frontend LOCAL_TICK_MS=125
 const TICK_INTERVAL_MS: u64 = 1000;
-const WIPE_INTERVAL_NS: u64 = 120_000_000_000; // 2 minutes
-const GRACE_PERIOD_NS: u64 = 600_000_000_000; // 10 minutes
+const MAX_GENERATIONS_PER_TICK: u32 = 32; // Bounds the cycles a single tick can burn
+const MIN_TICK_INTERVAL_MS: u64 = 100;
+const MAX_TICK_INTERVAL_MS: u64 = 60_000;
+const WIPE_INTERVAL_NS: u64 = 120_000_000_000; // 2 minutes (default, see set_wipe_interval)
+const GRACE_PERIOD_NS: u64 = 600_000_000_000; // 10 minutes (default, see set_grace_period)
+const MIN_WIPE_INTERVAL_NS: u64 = 30_000_000_000; // 30 seconds
+const MAX_WIPE_FREEZE_GENS: u64 = 2_400; // 5 minutes at GENERATIONS_PER_TICK
 const IDLE_FREEZE_NS: u64 = 1_800_000_000_000; // 30 minutes - freeze if no player activity
+const MIN_QUADRANT_WIPE_INTERVAL_NS: u64 = 10_000_000_000; // 10 seconds
+const RELOCATE_COOLDOWN_NS: u64 = 60_000_000_000; // 1 minute between base moves
+const DISCONNECT_GRACE_GENS: u64 = 16; // 2 seconds at 8 gen/sec
+const SHRINK_INTERVAL_NS: u64 = 60_000_000_000; // 1 minute per ring in sudden death
+const SHRINK_STEP: u16 = 16; // Ring width removed from each edge per shrink
+const MIN_ARENA_SIZE: u16 = 64; // Sudden-death arena stops shrinking at 64x64
 
 /// Base dimensions
 const BASE_SIZE: u16 = 8;
+const MIN_BASE_INTERIOR: u16 = 4;
+const MIN_BASE_SIZE: u16 = MIN_BASE_INTERIOR + 2; // 1-cell wall on each side
+const MAX_BASE_SIZE: u16 = 16;
+
+/// Spectator replay: generations of alive-bitmap diffs kept for catch-up
+const RECENT_DIFF_LIMIT: usize = 100;
+
+/// Cell decay (optional, off by default): a cell continuously alive this many
+/// generations dies regardless of neighbors
+const MAX_CELL_AGE: u16 = 1000;
 
 // =============================================================================
 // DATA STRUCTURES
@@ -81,13 +116,55 @@ struct Base {
     y: u16,
     /// Treasury (0 = eliminated)
     coins: u64,
+    /// Side length of the square footprint. None only for bases persisted
+    /// before sizing existed; post_upgrade fills in BASE_SIZE.
+    size: Option<u16>,
+}
+
+impl Base {
+    fn size(&self) -> u16 {
+        self.size.unwrap_or(BASE_SIZE)
+    }
+}
+
+/// Independent wipe schedule for one quadrant
+#[derive(Clone, Copy, Debug, PartialEq, Eq, CandidType, Deserialize, Serialize)]
+pub struct QuadrantWipe {
+    pub last_wipe_ns: u64,
+    pub interval_ns: u64,
+}
+
+impl Default for QuadrantWipe {
+    /// Same per-quadrant cadence as the global rotation: once every 16 intervals
+    fn default() -> Self {
+        Self { last_wipe_ns: 0, interval_ns: WIPE_INTERVAL_NS * TOTAL_QUADRANTS as u64 }
+    }
+}
+
+/// Which live cells a quadrant wipe leaves alone
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, CandidType, Deserialize, Serialize)]
+pub enum WipeExemption {
+    /// Wipe everything in the quadrant (classic behavior)
+    #[default]
+    None,
+    /// Spare cells inside any base footprint
+    Bases,
+    /// Spare base footprints and all territory of players who still have a base
+    BasesAndTerritory,
+}
+
+/// Sudden-death arena: only [inset, GRID_SIZE - inset) on both axes is playable
+#[derive(Clone, Copy, Debug, PartialEq, Eq, CandidType, Deserialize, Serialize)]
+struct Arena {
+    inset: u16,
+    last_shrink_ns: u64,
 }
 
 /// Cell fate during generation processing
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 enum CellFate {
     Survives,
-    Birth(usize), // new_owner
+    Birth(Option<usize>), // new_owner, None = neutral
     Death,
     StaysDead,
 }
@@ -159,6 +236,15 @@ impl TerritoryChanges {
     }
 }
 
+/// Territory cut off from its base while disconnect grace is on. Culled if
+/// still cut off DISCONNECT_GRACE_GENS generations after `since_generation`.
+#[derive(CandidType, Deserialize, Serialize, Clone)]
+struct OrphanedComponent {
+    player: u8,
+    since_generation: u64,
+    cells: Vec<(u16, u16)>,
+}
+
 /// State to persist across upgrades
 #[derive(CandidType, Deserialize, Serialize)]
 struct PersistedState {
@@ -176,12 +262,70 @@ struct PersistedState {
     owner: Vec<u8>,
     #[serde(default)]
     last_activity_ns: Option<u64>,
+    #[serde(default)]
+    alliances: Option<Vec<Option<u8>>>,
+    #[serde(default)]
+    cell_decay_enabled: Option<bool>,
+    #[serde(default)]
+    wipe_exemption: Option<WipeExemption>,
+    #[serde(default)]
+    recent_diffs: Option<Vec<GenDiff>>,
+    #[serde(default)]
+    diff_base: Option<Vec<u64>>,
+    #[serde(default)]
+    readonly: Option<bool>,
+    #[serde(default)]
+    independent_wipes: Option<bool>,
+    #[serde(default)]
+    quadrant_wipes: Option<Vec<QuadrantWipe>>,
+    #[serde(default)]
+    placement_burn_bps: Option<u64>,
+    #[serde(default)]
+    total_burned: Option<u64>,
+    #[serde(default)]
+    wipe_interval_ns: Option<u64>,
+    #[serde(default)]
+    grace_period_ns: Option<u64>,
+    #[serde(default)]
+    disconnect_grace_enabled: Option<bool>,
+    #[serde(default)]
+    orphans: Option<Vec<OrphanedComponent>>,
+    #[serde(default)]
+    siege_events: Option<Vec<SiegeEvent>>,
+    #[serde(default)]
+    starting_treasury: Option<u64>,
+    #[serde(default)]
+    arena: Option<Arena>,
+    #[serde(default)]
+    eliminations: Option<Vec<EliminationRecord>>,
+    #[serde(default)]
+    wipe_freeze_gens: Option<u64>,
+    #[serde(default)]
+    quadrant_placed_gens: Option<Vec<Option<u64>>>,
+    #[serde(default)]
+    quadrant_density_limit: Option<u32>,
+    #[serde(default)]
+    spectators: Option<Vec<Principal>>,
+    #[serde(default)]
+    tick_generations: Option<u32>,
+    #[serde(default)]
+    tick_interval_ms: Option<u64>,
+    #[serde(default)]
+    profiles: Option<Vec<Option<PlayerProfile>>>,
 }
 
 // =============================================================================
 // API RESPONSE TYPES
 // =============================================================================
 
+/// Player-chosen display metadata, so every client names and colors a slot the same way
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct PlayerProfile {
+    pub display_name: String,
+    /// Packed RGB, 0xRRGGBB
+    pub color: u32,
+}
+
 #[derive(CandidType, Deserialize, Serialize, Clone)]
 pub struct PlayerInfo {
     pub principal: Principal,
@@ -190,6 +334,7 @@ pub struct PlayerInfo {
     pub territory_cells: u32,
     pub in_grace_period: bool,
     pub grace_seconds_remaining: Option<u64>,
+    pub profile: Option<PlayerProfile>,
 }
 
 #[derive(CandidType, Deserialize, Serialize, Clone)]
@@ -197,6 +342,7 @@ pub struct BaseInfo {
     pub x: u16,
     pub y: u16,
     pub coins: u64,
+    pub size: u16,
     pub slot: u8,
 }
 
@@ -208,6 +354,7 @@ pub struct SlotInfo {
     pub territory_cells: u32,
     pub in_grace_period: bool,
     pub grace_seconds_remaining: Option<u64>,
+    pub profile: Option<PlayerProfile>,
 }
 
 #[derive(CandidType, Deserialize, Serialize)]
@@ -233,6 +380,117 @@ pub struct WipeInfo {
     pub seconds_until: u64,
 }
 
+/// Playable square while sudden death is on: min <= x, y < max
+#[derive(CandidType, Deserialize, Serialize, Debug, PartialEq)]
+pub struct ArenaInfo {
+    pub min: u16,
+    pub max: u16,
+    /// 0 once the arena has reached MIN_ARENA_SIZE
+    pub seconds_until_shrink: u64,
+}
+
+#[derive(CandidType, Deserialize, Serialize, Debug, PartialEq)]
+pub struct Timings {
+    pub wipe_interval_secs: u64,
+    pub grace_period_secs: u64,
+}
+
+/// Minimap aggregate for one 128x128 quadrant
+#[derive(CandidType, Deserialize, Serialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct QuadStat {
+    pub alive: u32,
+    /// Slot owning the most live cells here (lowest slot on ties)
+    pub dominant_owner: Option<u8>,
+    pub has_base: bool,
+}
+
+#[derive(CandidType, Deserialize, Serialize, Debug)]
+pub struct WipeImpact {
+    pub quadrant: u8,
+    pub seconds: u64,
+    /// Live cells the wipe would kill (exempt cells excluded)
+    pub alive_cells: u32,
+    /// Slots owning any of those cells, most affected first
+    pub affected_players: Vec<u8>,
+}
+
+/// A birth blocked by a base wall, draining the defender's treasury
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct SiegeEvent {
+    /// Generation in which the birth was blocked
+    pub generation: u64,
+    pub victim_slot: u8,
+    pub attacker_slot: u8,
+    pub coins_transferred: u64,
+    pub x: u16,
+    pub y: u16,
+}
+
+/// Why a slot was cleared
+#[derive(CandidType, Deserialize, Serialize, Clone, Copy, Debug, PartialEq)]
+pub enum EliminationReason {
+    /// Siege drained the base treasury to zero
+    BaseDestroyed,
+    /// No live cells for the whole grace period
+    GraceExpired,
+    /// The player left via leave_game
+    VoluntaryLeave,
+    /// Sudden death shrank the arena past the base
+    OutsideArena,
+    /// The player handed their treasury to another base via surrender_to
+    Surrender,
+}
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct EliminationRecord {
+    pub slot: u8,
+    pub principal: Principal,
+    pub generation: u64,
+    pub reason: EliminationReason,
+}
+
+/// Why place_cells_checked placed nothing
+#[derive(CandidType, Deserialize, Serialize, Debug, PartialEq)]
+pub enum PlaceError {
+    /// The whole call was refused (read-only, batch too large, not in game,
+    /// insufficient coins, density limit)
+    Call(String),
+    /// Every rejected cell, at the coordinates the caller sent, with its reason
+    Cells(Vec<(i32, i32, String)>),
+}
+
+/// Alive-bitmap change for one generation: XOR masks for every word that
+/// changed since the previous generation (including placements and wipes)
+#[derive(CandidType, Deserialize, Serialize, Clone)]
+pub struct GenDiff {
+    pub generation: u64,
+    pub words: Vec<(u16, u64)>,
+}
+
+#[derive(CandidType, Deserialize, Serialize, Clone, Debug, PartialEq)]
+pub struct SparseCell {
+    pub x: u16,
+    pub y: u16,
+    /// Owning slot, or None for unowned cells
+    pub owner: Option<u8>,
+}
+
+/// Net change to the alive grid between two generations
+#[derive(CandidType, Deserialize, Serialize, Debug)]
+pub struct GridDelta {
+    pub from_gen: u64,
+    pub to_gen: u64,
+    pub born: Vec<SparseCell>,
+    pub died: Vec<(u16, u16)>,
+}
+
+#[derive(CandidType, Deserialize, Serialize)]
+pub struct Affordability {
+    pub balance: u64,
+    /// Cells the wallet can pay for at PLACEMENT_COST (not capped by MAX_PLACE_CELLS)
+    pub max_placeable_cells: u64,
+}
+
 // =============================================================================
 // GLOBAL STATE
 // =============================================================================
@@ -255,14 +513,75 @@ thread_local! {
     static WALLETS: RefCell<HashMap<Principal, u64>> = RefCell::new(HashMap::new());
     static CELL_COUNTS: RefCell<[u32; MAX_PLAYERS]> = RefCell::new([0u32; MAX_PLAYERS]);
     static ZERO_CELLS_SINCE: RefCell<[Option<u64>; MAX_PLAYERS]> = RefCell::new([None; MAX_PLAYERS]);
+    // Last base relocation per slot (transient: cooldown resets on upgrade)
+    static LAST_RELOCATE_NS: RefCell<[Option<u64>; MAX_PLAYERS]> = const { RefCell::new([None; MAX_PLAYERS]) };
+
+    // Alliances - allied partner slot per player, and pending proposals (proposer -> target)
+    static ALLIANCES: RefCell<[Option<u8>; MAX_PLAYERS]> = const { RefCell::new([None; MAX_PLAYERS]) };
+    static ALLIANCE_PROPOSALS: RefCell<[Option<u8>; MAX_PLAYERS]> = const { RefCell::new([None; MAX_PLAYERS]) };
+
+    // Position in the request_spawn quadrant rotation (transient: restarts at 0 on upgrade)
+    static SPAWN_CURSOR: RefCell<u8> = const { RefCell::new(0) };
 
     // Game state
     static GENERATION: RefCell<u64> = RefCell::new(0);
     static IS_RUNNING: RefCell<bool> = RefCell::new(true);
     static NEXT_WIPE_QUADRANT: RefCell<u8> = RefCell::new(0);
     static LAST_WIPE_NS: RefCell<u64> = RefCell::new(0);
+    // Runtime-tunable timings (controller setters)
+    static TICK_GENERATIONS: RefCell<u32> = const { RefCell::new(GENERATIONS_PER_TICK) };
+    static TICK_INTERVAL: RefCell<u64> = const { RefCell::new(TICK_INTERVAL_MS) };
+    static WIPE_INTERVAL: RefCell<u64> = const { RefCell::new(WIPE_INTERVAL_NS) };
+    static GRACE_PERIOD: RefCell<u64> = const { RefCell::new(GRACE_PERIOD_NS) };
+    // Optional per-quadrant wipe timers (replace the global rotation when enabled)
+    static INDEPENDENT_WIPES: RefCell<bool> = const { RefCell::new(false) };
+    static QUADRANT_WIPES: RefCell<[QuadrantWipe; TOTAL_QUADRANTS as usize]> = RefCell::new(Default::default());
+    static CELL_DECAY_ENABLED: RefCell<bool> = const { RefCell::new(false) };
+    static WIPE_EXEMPTION: RefCell<WipeExemption> = const { RefCell::new(WipeExemption::None) };
+    // Wipe freeze - a quadrant with placements in the last WIPE_FREEZE_GENS
+    // generations skips its wipe (0 = off)
+    static WIPE_FREEZE_GENS: RefCell<u64> = const { RefCell::new(0) };
+    static QUADRANT_PLACED_GEN: RefCell<[Option<u64>; TOTAL_QUADRANTS as usize]> = const { RefCell::new([None; TOTAL_QUADRANTS as usize]) };
+    // Anti-griefing - most live cells one player may own in a single quadrant
+    // before further placements there are refused
+    static QUADRANT_DENSITY_LIMIT: RefCell<u32> = const { RefCell::new(DEFAULT_QUADRANT_DENSITY_LIMIT) };
+    // Read-only: simulation keeps running but player mutations are rejected
+    static READONLY: RefCell<bool> = const { RefCell::new(false) };
+    // Disconnect grace - cut-off territory waits DISCONNECT_GRACE_GENS before culling
+    static DISCONNECT_GRACE_ENABLED: RefCell<bool> = const { RefCell::new(false) };
+    static ORPHANS: RefCell<Vec<OrphanedComponent>> = const { RefCell::new(Vec::new()) };
+
+    // Economy sink - share of placement cost burned instead of paid to the base
+    static PLACEMENT_BURN_BPS: RefCell<u64> = const { RefCell::new(0) };
+    static TOTAL_BURNED: RefCell<u64> = const { RefCell::new(0) };
+    // Coins a new base starts with (the join price stays BASE_COST)
+    static STARTING_TREASURY: RefCell<u64> = const { RefCell::new(BASE_COST) };
+
+    // Sudden death (None = whole grid playable)
+    static ARENA: RefCell<Option<Arena>> = const { RefCell::new(None) };
+
+    // Generations each cell has been continuously alive (only maintained while decay is on).
+    // Heap-allocated: 512KB is too large to build on the stack.
+    static CELL_AGE: RefCell<Vec<u16>> = RefCell::new(vec![0u16; TOTAL_CELLS]);
+
+    // Spectator replay - bitmap as of the last recorded generation, and the
+    // most recent RECENT_DIFF_LIMIT diffs (oldest first)
+    static DIFF_BASE: RefCell<Vec<u64>> = RefCell::new(vec![0u64; TOTAL_WORDS]);
+    static RECENT_DIFFS: RefCell<VecDeque<GenDiff>> = RefCell::new(VecDeque::with_capacity(RECENT_DIFF_LIMIT));
     static LAST_ACTIVITY_NS: RefCell<u64> = RefCell::new(0);
 
+    // Attack attribution - most recent SIEGE_EVENT_LIMIT siege hits (oldest first)
+    static SIEGE_EVENTS: RefCell<VecDeque<SiegeEvent>> = const { RefCell::new(VecDeque::new()) };
+
+    // Kill feed - most recent ELIMINATION_LOG_LIMIT eliminations (oldest first)
+    static ELIMINATIONS: RefCell<VecDeque<EliminationRecord>> = const { RefCell::new(VecDeque::new()) };
+
+    // Display name and color per slot, cleared when the slot is vacated
+    static PROFILES: RefCell<[Option<PlayerProfile>; MAX_PLAYERS]> = RefCell::new(Default::default());
+
+    // Registered watchers - no slot, base or cost; dropped when they join
+    static SPECTATORS: RefCell<BTreeSet<Principal>> = const { RefCell::new(BTreeSet::new()) };
+
     // BFS workspace (pre-allocated)
     static BFS_WORKSPACE: RefCell<BFSWorkspace> = RefCell::new(BFSWorkspace::new());
 
@@ -270,6 +589,63 @@ thread_local! {
     static TIMER_ID: RefCell<Option<TimerId>> = RefCell::new(None);
 }
 
+// =============================================================================
+// HELPER FUNCTIONS - TIME
+// =============================================================================
+
+/// Current time in nanoseconds. All game timestamps go through here so tests
+/// can pin the clock with `set_test_time_ns`.
+#[cfg(not(test))]
+pub(crate) fn now_ns() -> u64 {
+    ic_cdk::api::time()
+}
+
+#[cfg(test)]
+thread_local! {
+    static TEST_TIME_NS: RefCell<u64> = const { RefCell::new(0) };
+}
+
+#[cfg(test)]
+pub(crate) fn now_ns() -> u64 {
+    TEST_TIME_NS.with(|t| *t.borrow())
+}
+
+#[cfg(test)]
+fn set_test_time_ns(ns: u64) {
+    TEST_TIME_NS.with(|t| *t.borrow_mut() = ns);
+}
+
+// =============================================================================
+// HELPER FUNCTIONS - ACCESS
+// =============================================================================
+
+#[cfg(not(test))]
+fn require_controller() -> Result<(), String> {
+    if !ic_cdk::api::is_controller(&ic_cdk::api::msg_caller()) {
+        return Err("Only controllers can do this".to_string());
+    }
+    Ok(())
+}
+
+// Tests call endpoints as a non-controller unless `set_test_controller` says otherwise
+#[cfg(test)]
+thread_local! {
+    static TEST_CONTROLLER: RefCell<bool> = const { RefCell::new(false) };
+}
+
+#[cfg(test)]
+fn require_controller() -> Result<(), String> {
+    if !TEST_CONTROLLER.with(|c| *c.borrow()) {
+        return Err("Only controllers can do this".to_string());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+fn set_test_controller(is_controller: bool) {
+    TEST_CONTROLLER.with(|c| *c.borrow_mut() = is_controller);
+}
+
 // =============================================================================
 // HELPER FUNCTIONS - BITMAP
 // =============================================================================
@@ -423,6 +799,19 @@ fn quadrant_bounds(q: u8) -> (u16, u16, u16, u16) {
     (qx * QUADRANT_SIZE, qy * QUADRANT_SIZE, QUADRANT_SIZE, QUADRANT_SIZE)
 }
 
+/// ALIVE words covering a quadrant - two per row - as (word_idx, x of bit 0, y)
+fn quadrant_words(q: u8) -> impl Iterator<Item = (usize, u16, u16)> {
+    let (x_start, y_start, _, _) = quadrant_bounds(q);
+    let word_col_start = (x_start / 64) as usize;
+    (0..QUADRANT_SIZE).flat_map(move |row_offset| {
+        let y = y_start + row_offset;
+        let word_row_base = (y as usize) * WORDS_PER_ROW;
+        (0..2u16).map(move |word_offset| {
+            (word_row_base + word_col_start + word_offset as usize, x_start + word_offset * 64, y)
+        })
+    })
+}
+
 fn quadrant_has_base(q: u8) -> bool {
     BASES.with(|bases| {
         let bases = bases.borrow();
@@ -444,7 +833,7 @@ fn quadrant_has_base(q: u8) -> bool {
 fn is_in_base(base: &Base, x: u16, y: u16) -> bool {
     let dx = x.wrapping_sub(base.x) & 511;
     let dy = y.wrapping_sub(base.y) & 511;
-    dx < BASE_SIZE && dy < BASE_SIZE
+    dx < base.size() && dy < base.size()
 }
 
 /// Check if position is in any player's protection zone
@@ -463,12 +852,57 @@ fn in_protection_zone(x: u16, y: u16) -> Option<usize> {
     })
 }
 
-fn bases_would_overlap(new_x: u16, new_y: u16, existing: &Base) -> bool {
-    let dx = new_x.abs_diff(existing.x);
-    let dy = new_y.abs_diff(existing.y);
-    let dx = dx.min(GRID_SIZE - dx);
-    let dy = dy.min(GRID_SIZE - dy);
-    dx < BASE_SIZE && dy < BASE_SIZE
+/// Steps from `from` to `to` moving only forward around the torus
+fn wrapped_offset(from: u16, to: u16) -> u16 {
+    to.wrapping_sub(from) & 511
+}
+
+/// Chebyshev (king-move) distance on the torus: the larger of the two axis
+/// gaps, each taken the short way round. Matches the square base footprints.
+fn toroidal_distance(x1: u16, y1: u16, x2: u16, y2: u16) -> u16 {
+    let dx = wrapped_offset(x1, x2).min(wrapped_offset(x2, x1));
+    let dy = wrapped_offset(y1, y2).min(wrapped_offset(y2, y1));
+    dx.max(dy)
+}
+
+fn bases_would_overlap(new_x: u16, new_y: u16, new_size: u16, existing: &Base) -> bool {
+    // Footprints overlap on an axis when either start falls inside the other's span (toroidal)
+    let x_overlap = wrapped_offset(new_x, existing.x) < new_size || wrapped_offset(existing.x, new_x) < existing.size();
+    let y_overlap = wrapped_offset(new_y, existing.y) < new_size || wrapped_offset(existing.y, new_y) < existing.size();
+    x_overlap && y_overlap
+}
+
+/// Closest other base to `slot`'s, as (slot, center-to-center toroidal_distance)
+fn nearest_enemy_base(slot: usize) -> Option<(u8, u16)> {
+    BASES.with(|bases| {
+        let bases = bases.borrow();
+        let own = bases.get(slot)?.as_ref()?;
+        let center = |b: &Base| (b.x.wrapping_add(b.size() / 2) & 511, b.y.wrapping_add(b.size() / 2) & 511);
+        let (x, y) = center(own);
+        bases
+            .iter()
+            .enumerate()
+            .filter(|&(i, _)| i != slot)
+            .filter_map(|(i, b)| b.as_ref().map(|b| (i as u8, center(b))))
+            .map(|(i, (bx, by))| (i, toroidal_distance(x, y, bx, by)))
+            .min_by_key(|&(i, distance)| (distance, i))
+    })
+}
+
+fn validate_base_size(size: u16) -> Result<(), String> {
+    if !size.is_multiple_of(2) {
+        return Err("Base size must be even".to_string());
+    }
+    if size < MIN_BASE_SIZE {
+        return Err(format!(
+            "Base size {} leaves an interior smaller than {}x{}",
+            size, MIN_BASE_INTERIOR, MIN_BASE_INTERIOR
+        ));
+    }
+    if size > MAX_BASE_SIZE {
+        return Err(format!("Base size must be at most {}", MAX_BASE_SIZE));
+    }
+    Ok(())
 }
 
 // =============================================================================
@@ -612,7 +1046,7 @@ fn step_generation() {
     let (mut births, mut deaths, mut survivors) = {
         benchmark!(VecAllocation);
         (
-            Vec::<(usize, usize)>::with_capacity(500),
+            Vec::<(usize, Option<usize>)>::with_capacity(500),
             Vec::<usize>::with_capacity(500),
             Vec::<usize>::with_capacity(15000),
         )
@@ -624,12 +1058,20 @@ fn step_generation() {
         compute_fates_into(&mut births, &mut deaths, &mut survivors);
     }
 
+    // Phase 1b: Age survivors and retire any past MAX_CELL_AGE
+    if CELL_DECAY_ENABLED.with(|d| *d.borrow()) {
+        apply_cell_decay(&births, &mut deaths, &mut survivors);
+    }
+
     // Phase 2: Apply changes
     {
         benchmark!(ApplyChanges);
         apply_changes(&births, &deaths, &survivors);
     }
 
+    // Phase 2b: Cull orphaned territory whose grace ran out
+    recheck_orphans();
+
     // Phase 3: Deallocate vectors (measured separately)
     {
         benchmark!(VecDeallocation);
@@ -639,13 +1081,78 @@ fn step_generation() {
     }
 
     // Increment generation
-    GENERATION.with(|gen| {
-        *gen.borrow_mut() += 1;
+    let generation = GENERATION.with(|gen| {
+        let mut gen = gen.borrow_mut();
+        *gen += 1;
+        *gen
+    });
+
+    record_generation_diff(generation);
+}
+
+/// Diff ALIVE against the previously recorded bitmap and push it onto the
+/// replay buffer. Comparing whole bitmaps (rather than this step's births and
+/// deaths) also captures placements, wipes and eliminations since last step.
+fn record_generation_diff(generation: u64) {
+    let words = ALIVE.with(|alive| {
+        DIFF_BASE.with(|base| {
+            let alive = alive.borrow();
+            let mut base = base.borrow_mut();
+            let mut words = Vec::new();
+            for (word_idx, (prev, &curr)) in base.iter_mut().zip(alive.iter()).enumerate() {
+                if *prev != curr {
+                    words.push((word_idx as u16, *prev ^ curr));
+                    *prev = curr;
+                }
+            }
+            words
+        })
+    });
+
+    RECENT_DIFFS.with(|diffs| {
+        let mut diffs = diffs.borrow_mut();
+        if diffs.len() == RECENT_DIFF_LIMIT {
+            diffs.pop_front();
+        }
+        diffs.push_back(GenDiff { generation, words });
+    });
+}
+
+/// Drop buffered diffs and rebase on the current bitmap (used when upgrading
+/// from a version that didn't persist the buffer)
+fn reset_recent_diffs() {
+    ALIVE.with(|alive| {
+        DIFF_BASE.with(|base| base.borrow_mut().copy_from_slice(&alive.borrow()[..]));
+    });
+    RECENT_DIFFS.with(|diffs| diffs.borrow_mut().clear());
+}
+
+/// Reset ages for births and deaths, bump survivors, and move survivors that
+/// reach MAX_CELL_AGE into `deaths`
+fn apply_cell_decay(births: &[(usize, Option<usize>)], deaths: &mut Vec<usize>, survivors: &mut Vec<usize>) {
+    CELL_AGE.with(|ages| {
+        let mut ages = ages.borrow_mut();
+        for &cell_idx in deaths.iter() {
+            ages[cell_idx] = 0;
+        }
+        for &(cell_idx, _) in births {
+            ages[cell_idx] = 0;
+        }
+        survivors.retain(|&cell_idx| {
+            ages[cell_idx] = ages[cell_idx].saturating_add(1);
+            if ages[cell_idx] >= MAX_CELL_AGE {
+                ages[cell_idx] = 0;
+                deaths.push(cell_idx);
+                false
+            } else {
+                true
+            }
+        });
     });
 }
 
 fn compute_fates_into(
-    births: &mut Vec<(usize, usize)>,
+    births: &mut Vec<(usize, Option<usize>)>,
     deaths: &mut Vec<usize>,
     survivors: &mut Vec<usize>,
 ) {
@@ -656,55 +1163,63 @@ fn compute_fates_into(
 
     POTENTIAL.with(|potential| {
         ALIVE.with(|alive| {
-            let potential = potential.borrow();
-            let alive = alive.borrow();
+            collect_fates(&potential.borrow(), &alive.borrow(), births, deaths, survivors);
+        })
+    });
+}
 
-            for word_idx in 0..TOTAL_WORDS {
-                let mut potential_word = potential[word_idx];
-                if potential_word == 0 {
-                    continue;
-                }
+/// Fate of every potential cell of `alive`, sorted into the three vectors
+fn collect_fates(
+    potential: &[u64; TOTAL_WORDS],
+    alive: &[u64; TOTAL_WORDS],
+    births: &mut Vec<(usize, Option<usize>)>,
+    deaths: &mut Vec<usize>,
+    survivors: &mut Vec<usize>,
+) {
+    for word_idx in 0..TOTAL_WORDS {
+        let mut potential_word = potential[word_idx];
+        if potential_word == 0 {
+            continue;
+        }
 
-                let row = word_idx / WORDS_PER_ROW;
-                let row_above = if row > 0 { word_idx - WORDS_PER_ROW } else { word_idx + TOTAL_WORDS - WORDS_PER_ROW };
-                let row_below = if row < GRID_SIZE as usize - 1 { word_idx + WORDS_PER_ROW } else { word_idx - TOTAL_WORDS + WORDS_PER_ROW };
-
-                // Load the 3 row words
-                let above = alive[row_above];
-                let same = alive[word_idx];
-                let below = alive[row_below];
-
-                // Adjacent words for edge bits
-                let left_above = alive[wrap_word_left(row_above)];
-                let left_same = alive[wrap_word_left(word_idx)];
-                let left_below = alive[wrap_word_left(row_below)];
-                let right_above = alive[wrap_word_right(row_above)];
-                let right_same = alive[wrap_word_right(word_idx)];
-                let right_below = alive[wrap_word_right(row_below)];
-
-                while potential_word != 0 {
-                    let bit_pos = potential_word.trailing_zeros() as usize;
-                    potential_word &= potential_word - 1;
-
-                    let cell_idx = word_idx * 64 + bit_pos;
-                    let fate = compute_cell_fate(
-                        bit_pos,
-                        above, same, below,
-                        left_above, left_same, left_below,
-                        right_above, right_same, right_below,
-                        cell_idx,
-                    );
-
-                    match fate {
-                        CellFate::Survives => survivors.push(cell_idx),
-                        CellFate::Birth(owner) => births.push((cell_idx, owner)),
-                        CellFate::Death => deaths.push(cell_idx),
-                        CellFate::StaysDead => {}
-                    }
-                }
+        let row = word_idx / WORDS_PER_ROW;
+        let row_above = if row > 0 { word_idx - WORDS_PER_ROW } else { word_idx + TOTAL_WORDS - WORDS_PER_ROW };
+        let row_below = if row < GRID_SIZE as usize - 1 { word_idx + WORDS_PER_ROW } else { word_idx + WORDS_PER_ROW - TOTAL_WORDS };
+
+        // Load the 3 row words
+        let above = alive[row_above];
+        let same = alive[word_idx];
+        let below = alive[row_below];
+
+        // Adjacent words for edge bits
+        let left_above = alive[wrap_word_left(row_above)];
+        let left_same = alive[wrap_word_left(word_idx)];
+        let left_below = alive[wrap_word_left(row_below)];
+        let right_above = alive[wrap_word_right(row_above)];
+        let right_same = alive[wrap_word_right(word_idx)];
+        let right_below = alive[wrap_word_right(row_below)];
+
+        while potential_word != 0 {
+            let bit_pos = potential_word.trailing_zeros() as usize;
+            potential_word &= potential_word - 1;
+
+            let cell_idx = word_idx * 64 + bit_pos;
+            let fate = compute_cell_fate(
+                bit_pos,
+                above, same, below,
+                left_above, left_same, left_below,
+                right_above, right_same, right_below,
+                cell_idx,
+            );
+
+            match fate {
+                CellFate::Survives => survivors.push(cell_idx),
+                CellFate::Birth(owner) => births.push((cell_idx, owner)),
+                CellFate::Death => deaths.push(cell_idx),
+                CellFate::StaysDead => {}
             }
-        })
-    });
+        }
+    }
 }
 
 /// Count neighbors using popcount (WASM i64.popcnt instruction)
@@ -821,10 +1336,7 @@ fn find_birth_owner(
     x: u16, y: u16,
     nw: u8, n: u8, ne: u8, w: u8, e: u8, sw: u8, s: u8, se: u8,
     cell_idx: usize,
-) -> usize {
-    let mut owner_counts = [0u8; MAX_PLAYERS];
-    let mut neutral_count = 0u8;
-
+) -> Option<usize> {
     // Get neighbor coordinates
     let neighbors = [
         (nw, (x.wrapping_sub(1) & 511, y.wrapping_sub(1) & 511)),
@@ -837,51 +1349,104 @@ fn find_birth_owner(
         (se, (x.wrapping_add(1) & 511, y.wrapping_add(1) & 511)),
     ];
 
+    let mut parents: ArrayVec<Option<usize>, 8> = ArrayVec::new();
     for (alive, (nx, ny)) in neighbors {
         if alive == 1 {
-            if let Some(owner) = find_owner(nx, ny) {
-                owner_counts[owner] += 1;
-            } else {
-                neutral_count += 1;
-            }
+            parents.push(find_owner(nx, ny));
+        }
+    }
+
+    let allies = ALLIANCES.with(|a| *a.borrow());
+    birth_owner(&parents, &allies, |n| tie_break_index(cell_idx, n))
+}
+
+/// Owner of a newborn cell, given the owners of its live parents in neighbor
+/// order (None = unowned) and the alliance table. `tie_pick(n)` chooses among
+/// n tied candidates. None when every parent is unowned: the birth stays neutral.
+/// Pure, so ownership rules can be tested in isolation.
+fn birth_owner(
+    parents: &[Option<usize>],
+    allies: &[Option<u8>; MAX_PLAYERS],
+    tie_pick: impl Fn(usize) -> usize,
+) -> Option<usize> {
+    let mut owner_counts = [0u8; MAX_PLAYERS];
+    let mut neutral_count = 0u8;
+
+    for parent in parents {
+        match parent {
+            Some(owner) => owner_counts[*owner] += 1,
+            None => neutral_count += 1,
+        }
+    }
+
+    // Allies count as one owner for the majority: each player's group count
+    // includes their partner's parents
+    let mut group_counts = owner_counts;
+    for (i, ally) in allies.iter().enumerate() {
+        if let Some(ally) = ally {
+            group_counts[i] += owner_counts[*ally as usize];
         }
     }
 
     // Find max count
-    let max_count = *owner_counts.iter().max().unwrap_or(&0);
+    let max_count = *group_counts.iter().max().unwrap_or(&0);
 
     // If neutral has more, return a "neutral birth" (we'll assign to first player with any)
     if neutral_count > max_count {
-        // Neutral birth - assign to first player found among parents
-        for (alive, (nx, ny)) in neighbors {
-            if alive == 1 {
-                if let Some(owner) = find_owner(nx, ny) {
-                    return owner;
-                }
-            }
-        }
-        return 0; // Fallback
+        // Neutral majority - assign to first player found among parents,
+        // or leave unowned when no parent has an owner
+        return parents.iter().flatten().copied().next();
     }
 
-    // Find candidates with max count
+    // Find candidate groups with max count, keyed by their lowest slot
     let mut candidates: ArrayVec<usize, MAX_PLAYERS> = ArrayVec::new();
-    for (i, &count) in owner_counts.iter().enumerate() {
-        if count == max_count && count > 0 {
+    for (i, &count) in group_counts.iter().enumerate() {
+        let is_group_lead = allies[i].is_none_or(|ally| i < ally as usize || owner_counts[ally as usize] == 0);
+        if count == max_count && owner_counts[i] > 0 && is_group_lead {
             candidates.push(i);
         }
     }
 
-    if candidates.len() == 1 {
+    let winner = if candidates.len() == 1 {
         candidates[0]
     } else if candidates.is_empty() {
-        0 // Neutral birth, shouldn't happen with alive parents
+        return None; // Neutral birth, shouldn't happen with alive parents
     } else {
-        // Tie-break using cell index
-        candidates[cell_idx % candidates.len()]
-    }
+        // Tie-break: hashed so no slot is favored along lattice lines
+        candidates[tie_pick(candidates.len())]
+    };
+
+    // Within an alliance, the birth goes to whichever member contributed more parents
+    let owner = match allies[winner] {
+        Some(ally) => {
+            let ally = ally as usize;
+            match owner_counts[winner].cmp(&owner_counts[ally]) {
+                std::cmp::Ordering::Greater => winner,
+                std::cmp::Ordering::Less => ally,
+                std::cmp::Ordering::Equal => [winner.min(ally), winner.max(ally)][tie_pick(2)],
+            }
+        }
+        None => winner,
+    };
+    Some(owner)
+}
+
+/// Deterministic, replayable pick in 0..n for a tied birth. Mixing the
+/// generation in means a cell contested repeatedly doesn't always go the same way.
+fn tie_break_index(cell_idx: usize, n: usize) -> usize {
+    let generation = GENERATION.with(|g| *g.borrow());
+    // cell_idx < 2^18, so (generation, cell_idx) packs without collisions
+    (splitmix64((generation << 18) ^ cell_idx as u64) % n as u64) as usize
+}
+
+fn splitmix64(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
 }
 
-fn apply_changes(births: &[(usize, usize)], deaths: &[usize], survivors: &[usize]) {
+fn apply_changes(births: &[(usize, Option<usize>)], deaths: &[usize], survivors: &[usize]) {
     // Clear NEXT_POTENTIAL
     NEXT_POTENTIAL.with(|np| {
         np.borrow_mut().fill(0);
@@ -909,7 +1474,7 @@ fn apply_changes(births: &[(usize, usize)], deaths: &[usize], survivors: &[usize
                 BASES.with(|bases| {
                     if bases.borrow()[owner].is_some() {
                         ZERO_CELLS_SINCE.with(|zcs| {
-                            zcs.borrow_mut()[owner] = Some(ic_cdk::api::time());
+                            zcs.borrow_mut()[owner] = Some(now_ns());
                         });
                     }
                 });
@@ -930,6 +1495,9 @@ fn apply_changes(births: &[(usize, usize)], deaths: &[usize], survivors: &[usize
             in_protection_zone(x, y)
         };
         if let Some(base_owner) = base_owner_opt {
+            let Some(new_owner) = new_owner else {
+                continue; // Neutral births can't grow inside a base zone
+            };
             if base_owner != new_owner {
                 // SIEGE! Birth prevented, transfer coins (capped at what defender has)
                 let mut eliminated = false;
@@ -941,6 +1509,14 @@ fn apply_changes(births: &[(usize, usize)], deaths: &[usize], survivors: &[usize
                             // Take up to SIEGE_DAMAGE, but not more than defender has
                             let damage = base.coins.min(SIEGE_DAMAGE);
                             base.coins -= damage;
+                            record_siege_event(SiegeEvent {
+                                generation: GENERATION.with(|g| *g.borrow()) + 1,
+                                victim_slot: base_owner as u8,
+                                attacker_slot: new_owner as u8,
+                                coins_transferred: damage,
+                                x,
+                                y,
+                            });
 
                             // Transfer coins to attacker's wallet
                             PLAYERS.with(|players| {
@@ -960,7 +1536,7 @@ fn apply_changes(births: &[(usize, usize)], deaths: &[usize], survivors: &[usize
                 });
 
                 if eliminated {
-                    eliminate_player(base_owner);
+                    eliminate_player(base_owner, EliminationReason::BaseDestroyed);
                 }
 
                 continue; // Birth prevented
@@ -969,7 +1545,7 @@ fn apply_changes(births: &[(usize, usize)], deaths: &[usize], survivors: &[usize
 
         // Check if territory changes (for disconnection check)
         if let Some(old_owner) = find_owner(x, y) {
-            if old_owner != new_owner {
+            if Some(old_owner) != new_owner {
                 territory_changes.affected_players |= 1 << old_owner;
                 if territory_changes.lost_cells[old_owner].len() < 64 {
                     territory_changes.lost_cells[old_owner].push((x, y));
@@ -980,6 +1556,10 @@ fn apply_changes(births: &[(usize, usize)], deaths: &[usize], survivors: &[usize
 
         // Normal birth
         set_alive_idx(cell_idx);
+        mark_with_neighbors_potential(cell_idx);
+        let Some(new_owner) = new_owner else {
+            continue; // Neutral birth: no territory or cell count
+        };
         set_territory(new_owner, x, y);
 
         // Update cell count
@@ -991,8 +1571,6 @@ fn apply_changes(births: &[(usize, usize)], deaths: &[usize], survivors: &[usize
         ZERO_CELLS_SINCE.with(|zcs| {
             zcs.borrow_mut()[new_owner] = None;
         });
-
-        mark_with_neighbors_potential(cell_idx);
     }
 
     // Apply survivors (just mark in NEXT_POTENTIAL)
@@ -1011,6 +1589,16 @@ fn apply_changes(births: &[(usize, usize)], deaths: &[usize], survivors: &[usize
     check_all_disconnections(&territory_changes);
 }
 
+fn record_siege_event(event: SiegeEvent) {
+    SIEGE_EVENTS.with(|events| {
+        let mut events = events.borrow_mut();
+        if events.len() == SIEGE_EVENT_LIMIT {
+            events.pop_front();
+        }
+        events.push_back(event);
+    });
+}
+
 // =============================================================================
 // DISCONNECTION ALGORITHM
 // =============================================================================
@@ -1057,7 +1645,11 @@ fn check_all_disconnections(changes: &TerritoryChanges) {
 
             if !unreached.is_empty() {
                 let disconnected = find_disconnected_components(&mut ws, player, &unreached);
-                apply_disconnection(player, &disconnected);
+                if DISCONNECT_GRACE_ENABLED.with(|g| *g.borrow()) {
+                    record_orphans(player, disconnected);
+                } else {
+                    apply_disconnection(player, &disconnected);
+                }
             }
         });
     }
@@ -1082,8 +1674,8 @@ fn bfs_find_unreached(
         .collect();
 
     // Seed BFS with base cells
-    for dy in 0..BASE_SIZE {
-        for dx in 0..BASE_SIZE {
+    for dy in 0..base.size() {
+        for dx in 0..base.size() {
             let x = base.x.wrapping_add(dx) & 511;
             let y = base.y.wrapping_add(dy) & 511;
 
@@ -1175,44 +1767,228 @@ fn find_disconnected_components(
     disconnected
 }
 
-fn apply_disconnection(player: usize, disconnected: &[(u16, u16)]) {
-    for &(x, y) in disconnected {
-        clear_territory(player, x, y);
-
-        let idx = coords_to_idx(x, y);
-
-        if is_alive_idx(idx) {
-            clear_alive_idx(idx);
-
-            CELL_COUNTS.with(|cc| {
-                let mut cc = cc.borrow_mut();
-                if cc[player] > 0 {
-                    cc[player] -= 1;
-                }
+/// Start the grace clock for newly cut-off cells (cells already orphaned keep
+/// their original clock)
+fn record_orphans(player: usize, disconnected: Vec<(u16, u16)>) {
+    ORPHANS.with(|orphans| {
+        let mut orphans = orphans.borrow_mut();
+        let known: HashSet<(u16, u16)> = orphans
+            .iter()
+            .filter(|o| o.player as usize == player)
+            .flat_map(|o| o.cells.iter().copied())
+            .collect();
+        let cells: Vec<(u16, u16)> = disconnected.into_iter().filter(|c| !known.contains(c)).collect();
+        if !cells.is_empty() {
+            orphans.push(OrphanedComponent {
+                player: player as u8,
+                since_generation: GENERATION.with(|g| *g.borrow()),
+                cells,
             });
-
-            mark_neighbors_potential(idx);
         }
-    }
-
-    // Check if player now has 0 cells
-    let count = CELL_COUNTS.with(|cc| cc.borrow()[player]);
-    if count == 0 {
-        BASES.with(|bases| {
-            if bases.borrow()[player].is_some() {
-                ZERO_CELLS_SINCE.with(|zcs| {
-                    zcs.borrow_mut()[player] = Some(ic_cdk::api::time());
-                });
-            }
-        });
-    }
+    });
+}
+
+/// Re-check every orphaned component against its base. Reconnected cells are
+/// forgiven; components still cut off past the grace window are culled along
+/// with any territory that has grown onto them.
+fn recheck_orphans() {
+    let mut orphans = ORPHANS.with(|o| std::mem::take(&mut *o.borrow_mut()));
+    if orphans.is_empty() {
+        return;
+    }
+    let generation = GENERATION.with(|g| *g.borrow());
+
+    for player in 0..MAX_PLAYERS {
+        if !orphans.iter().any(|o| o.player as usize == player) {
+            continue;
+        }
+        let Some(base) = BASES.with(|bases| bases.borrow()[player].clone()) else {
+            orphans.retain(|o| o.player as usize != player);
+            continue;
+        };
+
+        BFS_WORKSPACE.with(|ws| {
+            let mut ws = ws.borrow_mut();
+            ws.clear();
+
+            // Full BFS from the base: everything visited is connected
+            bfs_find_unreached(&mut ws, player, &base, &[]);
+
+            let mut expired: Vec<(u16, u16)> = Vec::new();
+            orphans.retain_mut(|o| {
+                if o.player as usize != player {
+                    return true;
+                }
+                o.cells.retain(|&(x, y)| player_owns(player, x, y) && !ws.is_visited(x, y));
+                if o.cells.is_empty() {
+                    return false;
+                }
+                if generation.saturating_sub(o.since_generation) >= DISCONNECT_GRACE_GENS {
+                    expired.extend_from_slice(&o.cells);
+                    return false;
+                }
+                true
+            });
+
+            if !expired.is_empty() {
+                let disconnected = find_disconnected_components(&mut ws, player, &expired);
+                apply_disconnection(player, &disconnected);
+            }
+        });
+    }
+
+    ORPHANS.with(|o| *o.borrow_mut() = orphans);
+}
+
+fn set_disconnect_grace_enabled(enabled: bool) {
+    DISCONNECT_GRACE_ENABLED.with(|g| *g.borrow_mut() = enabled);
+}
+
+fn apply_disconnection(player: usize, disconnected: &[(u16, u16)]) {
+    for &(x, y) in disconnected {
+        clear_territory(player, x, y);
+
+        let idx = coords_to_idx(x, y);
+
+        if is_alive_idx(idx) {
+            clear_alive_idx(idx);
+
+            CELL_COUNTS.with(|cc| {
+                let mut cc = cc.borrow_mut();
+                if cc[player] > 0 {
+                    cc[player] -= 1;
+                }
+            });
+
+            mark_neighbors_potential(idx);
+        }
+    }
+
+    // Check if player now has 0 cells
+    let count = CELL_COUNTS.with(|cc| cc.borrow()[player]);
+    if count == 0 {
+        BASES.with(|bases| {
+            if bases.borrow()[player].is_some() {
+                ZERO_CELLS_SINCE.with(|zcs| {
+                    zcs.borrow_mut()[player] = Some(now_ns());
+                });
+            }
+        });
+    }
+}
+
+/// Territory cells whose loss would cut other territory off from the base:
+/// articulation points of the orthogonal territory graph, with the whole base
+/// footprint contracted into a single root node. Territory already unreachable
+/// from the base is ignored. Sorted by (y, x).
+fn fragile_cells(player: usize) -> Vec<(u16, u16)> {
+    let Some(base) = BASES.with(|bases| bases.borrow()[player].clone()) else {
+        return Vec::new();
+    };
+
+    // Node 0 is the base; every other owned cell gets its own node
+    let mut coords: Vec<(u16, u16)> = vec![(base.x, base.y)];
+    let mut node_of: HashMap<(u16, u16), usize> = HashMap::new();
+    TERRITORY.with(|territory| {
+        let territory = territory.borrow();
+        let pt = &territory[player];
+        let mut mask = pt.chunk_mask;
+        for chunk in pt.chunks.iter() {
+            let chunk_idx = mask.trailing_zeros() as usize;
+            mask &= mask - 1;
+            let chunk_base_x = (chunk_idx % CHUNKS_PER_ROW) * 64;
+            let chunk_base_y = (chunk_idx / CHUNKS_PER_ROW) * 64;
+
+            for (local_y, &row) in chunk.iter().enumerate() {
+                let mut word = row;
+                while word != 0 {
+                    let local_x = word.trailing_zeros() as usize;
+                    word &= word - 1;
+
+                    let x = (chunk_base_x + local_x) as u16;
+                    let y = (chunk_base_y + local_y) as u16;
+                    let node = if is_in_base(&base, x, y) {
+                        0
+                    } else {
+                        coords.push((x, y));
+                        coords.len() - 1
+                    };
+                    node_of.insert((x, y), node);
+                }
+            }
+        }
+    });
+
+    let mut adjacency: Vec<Vec<usize>> = vec![Vec::new(); coords.len()];
+    for (&(x, y), &node) in &node_of {
+        for neighbor in orthogonal_neighbors(x, y) {
+            if let Some(&other) = node_of.get(&neighbor) {
+                if other != node {
+                    adjacency[node].push(other);
+                }
+            }
+        }
+    }
+
+    // Iterative Tarjan DFS from the base: a non-root node u is a cut vertex
+    // when some DFS child v cannot reach above u (low[v] >= disc[u])
+    let mut disc = vec![0u32; coords.len()];
+    let mut low = vec![0u32; coords.len()];
+    let mut fragile = vec![false; coords.len()];
+    let mut timer = 1;
+    disc[0] = timer;
+    low[0] = timer;
+    let mut stack: Vec<(usize, usize, usize)> = vec![(0, usize::MAX, 0)]; // (node, parent, next edge)
+
+    while let Some(top) = stack.last_mut() {
+        let (v, parent) = (top.0, top.1);
+        if top.2 < adjacency[v].len() {
+            let w = adjacency[v][top.2];
+            top.2 += 1;
+            if w == parent {
+                continue;
+            }
+            if disc[w] == 0 {
+                timer += 1;
+                disc[w] = timer;
+                low[w] = timer;
+                stack.push((w, v, 0));
+            } else {
+                low[v] = low[v].min(disc[w]);
+            }
+        } else {
+            stack.pop();
+            if let Some(&(u, _, _)) = stack.last() {
+                low[u] = low[u].min(low[v]);
+                if u != 0 && low[v] >= disc[u] {
+                    fragile[u] = true;
+                }
+            }
+        }
+    }
+
+    let mut out: Vec<(u16, u16)> = (1..coords.len())
+        .filter(|&node| fragile[node])
+        .map(|node| coords[node])
+        .collect();
+    out.sort_by_key(|&(x, y)| (y, x));
+    out
 }
 
 // =============================================================================
 // GAME MECHANICS
 // =============================================================================
 
-fn eliminate_player(player: usize) {
+fn eliminate_player(player: usize, reason: EliminationReason) {
+    if let Some(principal) = PLAYERS.with(|p| p.borrow()[player]) {
+        record_elimination(EliminationRecord {
+            slot: player as u8,
+            principal,
+            generation: GENERATION.with(|g| *g.borrow()),
+            reason,
+        });
+    }
+
     // 1. Kill ALL player's alive cells AND clear OWNER entries
     //    (iterate via territory bitmap, do both in single pass)
     TERRITORY.with(|territory| {
@@ -1275,69 +2051,147 @@ fn eliminate_player(player: usize) {
     ZERO_CELLS_SINCE.with(|zcs| {
         zcs.borrow_mut()[player] = None;
     });
+
+    // 4. Dissolve any alliance or proposal involving the slot
+    clear_alliances_for(player);
+    LAST_RELOCATE_NS.with(|lr| lr.borrow_mut()[player] = None);
+    ORPHANS.with(|o| o.borrow_mut().retain(|o| o.player as usize != player));
+    PROFILES.with(|pr| pr.borrow_mut()[player] = None);
+}
+
+fn record_elimination(record: EliminationRecord) {
+    ELIMINATIONS.with(|log| {
+        let mut log = log.borrow_mut();
+        if log.len() == ELIMINATION_LOG_LIMIT {
+            log.pop_front();
+        }
+        log.push_back(record);
+    });
 }
 
 fn wipe_quadrant(quadrant: u8) {
     benchmark!(WipeQuadrant);
 
-    let (x_start, y_start, _, _) = quadrant_bounds(quadrant);
+    if wipe_frozen(quadrant) {
+        ic_cdk::println!("wipe_quadrant: skipped quadrant {} (fresh placements)", quadrant);
+        return;
+    }
+
+    let exemption = WIPE_EXEMPTION.with(|w| *w.borrow());
 
     ALIVE.with(|alive| {
         let mut alive = alive.borrow_mut();
 
-        for row_offset in 0..QUADRANT_SIZE {
-            let y = y_start + row_offset;
-            let word_row_base = (y as usize) * WORDS_PER_ROW;
-            let word_col_start = (x_start / 64) as usize;
+        for (word_idx, word_x, y) in quadrant_words(quadrant) {
+            let mut alive_word = alive[word_idx];
+
+            if alive_word == 0 {
+                continue;
+            }
+
+            let mut kept = 0u64;
+            while alive_word != 0 {
+                let bit_pos = alive_word.trailing_zeros() as usize;
+                alive_word &= alive_word - 1;
 
-            for word_offset in 0..2 {
-                let word_idx = word_row_base + word_col_start + word_offset;
-                let mut alive_word = alive[word_idx];
+                let x = word_x + bit_pos as u16;
+                let idx = coords_to_idx(x, y);
 
-                if alive_word == 0 {
+                if is_wipe_exempt(exemption, x, y) {
+                    kept |= 1u64 << bit_pos;
                     continue;
                 }
 
-                while alive_word != 0 {
-                    let bit_pos = alive_word.trailing_zeros() as usize;
-                    alive_word &= alive_word - 1;
+                release_wiped_cell(x, y, idx);
+            }
 
-                    let x = ((word_col_start + word_offset) * 64 + bit_pos) as u16;
-                    let idx = coords_to_idx(x, y);
+            alive[word_idx] = kept;
+        }
+    });
+}
 
-                    if let Some(owner) = find_owner(x, y) {
-                        CELL_COUNTS.with(|cc| {
-                            let mut cc = cc.borrow_mut();
-                            if cc[owner] > 0 {
-                                cc[owner] -= 1;
-                            }
+/// Remember that `quadrant` just received placements
+fn mark_quadrant_placed(quadrant: u8) {
+    let generation = GENERATION.with(|g| *g.borrow());
+    QUADRANT_PLACED_GEN.with(|p| p.borrow_mut()[quadrant as usize] = Some(generation));
+}
 
-                            if cc[owner] == 0 {
-                                BASES.with(|bases| {
-                                    if bases.borrow()[owner].is_some() {
-                                        ZERO_CELLS_SINCE.with(|zcs| {
-                                            zcs.borrow_mut()[owner] = Some(ic_cdk::api::time());
-                                        });
-                                    }
-                                });
-                            }
+/// Placements landed in `quadrant` within the last WIPE_FREEZE_GENS generations
+fn wipe_frozen(quadrant: u8) -> bool {
+    let freeze = WIPE_FREEZE_GENS.with(|f| *f.borrow());
+    let generation = GENERATION.with(|g| *g.borrow());
+    let placed = QUADRANT_PLACED_GEN.with(|p| p.borrow()[quadrant as usize]);
+    freeze > 0 && placed.is_some_and(|placed| generation.saturating_sub(placed) < freeze)
+}
+
+fn set_wipe_freeze(generations: u64) -> Result<(), String> {
+    if generations > MAX_WIPE_FREEZE_GENS {
+        return Err(format!("Freeze must be at most {} generations", MAX_WIPE_FREEZE_GENS));
+    }
+    WIPE_FREEZE_GENS.with(|f| *f.borrow_mut() = generations);
+    Ok(())
+}
+
+/// Bookkeeping for a cell a wipe has just cleared from ALIVE
+fn release_wiped_cell(x: u16, y: u16, idx: usize) {
+    if let Some(owner) = find_owner(x, y) {
+        CELL_COUNTS.with(|cc| {
+            let mut cc = cc.borrow_mut();
+            if cc[owner] > 0 {
+                cc[owner] -= 1;
+            }
+
+            if cc[owner] == 0 {
+                BASES.with(|bases| {
+                    if bases.borrow()[owner].is_some() {
+                        ZERO_CELLS_SINCE.with(|zcs| {
+                            zcs.borrow_mut()[owner] = Some(now_ns());
                         });
                     }
+                });
+            }
+        });
+    }
 
-                    mark_neighbors_potential(idx);
-                }
+    mark_neighbors_potential(idx);
+}
 
-                alive[word_idx] = 0;
-            }
+fn is_wipe_exempt(exemption: WipeExemption, x: u16, y: u16) -> bool {
+    match exemption {
+        WipeExemption::None => false,
+        WipeExemption::Bases => in_protection_zone(x, y).is_some(),
+        WipeExemption::BasesAndTerritory => {
+            in_protection_zone(x, y).is_some()
+                || find_owner(x, y).is_some_and(|owner| BASES.with(|b| b.borrow()[owner].is_some()))
         }
-    });
+    }
 }
 
 fn run_wipe_if_needed() {
-    let now = ic_cdk::api::time();
+    let now = now_ns();
+
+    if INDEPENDENT_WIPES.with(|iw| *iw.borrow()) {
+        for quadrant in 0..TOTAL_QUADRANTS {
+            let due = QUADRANT_WIPES.with(|qw| {
+                let mut qw = qw.borrow_mut();
+                let q = &mut qw[quadrant as usize];
+                if now.saturating_sub(q.last_wipe_ns) >= q.interval_ns {
+                    q.last_wipe_ns = now;
+                    true
+                } else {
+                    false
+                }
+            });
+            if due {
+                wipe_quadrant(quadrant);
+            }
+        }
+        return;
+    }
+
     let last_wipe = LAST_WIPE_NS.with(|lw| *lw.borrow());
 
-    if now - last_wipe >= WIPE_INTERVAL_NS {
+    if now - last_wipe >= wipe_interval_ns() {
         let quadrant = NEXT_WIPE_QUADRANT.with(|q| *q.borrow());
         wipe_quadrant(quadrant);
 
@@ -1350,102 +2204,426 @@ fn run_wipe_if_needed() {
     }
 }
 
+/// Next quadrant to be wiped and seconds until it happens
+fn next_wipe() -> (u8, u64) {
+    let now = now_ns();
+
+    if INDEPENDENT_WIPES.with(|iw| *iw.borrow()) {
+        return QUADRANT_WIPES.with(|qw| {
+            qw.borrow()
+                .iter()
+                .enumerate()
+                .map(|(q, w)| (q as u8, w.interval_ns.saturating_sub(now.saturating_sub(w.last_wipe_ns))))
+                .min_by_key(|&(_, remaining)| remaining)
+                .map(|(q, remaining)| (q, remaining / 1_000_000_000))
+                .unwrap_or((0, 0))
+        });
+    }
+
+    let next_quadrant = NEXT_WIPE_QUADRANT.with(|q| *q.borrow());
+    let last_wipe = LAST_WIPE_NS.with(|lw| *lw.borrow());
+    let elapsed = now.saturating_sub(last_wipe);
+    (next_quadrant, wipe_interval_ns().saturating_sub(elapsed) / 1_000_000_000)
+}
+
+fn wipe_interval_ns() -> u64 {
+    WIPE_INTERVAL.with(|w| *w.borrow())
+}
+
+fn grace_period_ns() -> u64 {
+    GRACE_PERIOD.with(|g| *g.borrow())
+}
+
+/// Global rotation interval, clamped to MIN_WIPE_INTERVAL_NS
+fn set_wipe_interval_secs(seconds: u64) {
+    let interval_ns = seconds.saturating_mul(1_000_000_000).max(MIN_WIPE_INTERVAL_NS);
+    WIPE_INTERVAL.with(|w| *w.borrow_mut() = interval_ns);
+}
+
+/// Clamped to 1..=MAX_GENERATIONS_PER_TICK generations every
+/// MIN_TICK_INTERVAL_MS..=MAX_TICK_INTERVAL_MS. Takes effect on the next start_timer.
+fn set_tick_speed_values(generations_per_tick: u32, tick_interval_ms: u64) {
+    TICK_GENERATIONS.with(|g| *g.borrow_mut() = generations_per_tick.clamp(1, MAX_GENERATIONS_PER_TICK));
+    TICK_INTERVAL.with(|t| *t.borrow_mut() = tick_interval_ms.clamp(MIN_TICK_INTERVAL_MS, MAX_TICK_INTERVAL_MS));
+}
+
+fn set_grace_period_secs(seconds: u64) {
+    GRACE_PERIOD.with(|g| *g.borrow_mut() = seconds.saturating_mul(1_000_000_000));
+}
+
+/// Switch between the global rotation and per-quadrant timers. Enabling
+/// restarts every quadrant's clock so nothing is wiped immediately.
+fn set_independent_wipes_enabled(enabled: bool) {
+    if enabled {
+        let now = now_ns();
+        QUADRANT_WIPES.with(|qw| qw.borrow_mut().iter_mut().for_each(|q| q.last_wipe_ns = now));
+    } else {
+        LAST_WIPE_NS.with(|lw| *lw.borrow_mut() = now_ns());
+    }
+    INDEPENDENT_WIPES.with(|iw| *iw.borrow_mut() = enabled);
+}
+
+fn set_quadrant_wipe_interval_ns(quadrant: u8, interval_ns: u64) -> Result<(), String> {
+    if quadrant >= TOTAL_QUADRANTS {
+        return Err(format!("Invalid quadrant {} (max {})", quadrant, TOTAL_QUADRANTS - 1));
+    }
+    if interval_ns < MIN_QUADRANT_WIPE_INTERVAL_NS {
+        return Err(format!("Interval must be at least {} seconds", MIN_QUADRANT_WIPE_INTERVAL_NS / 1_000_000_000));
+    }
+    QUADRANT_WIPES.with(|qw| qw.borrow_mut()[quadrant as usize].interval_ns = interval_ns);
+    Ok(())
+}
+
 fn check_grace_periods() {
-    let now = ic_cdk::api::time();
+    let now = now_ns();
 
     for player in 0..MAX_PLAYERS {
         let zero_since = ZERO_CELLS_SINCE.with(|zcs| zcs.borrow()[player]);
 
         if let Some(since) = zero_since {
-            if now - since >= GRACE_PERIOD_NS {
+            if now - since >= grace_period_ns() {
                 let has_base = BASES.with(|bases| bases.borrow()[player].is_some());
                 if has_base {
-                    eliminate_player(player);
+                    eliminate_player(player, EliminationReason::GraceExpired);
                 }
             }
         }
     }
 }
 
+/// Clear the grid and refill it with a reproducible neutral soup: each cell
+/// is alive with probability density_pct / 100, decided by splitmix64 of the
+/// seed and cell index. Only allowed with no one seated, so no bases,
+/// territory or coins are destroyed.
+fn reset_with_seed_internal(seed: u64, density_pct: u8) -> Result<(), String> {
+    if density_pct > MAX_SEED_DENSITY_PCT {
+        return Err(format!("Density must be at most {}%", MAX_SEED_DENSITY_PCT));
+    }
+    if PLAYERS.with(|p| p.borrow().iter().any(|p| p.is_some())) {
+        return Err("Players are still in the game".to_string());
+    }
+
+    ALIVE.with(|alive| {
+        let mut alive = alive.borrow_mut();
+        for (word_idx, word) in alive.iter_mut().enumerate() {
+            *word = 0;
+            for bit in 0..64 {
+                let idx = (word_idx * 64 + bit) as u64;
+                if splitmix64(seed.wrapping_add(idx)) % 100 < density_pct as u64 {
+                    *word |= 1u64 << bit;
+                }
+            }
+        }
+    });
+    OWNER.with(|o| o.borrow_mut().fill(255));
+    TERRITORY.with(|t| *t.borrow_mut() = Default::default());
+    CELL_AGE.with(|ages| ages.borrow_mut().fill(0));
+    ORPHANS.with(|o| o.borrow_mut().clear());
+
+    rebuild_potential_from_alive();
+    reset_recent_diffs();
+    LAST_ACTIVITY_NS.with(|la| *la.borrow_mut() = now_ns());
+    Ok(())
+}
+
 // =============================================================================
-// TICK ORCHESTRATION
+// SUDDEN DEATH ARENA
 // =============================================================================
 
-fn tick() {
-    benchmark!(Tick);
+/// Cells cut from each edge (0 when sudden death is off)
+fn arena_inset() -> u16 {
+    ARENA.with(|a| a.borrow().map_or(0, |a| a.inset))
+}
 
-    let running = IS_RUNNING.with(|r| *r.borrow());
-    if !running {
-        return;
-    }
+fn in_arena(x: u16, y: u16) -> bool {
+    let inset = arena_inset();
+    let playable = inset..GRID_SIZE - inset;
+    playable.contains(&x) && playable.contains(&y)
+}
 
-    // OPTIMIZATION: Check if there are any alive cells or potential cells
-    // If not, skip expensive generation computation entirely
-    let has_activity = POTENTIAL.with(|p| {
-        p.borrow().iter().any(|&w| w != 0)
+/// Whole base footprint inside the arena. Once the arena has shrunk a
+/// footprint can't wrap around the grid edge and still fit.
+fn base_in_arena(x: u16, y: u16, size: u16) -> bool {
+    let inset = arena_inset();
+    let limit = GRID_SIZE - inset;
+    inset == 0 || (x >= inset && y >= inset && x + size <= limit && y + size <= limit)
+}
+
+/// Enabling restarts from the full grid with a fresh shrink clock;
+/// disabling reopens the whole grid.
+fn set_sudden_death_enabled(enabled: bool) {
+    let arena = enabled.then(|| Arena { inset: 0, last_shrink_ns: now_ns() });
+    ARENA.with(|a| *a.borrow_mut() = arena);
+}
+
+/// Shrink the arena by one ring when due. Bases caught outside are
+/// eliminated, and every live cell outside is killed - on every tick, so
+/// births spilling over the edge don't survive either.
+fn run_shrink_if_needed() {
+    let now = now_ns();
+    let shrunk = ARENA.with(|a| {
+        let mut a = a.borrow_mut();
+        let arena = a.as_mut()?;
+        let smallest = GRID_SIZE - 2 * (arena.inset + SHRINK_STEP) < MIN_ARENA_SIZE;
+        if smallest || now.saturating_sub(arena.last_shrink_ns) < SHRINK_INTERVAL_NS {
+            return Some(false);
+        }
+        arena.inset += SHRINK_STEP;
+        arena.last_shrink_ns = now;
+        Some(true)
     });
 
-    if has_activity {
-        // Run 10 generations
-        for _ in 0..GENERATIONS_PER_TICK {
-            step_generation();
+    match shrunk {
+        None => return,
+        Some(true) => {
+            for player in 0..MAX_PLAYERS {
+                let outside = BASES.with(|b| {
+                    b.borrow()[player].as_ref().is_some_and(|base| !base_in_arena(base.x, base.y, base.size()))
+                });
+                if outside {
+                    eliminate_player(player, EliminationReason::OutsideArena);
+                }
+            }
         }
-    } else {
-        // Just increment generation counter (no computation needed)
-        GENERATION.with(|gen| {
-            *gen.borrow_mut() += GENERATIONS_PER_TICK as u64;
-        });
+        Some(false) => {}
     }
 
-    // Check quadrant wipe timer (still needed even when idle)
-    run_wipe_if_needed();
-
-    // Check grace periods
-    check_grace_periods();
+    wipe_outside_arena();
+}
 
-    // Stop timer if board is completely empty (saves cycles)
-    let board_empty = ALIVE.with(|a| a.borrow().iter().all(|&w| w == 0));
-    if board_empty {
-        stop_timer();
+/// Force-kill every live cell outside the arena (ignores wipe exemptions)
+fn wipe_outside_arena() {
+    let inset = arena_inset();
+    if inset == 0 {
         return;
     }
 
-    // Freeze if no player activity for 30 minutes (saves cycles on straggler gliders)
-    let last_activity = LAST_ACTIVITY_NS.with(|la| *la.borrow());
-    let idle_time = ic_cdk::api::time().saturating_sub(last_activity);
-    if idle_time >= IDLE_FREEZE_NS {
-        stop_timer();
-    }
-}
+    ALIVE.with(|alive| {
+        let mut alive = alive.borrow_mut();
 
-fn start_timer() {
-    let timer_id = ic_cdk_timers::set_timer_interval(
-        Duration::from_millis(TICK_INTERVAL_MS),
-        || async { tick() },
-    );
-    TIMER_ID.with(|t| {
-        *t.borrow_mut() = Some(timer_id);
-    });
-}
+        for word_idx in 0..TOTAL_WORDS {
+            let mut outside = alive[word_idx] & !arena_word_mask(word_idx, inset);
+            if outside == 0 {
+                continue;
+            }
+            alive[word_idx] &= !outside;
 
-fn stop_timer() {
-    TIMER_ID.with(|t| {
-        if let Some(id) = t.borrow_mut().take() {
-            ic_cdk_timers::clear_timer(id);
+            while outside != 0 {
+                let bit_pos = outside.trailing_zeros() as usize;
+                outside &= outside - 1;
+
+                let idx = (word_idx << 6) | bit_pos;
+                let (x, y) = idx_to_coords(idx);
+                release_wiped_cell(x, y, idx);
+            }
         }
     });
 }
 
-fn is_timer_running() -> bool {
-    TIMER_ID.with(|t| t.borrow().is_some())
+/// Bits of one ALIVE word that lie inside the arena
+fn arena_word_mask(word_idx: usize, inset: u16) -> u64 {
+    let limit = (GRID_SIZE - inset) as usize;
+    let inset = inset as usize;
+    let y = word_idx / WORDS_PER_ROW;
+    if y < inset || y >= limit {
+        return 0;
+    }
+
+    let word_x = (word_idx % WORDS_PER_ROW) * 64;
+    let lo = inset.saturating_sub(word_x).min(64);
+    let hi = limit.saturating_sub(word_x).min(64);
+    if hi <= lo {
+        return 0;
+    }
+    let below_hi = if hi == 64 { u64::MAX } else { (1u64 << hi) - 1 };
+    below_hi & !((1u64 << lo) - 1)
+}
+
+fn arena_info() -> Option<ArenaInfo> {
+    let arena = ARENA.with(|a| *a.borrow())?;
+    let fully_shrunk = GRID_SIZE - 2 * (arena.inset + SHRINK_STEP) < MIN_ARENA_SIZE;
+    let seconds_until_shrink = if fully_shrunk {
+        0
+    } else {
+        SHRINK_INTERVAL_NS.saturating_sub(now_ns().saturating_sub(arena.last_shrink_ns)) / 1_000_000_000
+    };
+    Some(ArenaInfo { min: arena.inset, max: GRID_SIZE - arena.inset, seconds_until_shrink })
+}
+
+// =============================================================================
+// ALLIANCES
+// =============================================================================
+
+/// Record `player`'s offer to ally with `target`. Replaces any earlier offer.
+fn propose_alliance_for(player: usize, target: usize) -> Result<(), String> {
+    if target >= MAX_PLAYERS {
+        return Err(format!("Invalid slot: {}", target));
+    }
+    if target == player {
+        return Err("Cannot ally with yourself".to_string());
+    }
+    if PLAYERS.with(|p| p.borrow()[target].is_none()) {
+        return Err("Slot is empty".to_string());
+    }
+    ALLIANCES.with(|a| {
+        let a = a.borrow();
+        if a[player].is_some() {
+            return Err("Already in an alliance".to_string());
+        }
+        if a[target].is_some() {
+            return Err("Target is already in an alliance".to_string());
+        }
+        Ok(())
+    })?;
+
+    ALLIANCE_PROPOSALS.with(|p| p.borrow_mut()[player] = Some(target as u8));
+    Ok(())
+}
+
+/// Accept a pending offer from `proposer`, allying the two slots.
+fn accept_alliance_for(player: usize, proposer: usize) -> Result<(), String> {
+    if proposer >= MAX_PLAYERS {
+        return Err(format!("Invalid slot: {}", proposer));
+    }
+    if ALLIANCE_PROPOSALS.with(|p| p.borrow()[proposer]) != Some(player as u8) {
+        return Err("No pending proposal from that slot".to_string());
+    }
+    ALLIANCES.with(|a| {
+        let mut a = a.borrow_mut();
+        if a[player].is_some() || a[proposer].is_some() {
+            return Err("Already in an alliance".to_string());
+        }
+        a[player] = Some(proposer as u8);
+        a[proposer] = Some(player as u8);
+        Ok(())
+    })?;
+
+    ALLIANCE_PROPOSALS.with(|p| {
+        let mut p = p.borrow_mut();
+        p[player] = None;
+        p[proposer] = None;
+    });
+    Ok(())
+}
+
+fn break_alliance_for(player: usize) -> Result<(), String> {
+    let ally = ALLIANCES.with(|a| a.borrow_mut()[player].take())
+        .ok_or_else(|| "Not in an alliance".to_string())?;
+    ALLIANCES.with(|a| a.borrow_mut()[ally as usize] = None);
+    Ok(())
+}
+
+/// Drop the slot's alliance and any proposals made by or to it
+fn clear_alliances_for(player: usize) {
+    let _ = break_alliance_for(player);
+    ALLIANCE_PROPOSALS.with(|p| {
+        let mut p = p.borrow_mut();
+        p[player] = None;
+        for proposal in p.iter_mut() {
+            if *proposal == Some(player as u8) {
+                *proposal = None;
+            }
+        }
+    });
+}
+
+// =============================================================================
+// TICK ORCHESTRATION
+// =============================================================================
+
+fn tick() {
+    benchmark!(Tick);
+
+    let running = IS_RUNNING.with(|r| *r.borrow());
+    if !running {
+        return;
+    }
+
+    // OPTIMIZATION: Check if there are any alive cells or potential cells
+    // If not, skip expensive generation computation entirely
+    let has_activity = POTENTIAL.with(|p| {
+        p.borrow().iter().any(|&w| w != 0)
+    });
+
+    let generations = TICK_GENERATIONS.with(|g| *g.borrow());
+    if has_activity {
+        for _ in 0..generations {
+            step_generation();
+        }
+    } else {
+        // Nothing to compute, but each skipped generation still records its
+        // (empty) diff so replay clients don't see a gap and resync
+        for _ in 0..generations {
+            let generation = GENERATION.with(|gen| {
+                let mut gen = gen.borrow_mut();
+                *gen += 1;
+                *gen
+            });
+            record_generation_diff(generation);
+        }
+    }
+
+    // Check quadrant wipe timer (still needed even when idle)
+    run_wipe_if_needed();
+
+    // Shrink the sudden-death arena and clear anything outside it
+    run_shrink_if_needed();
+
+    // Check grace periods
+    check_grace_periods();
+
+    // Stop timer if board is completely empty (saves cycles)
+    let board_empty = ALIVE.with(|a| a.borrow().iter().all(|&w| w == 0));
+    if board_empty {
+        stop_timer();
+        return;
+    }
+
+    // Freeze if no player activity for 30 minutes (saves cycles on straggler gliders)
+    let last_activity = LAST_ACTIVITY_NS.with(|la| *la.borrow());
+    let idle_time = now_ns().saturating_sub(last_activity);
+    if idle_time >= IDLE_FREEZE_NS {
+        stop_timer();
+    }
+}
+
+fn start_timer() {
+    let timer_id = ic_cdk_timers::set_timer_interval(
+        Duration::from_millis(TICK_INTERVAL.with(|t| *t.borrow())),
+        || async { tick() },
+    );
+    TIMER_ID.with(|t| {
+        *t.borrow_mut() = Some(timer_id);
+    });
+}
+
+fn stop_timer() {
+    TIMER_ID.with(|t| {
+        if let Some(id) = t.borrow_mut().take() {
+            ic_cdk_timers::clear_timer(id);
+        }
+    });
+}
+
+fn is_timer_running() -> bool {
+    TIMER_ID.with(|t| t.borrow().is_some())
 }
 
 // =============================================================================
 // UPDATE FUNCTIONS (PLAYER ACTIONS)
 // =============================================================================
 
+/// Reject player mutations while the canister is in read-only mode
+fn ensure_writable() -> Result<(), String> {
+    if READONLY.with(|r| *r.borrow()) {
+        return Err("Server in read-only mode".to_string());
+    }
+    Ok(())
+}
+
 #[ic_cdk::update]
 fn faucet() -> Result<u64, String> {
+    ensure_writable()?;
     let caller = ic_cdk::api::msg_caller();
 
     if caller == Principal::anonymous() {
@@ -1460,18 +2638,132 @@ fn faucet() -> Result<u64, String> {
     })
 }
 
+/// Watch the game without taking a slot. Lets the frontend tell players from
+/// watchers; idempotent, and joining later drops the registration.
+#[ic_cdk::update]
+fn register_spectator() -> Result<(), String> {
+    ensure_writable()?;
+    register_spectator_for(ic_cdk::api::msg_caller())
+}
+
+fn register_spectator_for(caller: Principal) -> Result<(), String> {
+    if caller == Principal::anonymous() {
+        return Err("Must be authenticated".to_string());
+    }
+    if find_player_slot(caller).is_some() {
+        return Err("Already playing".to_string());
+    }
+    SPECTATORS.with(|s| {
+        let mut spectators = s.borrow_mut();
+        if !spectators.contains(&caller) && spectators.len() >= MAX_SPECTATORS {
+            return Err("Spectator list is full".to_string());
+        }
+        spectators.insert(caller);
+        Ok(())
+    })
+}
+
+/// Set the caller's display name (at most MAX_DISPLAY_NAME_LEN characters,
+/// no control characters) and packed 0xRRGGBB color, shown in SlotInfo
+#[ic_cdk::update]
+fn set_profile(name: String, color: u32) -> Result<(), String> {
+    ensure_writable()?;
+    set_profile_for(ic_cdk::api::msg_caller(), name, color)
+}
+
+fn set_profile_for(caller: Principal, name: String, color: u32) -> Result<(), String> {
+    let slot = find_player_slot(caller).ok_or("Not in game")?;
+    let name = name.trim();
+    if name.is_empty() || name.chars().count() > MAX_DISPLAY_NAME_LEN {
+        return Err(format!("Name must be 1 to {} characters", MAX_DISPLAY_NAME_LEN));
+    }
+    if name.chars().any(char::is_control) {
+        return Err("Name contains control characters".to_string());
+    }
+    if color > MAX_PROFILE_COLOR {
+        return Err("Color must be packed 0xRRGGBB".to_string());
+    }
+    PROFILES.with(|pr| {
+        pr.borrow_mut()[slot] = Some(PlayerProfile { display_name: name.to_string(), color });
+    });
+    Ok(())
+}
+
+#[ic_cdk::query]
+fn spectator_count() -> u32 {
+    SPECTATORS.with(|s| s.borrow().len() as u32)
+}
+
+/// Move wallet coins to another principal; returns the caller's new balance
+#[ic_cdk::update]
+fn transfer_coins(to: Principal, amount: u64) -> Result<u64, String> {
+    ensure_writable()?;
+    transfer_coins_for(ic_cdk::api::msg_caller(), to, amount)
+}
+
+fn transfer_coins_for(caller: Principal, to: Principal, amount: u64) -> Result<u64, String> {
+    if amount == 0 {
+        return Err("Amount must be greater than zero".to_string());
+    }
+    if to == caller {
+        return Err("Cannot transfer to yourself".to_string());
+    }
+    if to == Principal::anonymous() {
+        return Err("Cannot transfer to the anonymous principal".to_string());
+    }
+
+    WALLETS.with(|wallets| {
+        let mut wallets = wallets.borrow_mut();
+        let balance = wallets.get(&caller).copied().unwrap_or(0);
+        if balance < amount {
+            return Err(format!("Insufficient balance: have {}, need {}", balance, amount));
+        }
+        let credited = wallets
+            .get(&to)
+            .copied()
+            .unwrap_or(0)
+            .checked_add(amount)
+            .ok_or("Recipient balance would overflow")?;
+        wallets.insert(caller, balance - amount);
+        wallets.insert(to, credited);
+        Ok(balance - amount)
+    })
+}
+
 #[ic_cdk::update]
 fn join_game(base_x: i32, base_y: i32, desired_slot: u8) -> Result<u8, String> {
+    join_game_with_base(base_x, base_y, Some(desired_slot), BASE_SIZE)
+}
+
+/// Join with a larger (or smaller) square base, e.g. as a tournament handicap.
+/// Takes the lowest free slot.
+#[ic_cdk::update]
+fn join_game_sized(base_x: i32, base_y: i32, size: u16) -> Result<u8, String> {
+    join_game_with_base(base_x, base_y, None, size)
+}
+
+fn join_game_with_base(base_x: i32, base_y: i32, desired_slot: Option<u8>, size: u16) -> Result<u8, String> {
+    ensure_writable()?;
     let caller = ic_cdk::api::msg_caller();
 
     // Record activity for freeze detection
-    LAST_ACTIVITY_NS.with(|la| *la.borrow_mut() = ic_cdk::api::time());
+    LAST_ACTIVITY_NS.with(|la| *la.borrow_mut() = now_ns());
 
     // Restart timer if it was stopped (board was empty or frozen)
     if !is_timer_running() {
         start_timer();
     }
 
+    join_game_for(caller, base_x, base_y, desired_slot, size)
+}
+
+fn join_game_for(
+    caller: Principal,
+    base_x: i32,
+    base_y: i32,
+    desired_slot: Option<u8>,
+    size: u16,
+) -> Result<u8, String> {
     // Validation 1: Auth
     if caller == Principal::anonymous() {
         return Err("Must be authenticated".to_string());
@@ -1488,12 +2780,16 @@ fn join_game(base_x: i32, base_y: i32, desired_slot: u8) -> Result<u8, String> {
         return Err(format!("Need {} coins to join", BASE_COST));
     }
 
-    // Validation 4: Coords valid
+    // Validation 4: Coords and size valid
+    validate_base_size(size)?;
     if base_x < 0 || base_x >= GRID_SIZE as i32 || base_y < 0 || base_y >= GRID_SIZE as i32 {
         return Err("Coordinates out of range".to_string());
     }
     let base_x = base_x as u16;
     let base_y = base_y as u16;
+    if !base_in_arena(base_x, base_y, size) {
+        return Err("Base must be inside the arena".to_string());
+    }
 
     // Validation 5: Quadrant free
     let quadrant = get_quadrant(base_x, base_y);
@@ -1505,7 +2801,7 @@ fn join_game(base_x: i32, base_y: i32, desired_slot: u8) -> Result<u8, String> {
     BASES.with(|bases| {
         let bases = bases.borrow();
         for existing_base in bases.iter().flatten() {
-            if bases_would_overlap(base_x, base_y, existing_base) {
+            if bases_would_overlap(base_x, base_y, size, existing_base) {
                 return Err("Overlaps existing base".to_string());
             }
         }
@@ -1513,6 +2809,12 @@ fn join_game(base_x: i32, base_y: i32, desired_slot: u8) -> Result<u8, String> {
     })?;
 
     // Validation 7: Desired slot is valid and available
+    let desired_slot = match desired_slot {
+        Some(slot) => slot,
+        None => PLAYERS.with(|players| players.borrow().iter().position(|p| p.is_none()))
+            .map(|slot| slot as u8)
+            .ok_or_else(|| "Game is full".to_string())?,
+    };
     if desired_slot as usize >= MAX_PLAYERS {
         return Err(format!("Invalid slot {} (max {})", desired_slot, MAX_PLAYERS - 1));
     }
@@ -1537,18 +2839,246 @@ fn join_game(base_x: i32, base_y: i32, desired_slot: u8) -> Result<u8, String> {
         bases.borrow_mut()[slot] = Some(Base {
             x: base_x,
             y: base_y,
-            coins: BASE_COST,
+            coins: STARTING_TREASURY.with(|t| *t.borrow()),
+            size: Some(size),
         });
     });
 
     PLAYERS.with(|players| {
         players.borrow_mut()[slot] = Some(caller);
     });
+    SPECTATORS.with(|s| s.borrow_mut().remove(&caller));
+
+    claim_base_footprint(slot, base_x, base_y, size);
+
+    Ok(slot as u8)
+}
+
+/// Join without picking coordinates: a default-size base is placed at the
+/// center of the next free quadrant in a rotating order, so there is no
+/// announced spot to camp. Charges BASE_COST like join_game.
+#[ic_cdk::update]
+fn request_spawn() -> Result<BaseInfo, String> {
+    ensure_writable()?;
+    let caller = ic_cdk::api::msg_caller();
+
+    // Record activity for freeze detection
+    LAST_ACTIVITY_NS.with(|la| *la.borrow_mut() = now_ns());
+
+    // Restart timer if it was stopped (board was empty or frozen)
+    if !is_timer_running() {
+        start_timer();
+    }
+
+    request_spawn_for(caller)
+}
+
+fn request_spawn_for(caller: Principal) -> Result<BaseInfo, String> {
+    let (step, x, y) = next_spawn_point().ok_or("No free quadrant")?;
+    let slot = join_game_for(caller, x as i32, y as i32, None, BASE_SIZE)? as usize;
+    SPAWN_CURSOR.with(|c| *c.borrow_mut() = (step + 1) % TOTAL_QUADRANTS);
+
+    let base = BASES.with(|b| b.borrow()[slot].clone()).ok_or("No base")?;
+    Ok(BaseInfo { x: base.x, y: base.y, coins: base.coins, size: base.size(), slot: slot as u8 })
+}
+
+/// First usable spawn after the cursor: (rotation step, base x, base y).
+/// Step k of the rotation is quadrant k * SPAWN_STRIDE mod TOTAL_QUADRANTS.
+fn next_spawn_point() -> Option<(u8, u16, u16)> {
+    let cursor = SPAWN_CURSOR.with(|c| *c.borrow());
+    (0..TOTAL_QUADRANTS)
+        .map(|i| (cursor + i) % TOTAL_QUADRANTS)
+        .find_map(|step| {
+            let quadrant = (step * SPAWN_STRIDE) % TOTAL_QUADRANTS;
+            if quadrant_has_base(quadrant) {
+                return None;
+            }
+            let (qx, qy, w, h) = quadrant_bounds(quadrant);
+            let (x, y) = (qx + (w - BASE_SIZE) / 2, qy + (h - BASE_SIZE) / 2);
+            let blocked = !base_in_arena(x, y, BASE_SIZE) || BASES.with(|bases| {
+                bases.borrow().iter().flatten().any(|b| bases_would_overlap(x, y, BASE_SIZE, b))
+            });
+            (!blocked).then_some((step, x, y))
+        })
+}
+
+/// Move the caller's base for BASE_COST (burned; the base treasury carries
+/// over). Old footprint territory and cells are released, the new footprint
+/// is claimed, and territory cut off from the new base is culled.
+#[ic_cdk::update]
+fn relocate_base(new_x: i32, new_y: i32) -> Result<BaseInfo, String> {
+    ensure_writable()?;
+    let caller = ic_cdk::api::msg_caller();
+
+    // Record activity for freeze detection
+    LAST_ACTIVITY_NS.with(|la| *la.borrow_mut() = now_ns());
+
+    relocate_base_for(caller, new_x, new_y)
+}
+
+fn relocate_base_for(caller: Principal, new_x: i32, new_y: i32) -> Result<BaseInfo, String> {
+    let now = now_ns();
+    let slot = find_player_slot(caller).ok_or("Not in game")?;
+    let old = BASES.with(|b| b.borrow()[slot].clone()).ok_or("No base")?;
+
+    if let Some(last) = LAST_RELOCATE_NS.with(|lr| lr.borrow()[slot]) {
+        let elapsed = now.saturating_sub(last);
+        if elapsed < RELOCATE_COOLDOWN_NS {
+            return Err(format!(
+                "Relocation on cooldown for {} more seconds",
+                (RELOCATE_COOLDOWN_NS - elapsed).div_ceil(1_000_000_000)
+            ));
+        }
+    }
+
+    let wallet_balance = WALLETS.with(|w| *w.borrow().get(&caller).unwrap_or(&0));
+    if wallet_balance < BASE_COST {
+        return Err(format!("Need {} coins to relocate", BASE_COST));
+    }
+
+    // Same location checks as join_game, ignoring the player's own base
+    if new_x < 0 || new_x >= GRID_SIZE as i32 || new_y < 0 || new_y >= GRID_SIZE as i32 {
+        return Err("Coordinates out of range".to_string());
+    }
+    let (new_x, new_y) = (new_x as u16, new_y as u16);
+    let size = old.size();
+    if !base_in_arena(new_x, new_y, size) {
+        return Err("Base must be inside the arena".to_string());
+    }
+    let quadrant = get_quadrant(new_x, new_y);
+    BASES.with(|bases| {
+        for (i, other) in bases.borrow().iter().enumerate() {
+            let Some(other) = other else { continue };
+            if i == slot {
+                continue;
+            }
+            if get_quadrant(other.x, other.y) == quadrant {
+                return Err("Quadrant already has a base".to_string());
+            }
+            if bases_would_overlap(new_x, new_y, size, other) {
+                return Err("Overlaps existing base".to_string());
+            }
+        }
+        Ok(())
+    })?;
 
-    // CRITICAL: Clear the entire 8x8 base area of enemy territory and cells
+    // Charge the fee; it leaves circulation (TOTAL_BURNED only tracks the placement burn)
+    WALLETS.with(|w| {
+        if let Some(balance) = w.borrow_mut().get_mut(&caller) {
+            *balance -= BASE_COST;
+        }
+    });
+
+    // Release the old footprint: kill its cells and drop its territory
+    let mut changes = TerritoryChanges::new();
+    changes.affected_players |= 1 << slot;
+    for dy in 0..size {
+        for dx in 0..size {
+            let x = old.x.wrapping_add(dx) & 511;
+            let y = old.y.wrapping_add(dy) & 511;
+            let idx = coords_to_idx(x, y);
+
+            if is_alive_idx(idx) {
+                if let Some(owner) = find_owner(x, y) {
+                    CELL_COUNTS.with(|cc| {
+                        let mut cc = cc.borrow_mut();
+                        cc[owner] = cc[owner].saturating_sub(1);
+                    });
+                }
+                clear_alive_idx(idx);
+                mark_neighbors_potential(idx);
+            }
+            if player_owns(slot, x, y) {
+                clear_territory(slot, x, y);
+            }
+
+            // Perimeter cells are where the remaining territory attached
+            let on_edge = dx == 0 || dy == 0 || dx == size - 1 || dy == size - 1;
+            if on_edge && !changes.lost_cells[slot].is_full() {
+                changes.lost_cells[slot].push((x, y));
+            }
+        }
+    }
+
+    BASES.with(|b| {
+        if let Some(base) = &mut b.borrow_mut()[slot] {
+            base.x = new_x;
+            base.y = new_y;
+        }
+    });
+    claim_base_footprint(slot, new_x, new_y, size);
+    check_all_disconnections(&changes);
+
+    if CELL_COUNTS.with(|cc| cc.borrow()[slot]) == 0 {
+        ZERO_CELLS_SINCE.with(|zcs| {
+            zcs.borrow_mut()[slot].get_or_insert(now);
+        });
+    }
+    LAST_RELOCATE_NS.with(|lr| lr.borrow_mut()[slot] = Some(now));
+
+    let base = BASES.with(|b| b.borrow()[slot].clone()).ok_or("No base")?;
+    Ok(BaseInfo { x: base.x, y: base.y, coins: base.coins, size: base.size(), slot: slot as u8 })
+}
+
+/// Give up the caller's slot: half the base treasury (rounded down) goes back
+/// to their wallet, then the slot is cleared exactly as on elimination.
+/// Returns the refunded amount.
+#[ic_cdk::update]
+fn leave_game() -> Result<u64, String> {
+    ensure_writable()?;
+    leave_game_for(ic_cdk::api::msg_caller())
+}
+
+fn leave_game_for(caller: Principal) -> Result<u64, String> {
+    let slot = find_player_slot(caller).ok_or("Not in game")?;
+    let treasury = BASES.with(|b| b.borrow()[slot].as_ref().map_or(0, |base| base.coins));
+    let refund = treasury / 2;
+
+    WALLETS.with(|w| *w.borrow_mut().entry(caller).or_insert(0) += refund);
+    eliminate_player(slot, EliminationReason::VoluntaryLeave);
+
+    Ok(refund)
+}
+
+/// Give the caller's whole base treasury to another player's base, then
+/// eliminate the caller (cells and territory cleared). Nothing is refunded.
+#[ic_cdk::update]
+fn surrender_to(target_slot: u8) -> Result<(), String> {
+    ensure_writable()?;
+    surrender_to_for(ic_cdk::api::msg_caller(), target_slot)
+}
+
+fn surrender_to_for(caller: Principal, target_slot: u8) -> Result<(), String> {
+    let slot = find_player_slot(caller).ok_or("Not in game")?;
+    let target = target_slot as usize;
+    if target >= MAX_PLAYERS {
+        return Err("Invalid slot".to_string());
+    }
+    if target == slot {
+        return Err("Cannot surrender to yourself".to_string());
+    }
+    if PLAYERS.with(|p| p.borrow()[target].is_none()) || BASES.with(|b| b.borrow()[target].is_none()) {
+        return Err("Target slot is not occupied".to_string());
+    }
+
+    BASES.with(|bases| {
+        let mut bases = bases.borrow_mut();
+        let coins = bases[slot].as_mut().map_or(0, |base| std::mem::take(&mut base.coins));
+        if let Some(base) = &mut bases[target] {
+            base.coins += coins;
+        }
+    });
+    eliminate_player(slot, EliminationReason::Surrender);
+    Ok(())
+}
+
+/// Clear a new base footprint of other players' cells and territory, then
+/// claim all of it for `slot`
+fn claim_base_footprint(slot: usize, base_x: u16, base_y: u16, size: u16) {
+    // CRITICAL: Clear the entire base area of enemy territory and cells
     // This prevents the bug where overlapping territory causes cells to "siege" their own base
-    for dy in 0..BASE_SIZE {
-        for dx in 0..BASE_SIZE {
+    for dy in 0..size {
+        for dx in 0..size {
             let x = base_x.wrapping_add(dx) & 511;
             let y = base_y.wrapping_add(dy) & 511;
             let idx = coords_to_idx(x, y);
@@ -1578,39 +3108,92 @@ fn join_game(base_x: i32, base_y: i32, desired_slot: u8) -> Result<u8, String> {
         }
     }
 
-    // Initialize 8x8 base territory (full base footprint)
-    for dy in 0..BASE_SIZE {
-        for dx in 0..BASE_SIZE {
+    // Initialize base territory (full base footprint)
+    for dy in 0..size {
+        for dx in 0..size {
             let x = base_x.wrapping_add(dx) & 511;
             let y = base_y.wrapping_add(dy) & 511;
             set_territory(slot, x, y);
         }
     }
-
-    Ok(slot as u8)
 }
 
 #[ic_cdk::update]
 fn place_cells(cells: Vec<(i32, i32)>) -> Result<u32, String> {
+    ensure_writable()?;
     let caller = ic_cdk::api::msg_caller();
+    note_placement_activity();
 
-    // Record activity for freeze detection
-    LAST_ACTIVITY_NS.with(|la| *la.borrow_mut() = ic_cdk::api::time());
+    // Size limit validation
+    if cells.len() > MAX_PLACE_CELLS {
+        return Err(format!("Max {} cells per call", MAX_PLACE_CELLS));
+    }
 
-    // Restart timer if it was stopped (board was empty or frozen)
-    if !is_timer_running() {
-        start_timer();
+    if cells.is_empty() {
+        return Ok(0);
     }
 
-    // Size limit validation
+    let (slot, base) = placement_context(caller, cells.len())?;
+
+    // Phase 1: Validate ALL cells first (atomic)
+    for &(x, y) in &cells {
+        validate_placement(slot, &base, x, y)?;
+    }
+    check_quadrant_density(slot, &cells)?;
+
+    Ok(commit_placement(caller, slot, &cells))
+}
+
+/// Like place_cells, but on failure reports every rejected cell with its
+/// reason instead of stopping at the first. Still all-or-nothing: nothing is
+/// placed unless every cell passes, and a cell repeated within the batch is
+/// rejected rather than charged twice. Succeeds with the same value as
+/// place_cells; cells are reported in place_cells' i32 coordinates, and
+/// call-level refusals come back as PlaceError::Call rather than as a cell.
+#[ic_cdk::update]
+fn place_cells_checked(cells: Vec<(i32, i32)>) -> Result<u32, PlaceError> {
+    ensure_writable().map_err(PlaceError::Call)?;
+    let caller = ic_cdk::api::msg_caller();
+    note_placement_activity();
+
     if cells.len() > MAX_PLACE_CELLS {
-        return Err(format!("Max {} cells per call", MAX_PLACE_CELLS));
+        return Err(PlaceError::Call(format!("Max {} cells per call", MAX_PLACE_CELLS)));
     }
 
     if cells.is_empty() {
         return Ok(0);
     }
 
+    let (slot, base) = placement_context(caller, cells.len()).map_err(PlaceError::Call)?;
+
+    let failures = placement_failures(slot, &base, &cells);
+    if !failures.is_empty() {
+        return Err(PlaceError::Cells(failures));
+    }
+    check_quadrant_density(slot, &cells).map_err(PlaceError::Call)?;
+
+    Ok(commit_placement(caller, slot, &cells))
+}
+
+/// Shared by both placement endpoints before validation
+fn note_placement_activity() {
+    // Record activity for freeze detection
+    LAST_ACTIVITY_NS.with(|la| *la.borrow_mut() = now_ns());
+
+    // Restart timer if it was stopped (board was empty or frozen)
+    if !is_timer_running() {
+        start_timer();
+    }
+}
+
+/// Place validated cells; the success value of both placement endpoints
+fn commit_placement(caller: Principal, slot: usize, cells: &[(i32, i32)]) -> u32 {
+    apply_placement(caller, slot, cells);
+    cells.len() as u32
+}
+
+/// Caller's slot and base, checking they can afford `count` placements
+fn placement_context(caller: Principal, count: usize) -> Result<(usize, Base), String> {
     let slot = find_player_slot(caller).ok_or("Not in game")?;
 
     let base = BASES.with(|bases| {
@@ -1618,47 +3201,124 @@ fn place_cells(cells: Vec<(i32, i32)>) -> Result<u32, String> {
     }).ok_or("No base")?;
 
     let wallet_balance = WALLETS.with(|w| *w.borrow().get(&caller).unwrap_or(&0));
-    if wallet_balance < cells.len() as u64 * PLACEMENT_COST {
+    if wallet_balance < count as u64 * PLACEMENT_COST {
         return Err("Insufficient coins".to_string());
     }
 
-    // Phase 1: Validate ALL cells first (atomic)
-    for &(x, y) in &cells {
-        if x < 0 || x >= GRID_SIZE as i32 || y < 0 || y >= GRID_SIZE as i32 {
-            return Err("Coordinates out of range".to_string());
-        }
-        let x = x as u16;
-        let y = y as u16;
+    Ok((slot, base))
+}
 
-        // Base (including walls) is ALWAYS the owner's territory - no bitmap check needed
-        // For positions outside base, must own the territory
-        if !is_in_base(&base, x, y) && !player_owns(slot, x, y) {
-            return Err("Not your territory".to_string());
-        }
+fn validate_placement(slot: usize, base: &Base, x: i32, y: i32) -> Result<(), String> {
+    if x < 0 || x >= GRID_SIZE as i32 || y < 0 || y >= GRID_SIZE as i32 {
+        return Err("Coordinates out of range".to_string());
+    }
+    let x = x as u16;
+    let y = y as u16;
+
+    if !in_arena(x, y) {
+        return Err("Outside the arena".to_string());
+    }
+
+    // Base (including walls) is ALWAYS the owner's territory - no bitmap check needed
+    // For positions outside base, must own the territory
+    if !is_in_base(base, x, y) && !player_owns(slot, x, y) {
+        return Err("Not your territory".to_string());
+    }
 
-        if is_alive(x, y) {
-            return Err("Cell already alive".to_string());
+    if is_alive(x, y) {
+        return Err("Cell already alive".to_string());
+    }
+
+    Ok(())
+}
+
+/// Every cell that fails validation, at the caller's original coordinates
+/// (so out-of-range input is reported as sent, not against a valid cell).
+/// Repeats of a valid cell after its first occurrence are failures too.
+fn placement_failures(slot: usize, base: &Base, cells: &[(i32, i32)]) -> Vec<(i32, i32, String)> {
+    let mut seen = HashSet::with_capacity(cells.len());
+    cells
+        .iter()
+        .filter_map(|&(x, y)| {
+            validate_placement(slot, base, x, y)
+                .err()
+                .or_else(|| (!seen.insert((x, y))).then(|| "Duplicate cell in batch".to_string()))
+                .map(|reason| (x, y, reason))
+        })
+        .collect()
+}
+
+/// Live cells `slot` owns in quadrant `q`
+fn player_cells_in_quadrant(slot: usize, q: u8) -> u32 {
+    ALIVE.with(|alive| {
+        OWNER.with(|owner| {
+            let alive = alive.borrow();
+            let owner = owner.borrow();
+            let mut count = 0;
+            for (word_idx, word_x, y) in quadrant_words(q) {
+                let mut word = alive[word_idx];
+                while word != 0 {
+                    let bit_pos = word.trailing_zeros() as u16;
+                    word &= word - 1;
+                    if owner[coords_to_idx(word_x + bit_pos, y)] as usize == slot {
+                        count += 1;
+                    }
+                }
+            }
+            count
+        })
+    })
+}
+
+/// Refuse a batch that would take `slot` past the density limit in any
+/// quadrant it touches. Cells must already be validated (in range).
+fn check_quadrant_density(slot: usize, cells: &[(i32, i32)]) -> Result<(), String> {
+    let limit = QUADRANT_DENSITY_LIMIT.with(|l| *l.borrow());
+    let mut added = [0u32; TOTAL_QUADRANTS as usize];
+    for &(x, y) in cells {
+        added[get_quadrant(x as u16, y as u16) as usize] += 1;
+    }
+    for (q, &count) in added.iter().enumerate() {
+        if count > 0 && player_cells_in_quadrant(slot, q as u8) + count > limit {
+            return Err("Quadrant density limit reached".to_string());
         }
     }
+    Ok(())
+}
 
-    // Phase 2: Deduct coins (wallet -> base treasury)
-    let count = cells.len() as u64;
+fn set_quadrant_density(limit: u32) -> Result<(), String> {
+    let quadrant_cells = QUADRANT_SIZE as u32 * QUADRANT_SIZE as u32;
+    if limit == 0 || limit > quadrant_cells {
+        return Err(format!("Density limit must be between 1 and {}", quadrant_cells));
+    }
+    QUADRANT_DENSITY_LIMIT.with(|l| *l.borrow_mut() = limit);
+    Ok(())
+}
+
+/// Charge for and place already-validated cells
+fn apply_placement(caller: Principal, slot: usize, cells: &[(i32, i32)]) {
+    // Phase 2: Deduct coins (wallet -> base treasury, minus the burned share)
+    let cost = cells.len() as u64 * PLACEMENT_COST;
+    let burned = cost * PLACEMENT_BURN_BPS.with(|b| *b.borrow()) / BPS_DENOMINATOR;
     WALLETS.with(|wallets| {
         if let Some(balance) = wallets.borrow_mut().get_mut(&caller) {
-            *balance -= count * PLACEMENT_COST;
+            *balance -= cost;
         }
     });
     BASES.with(|bases| {
         if let Some(base) = &mut bases.borrow_mut()[slot] {
-            base.coins += count * PLACEMENT_COST;
+            base.coins += cost - burned;
         }
     });
+    TOTAL_BURNED.with(|t| *t.borrow_mut() += burned);
 
     // Phase 3: Place cells
-    for &(x, y) in &cells {
+    for &(x, y) in cells {
         let x = x as u16;
         let y = y as u16;
+        mark_quadrant_placed(get_quadrant(x, y));
         set_alive(x, y);
+        CELL_AGE.with(|ages| ages.borrow_mut()[coords_to_idx(x, y)] = 0);
         mark_with_neighbors_potential(coords_to_idx(x, y));
     }
 
@@ -1674,17 +3334,194 @@ fn place_cells(cells: Vec<(i32, i32)>) -> Result<u32, String> {
         });
     });
 
-    // Update cell count
-    CELL_COUNTS.with(|cc| {
-        cc.borrow_mut()[slot] += cells.len() as u32;
-    });
+    // Update cell count
+    CELL_COUNTS.with(|cc| {
+        cc.borrow_mut()[slot] += cells.len() as u32;
+    });
+
+    // Clear grace period
+    ZERO_CELLS_SINCE.with(|zcs| {
+        zcs.borrow_mut()[slot] = None;
+    });
+}
+
+#[ic_cdk::update]
+fn propose_alliance(slot: u8) -> Result<(), String> {
+    ensure_writable()?;
+    let caller = ic_cdk::api::msg_caller();
+    let player = find_player_slot(caller).ok_or_else(|| "Not in game".to_string())?;
+    propose_alliance_for(player, slot as usize)
+}
+
+#[ic_cdk::update]
+fn accept_alliance(slot: u8) -> Result<(), String> {
+    ensure_writable()?;
+    let caller = ic_cdk::api::msg_caller();
+    let player = find_player_slot(caller).ok_or_else(|| "Not in game".to_string())?;
+    accept_alliance_for(player, slot as usize)
+}
+
+#[ic_cdk::update]
+fn break_alliance() -> Result<(), String> {
+    ensure_writable()?;
+    let caller = ic_cdk::api::msg_caller();
+    let player = find_player_slot(caller).ok_or_else(|| "Not in game".to_string())?;
+    break_alliance_for(player)
+}
+
+/// Controller-only: toggle cell decay. Ages start from zero whenever decay is switched on.
+#[ic_cdk::update]
+fn set_cell_decay(enabled: bool) -> Result<(), String> {
+    require_controller()?;
+    set_cell_decay_enabled(enabled);
+    Ok(())
+}
+
+fn set_cell_decay_enabled(enabled: bool) {
+    if enabled {
+        CELL_AGE.with(|ages| ages.borrow_mut().fill(0));
+    }
+    CELL_DECAY_ENABLED.with(|d| *d.borrow_mut() = enabled);
+}
+
+/// Controller-only: freeze player mutations (joins, placements, faucet,
+/// alliances) while the simulation and all queries keep running
+#[ic_cdk::update]
+fn set_readonly(enabled: bool) -> Result<(), String> {
+    require_controller()?;
+    READONLY.with(|r| *r.borrow_mut() = enabled);
+    Ok(())
+}
+
+/// Controller-only: basis points of each placement's cost that are burned
+/// rather than added to the base treasury (0 = everything to treasury)
+#[ic_cdk::update]
+fn set_placement_burn_bps(bps: u64) -> Result<(), String> {
+    require_controller()?;
+    set_placement_burn(bps)
+}
+
+/// Controller-only: generations after a placement during which the
+/// quadrant's wipe is skipped (0 = off). A skipped quadrant waits for its
+/// next turn in the rotation.
+#[ic_cdk::update]
+fn set_wipe_freeze_generations(generations: u64) -> Result<(), String> {
+    require_controller()?;
+    set_wipe_freeze(generations)
+}
+
+/// Controller-only: most live cells one player may own in a single quadrant;
+/// placements beyond it are refused
+#[ic_cdk::update]
+fn set_quadrant_density_limit(limit: u32) -> Result<(), String> {
+    require_controller()?;
+    set_quadrant_density(limit)
+}
+
+/// Controller-only: coins a newly joined base starts with
+#[ic_cdk::update]
+fn set_starting_treasury(coins: u64) -> Result<(), String> {
+    require_controller()?;
+    set_starting_treasury_coins(coins)
+}
+
+fn set_starting_treasury_coins(coins: u64) -> Result<(), String> {
+    // A base with no coins can never be sieged out, so 0 is not allowed
+    if coins == 0 || coins > MAX_STARTING_TREASURY {
+        return Err(format!("Starting treasury must be between 1 and {}", MAX_STARTING_TREASURY));
+    }
+    STARTING_TREASURY.with(|t| *t.borrow_mut() = coins);
+    Ok(())
+}
+
+fn set_placement_burn(bps: u64) -> Result<(), String> {
+    if bps > BPS_DENOMINATOR {
+        return Err(format!("Burn must be at most {} bps", BPS_DENOMINATOR));
+    }
+    PLACEMENT_BURN_BPS.with(|b| *b.borrow_mut() = bps);
+    Ok(())
+}
+
+/// Controller-only: seconds between global quadrant wipes (minimum 30)
+#[ic_cdk::update]
+fn set_wipe_interval(seconds: u64) -> Result<(), String> {
+    require_controller()?;
+    set_wipe_interval_secs(seconds);
+    Ok(())
+}
+
+/// Controller-only: simulation speed. Values are clamped (see
+/// set_tick_speed_values); a running timer is restarted at the new interval.
+#[ic_cdk::update]
+fn set_tick_speed(generations_per_tick: u32, tick_interval_ms: u64) -> Result<(), String> {
+    require_controller()?;
+    set_tick_speed_values(generations_per_tick, tick_interval_ms);
+    if is_timer_running() {
+        stop_timer();
+        start_timer();
+    }
+    Ok(())
+}
+
+/// Controller-only: wipe the board and fill it with a seeded random neutral
+/// soup (at most MAX_SEED_DENSITY_PCT percent alive). Requires an empty game.
+#[ic_cdk::update]
+fn reset_with_seed(seed: u64, density_pct: u8) -> Result<(), String> {
+    require_controller()?;
+    reset_with_seed_internal(seed, density_pct)?;
+    if IS_RUNNING.with(|r| *r.borrow()) && !is_timer_running() {
+        start_timer();
+    }
+    Ok(())
+}
+
+/// Controller-only: seconds a player may sit at zero cells before elimination
+#[ic_cdk::update]
+fn set_grace_period(seconds: u64) -> Result<(), String> {
+    require_controller()?;
+    set_grace_period_secs(seconds);
+    Ok(())
+}
+
+/// Controller-only: give cut-off territory DISCONNECT_GRACE_GENS generations
+/// to reconnect before it is culled
+#[ic_cdk::update]
+fn set_disconnect_grace(enabled: bool) -> Result<(), String> {
+    require_controller()?;
+    set_disconnect_grace_enabled(enabled);
+    Ok(())
+}
+
+/// Controller-only: sudden death. The arena loses a SHRINK_STEP ring on
+/// every edge each SHRINK_INTERVAL_NS until it reaches MIN_ARENA_SIZE.
+#[ic_cdk::update]
+fn set_sudden_death(enabled: bool) -> Result<(), String> {
+    require_controller()?;
+    set_sudden_death_enabled(enabled);
+    Ok(())
+}
 
-    // Clear grace period
-    ZERO_CELLS_SINCE.with(|zcs| {
-        zcs.borrow_mut()[slot] = None;
-    });
+/// Controller-only: use per-quadrant wipe timers instead of the global rotation
+#[ic_cdk::update]
+fn set_independent_wipes(enabled: bool) -> Result<(), String> {
+    require_controller()?;
+    set_independent_wipes_enabled(enabled);
+    Ok(())
+}
+
+/// Controller-only: wipe interval for one quadrant under independent timers
+#[ic_cdk::update]
+fn set_quadrant_wipe_interval(quadrant: u8, interval_ns: u64) -> Result<(), String> {
+    require_controller()?;
+    set_quadrant_wipe_interval_ns(quadrant, interval_ns)
+}
 
-    Ok(cells.len() as u32)
+/// Controller-only: what survives a quadrant wipe
+#[ic_cdk::update]
+fn set_wipe_exemption(exemption: WipeExemption) -> Result<(), String> {
+    require_controller()?;
+    WIPE_EXEMPTION.with(|w| *w.borrow_mut() = exemption);
+    Ok(())
 }
 
 #[ic_cdk::update]
@@ -1702,7 +3539,7 @@ fn resume_game() -> Result<(), String> {
     });
 
     // Update activity timestamp to prevent immediate re-freeze
-    LAST_ACTIVITY_NS.with(|la| *la.borrow_mut() = ic_cdk::api::time());
+    LAST_ACTIVITY_NS.with(|la| *la.borrow_mut() = now_ns());
 
     // Restart timer if it was stopped
     if !is_timer_running() {
@@ -1738,9 +3575,9 @@ fn get_state() -> GameState {
         let zero_since = ZERO_CELLS_SINCE.with(|zcs| zcs.borrow()[slot]);
 
         let (in_grace_period, grace_seconds_remaining) = if let Some(since) = zero_since {
-            let now = ic_cdk::api::time();
+            let now = now_ns();
             let elapsed = now.saturating_sub(since);
-            let remaining = GRACE_PERIOD_NS.saturating_sub(elapsed);
+            let remaining = grace_period_ns().saturating_sub(elapsed);
             (true, Some(remaining / 1_000_000_000))
         } else {
             (false, None)
@@ -1752,20 +3589,18 @@ fn get_state() -> GameState {
                 x: b.x,
                 y: b.y,
                 coins: b.coins,
+                size: b.size(),
                 slot: slot as u8,
             }),
             alive_cells,
             territory_cells,
             in_grace_period,
             grace_seconds_remaining,
+            profile: PROFILES.with(|pr| pr.borrow()[slot].clone()),
         })
     }).collect();
 
-    let next_wipe_quadrant = NEXT_WIPE_QUADRANT.with(|q| *q.borrow());
-    let last_wipe = LAST_WIPE_NS.with(|lw| *lw.borrow());
-    let now = ic_cdk::api::time();
-    let elapsed = now.saturating_sub(last_wipe);
-    let seconds_until_wipe = WIPE_INTERVAL_NS.saturating_sub(elapsed) / 1_000_000_000;
+    let (next_wipe_quadrant, seconds_until_wipe) = next_wipe();
 
     GameState {
         generation,
@@ -1788,9 +3623,9 @@ fn get_slots_info() -> Vec<Option<SlotInfo>> {
         let zero_since = ZERO_CELLS_SINCE.with(|zcs| zcs.borrow()[slot]);
 
         let (in_grace_period, grace_seconds_remaining) = if let Some(since) = zero_since {
-            let now = ic_cdk::api::time();
+            let now = now_ns();
             let elapsed = now.saturating_sub(since);
-            let remaining = GRACE_PERIOD_NS.saturating_sub(elapsed);
+            let remaining = grace_period_ns().saturating_sub(elapsed);
             (true, Some(remaining / 1_000_000_000))
         } else {
             (false, None)
@@ -1802,16 +3637,25 @@ fn get_slots_info() -> Vec<Option<SlotInfo>> {
                 x: b.x,
                 y: b.y,
                 coins: b.coins,
+                size: b.size(),
                 slot: slot as u8,
             }),
             alive_cells,
             territory_cells,
             in_grace_period,
             grace_seconds_remaining,
+            profile: PROFILES.with(|pr| pr.borrow()[slot].clone()),
         })
     }).collect()
 }
 
+/// Nearest other player's base to `slot`'s base: (their slot, distance in
+/// cells). None if `slot` has no base or no one else does.
+#[ic_cdk::query]
+fn get_nearest_enemy_base(slot: u8) -> Option<(u8, u16)> {
+    nearest_enemy_base(slot as usize)
+}
+
 #[ic_cdk::query]
 fn get_base_info(slot: u8) -> Option<BaseInfo> {
     if slot as usize >= MAX_PLAYERS {
@@ -1822,6 +3666,7 @@ fn get_base_info(slot: u8) -> Option<BaseInfo> {
             x: b.x,
             y: b.y,
             coins: b.coins,
+            size: b.size(),
             slot,
         })
     })
@@ -1845,13 +3690,170 @@ fn get_territory_info(slot: u8) -> Option<TerritoryExport> {
     })
 }
 
+/// Raw (chunk_idx, chunk_words) pairs for a slot's territory, in ascending
+/// chunk order. Each chunk is exactly 64 row words (Candid has no fixed-length
+/// vector, so the interface shows `vec nat64`). Much smaller than a coordinate
+/// list for large territories; empty for out-of-range or empty slots.
+#[ic_cdk::query]
+fn get_territory_chunks(slot: u8) -> Vec<(u8, [u64; 64])> {
+    if slot as usize >= MAX_PLAYERS {
+        return Vec::new();
+    }
+    TERRITORY.with(|t| {
+        let t = t.borrow();
+        let pt = &t[slot as usize];
+
+        // chunks are stored in chunk_mask bit order, so walking set bits
+        // low-to-high yields ascending indices aligned with the Vec
+        let mut mask = pt.chunk_mask;
+        let mut out = Vec::with_capacity(pt.chunks.len());
+        for chunk in pt.chunks.iter() {
+            let chunk_idx = mask.trailing_zeros() as u8;
+            mask &= mask - 1;
+            let words: [u64; 64] = chunk.as_slice().try_into().expect("territory chunk is 64 words");
+            out.push((chunk_idx, words));
+        }
+        out
+    })
+}
+
+/// Alive cells `generations` steps after hypothetically placing `cells`,
+/// simulated on a copy of the grid. Placements take the owner of the territory
+/// they land on, as real ones do; births use the normal ownership rules, but
+/// sieges, decay, wipes and disconnection are skipped.
+fn preview_generations(cells: &[(u16, u16)], generations: u8) -> Vec<SparseCell> {
+    let mut alive = ALIVE.with(|a| *a.borrow());
+    let mut potential = [0u64; TOTAL_WORDS];
+    for &(x, y) in cells.iter().take(MAX_PLACE_CELLS) {
+        if x < GRID_SIZE && y < GRID_SIZE {
+            let idx = coords_to_idx(x, y);
+            alive[idx / 64] |= 1u64 << (idx % 64);
+        }
+    }
+
+    // Birth ownership reads OWNER and GENERATION, so preview births are
+    // written through and every change is undone before returning
+    let start_gen = GENERATION.with(|g| *g.borrow());
+    let mut overwritten: Vec<(usize, u8)> = Vec::new();
+    let (mut births, mut deaths, mut survivors) = (Vec::new(), Vec::new(), Vec::new());
+
+    for step in 0..generations.min(MAX_PREVIEW_GENERATIONS) {
+        GENERATION.with(|g| *g.borrow_mut() = start_gen + step as u64);
+        potential.fill(0);
+        for (word_idx, &word) in alive.iter().enumerate() {
+            let mut word = word;
+            while word != 0 {
+                let bit_pos = word.trailing_zeros() as usize;
+                word &= word - 1;
+                let (x, y) = idx_to_coords(word_idx * 64 + bit_pos);
+                for dy in [-1i16, 0, 1] {
+                    for dx in [-1i16, 0, 1] {
+                        let idx = coords_to_idx(x.wrapping_add(dx as u16) & 511, y.wrapping_add(dy as u16) & 511);
+                        potential[idx / 64] |= 1u64 << (idx % 64);
+                    }
+                }
+            }
+        }
+
+        births.clear();
+        deaths.clear();
+        survivors.clear();
+        collect_fates(&potential, &alive, &mut births, &mut deaths, &mut survivors);
+
+        for &cell_idx in &deaths {
+            alive[cell_idx / 64] &= !(1u64 << (cell_idx % 64));
+        }
+        OWNER.with(|o| {
+            let mut owner = o.borrow_mut();
+            for &(cell_idx, new_owner) in &births {
+                alive[cell_idx / 64] |= 1u64 << (cell_idx % 64);
+                overwritten.push((cell_idx, owner[cell_idx]));
+                owner[cell_idx] = new_owner.map_or(255, |slot| slot as u8);
+            }
+        });
+    }
+
+    let mut out = Vec::new();
+    OWNER.with(|o| {
+        let mut owner = o.borrow_mut();
+        for (word_idx, &word) in alive.iter().enumerate() {
+            let mut word = word;
+            while word != 0 {
+                let bit_pos = word.trailing_zeros() as usize;
+                word &= word - 1;
+                let cell_idx = word_idx * 64 + bit_pos;
+                let (x, y) = idx_to_coords(cell_idx);
+                let slot = owner[cell_idx];
+                out.push(SparseCell { x, y, owner: (slot != 255).then_some(slot) });
+            }
+        }
+        for &(cell_idx, previous) in overwritten.iter().rev() {
+            owner[cell_idx] = previous;
+        }
+    });
+    GENERATION.with(|g| *g.borrow_mut() = start_gen);
+    out
+}
+
+/// Dry run: the board after placing `cells` and stepping `generations`
+/// (capped at MAX_PREVIEW_GENERATIONS). Nothing is placed or charged.
+#[ic_cdk::query]
+fn preview_after_placement(cells: Vec<(u16, u16)>, generations: u8) -> Vec<SparseCell> {
+    preview_generations(&cells, generations)
+}
+
+/// Territory cells that would orphan part of the slot's territory if lost
+/// (cut vertices between the base and the rest of the territory).
+#[ic_cdk::query]
+fn get_fragile_cells(slot: u8) -> Vec<(u16, u16)> {
+    if slot as usize >= MAX_PLAYERS {
+        return Vec::new();
+    }
+    fragile_cells(slot as usize)
+}
+
+/// Live-cell count, dominant owner and base presence for every quadrant,
+/// from a single pass over the alive bitmap
+#[ic_cdk::query]
+fn get_quadrant_stats() -> [QuadStat; TOTAL_QUADRANTS as usize] {
+    let mut stats = [QuadStat::default(); TOTAL_QUADRANTS as usize];
+
+    ALIVE.with(|alive| {
+        OWNER.with(|owner| {
+            let alive = alive.borrow();
+            let owner = owner.borrow();
+
+            for (q, stat) in stats.iter_mut().enumerate() {
+                let mut tally = [0u32; MAX_PLAYERS];
+                for (word_idx, word_x, y) in quadrant_words(q as u8) {
+                    let mut word = alive[word_idx];
+                    stat.alive += word.count_ones();
+                    while word != 0 {
+                        let bit_pos = word.trailing_zeros() as u16;
+                        word &= word - 1;
+                        let slot = owner[coords_to_idx(word_x + bit_pos, y)];
+                        if (slot as usize) < MAX_PLAYERS {
+                            tally[slot as usize] += 1;
+                        }
+                    }
+                }
+                stat.dominant_owner = tally
+                    .iter()
+                    .enumerate()
+                    .filter(|&(_, &n)| n > 0)
+                    .max_by_key(|&(slot, &n)| (n, std::cmp::Reverse(slot)))
+                    .map(|(slot, _)| slot as u8);
+                stat.has_base = quadrant_has_base(q as u8);
+            }
+        })
+    });
+
+    stats
+}
+
 #[ic_cdk::query]
 fn get_next_wipe() -> WipeInfo {
-    let next_quadrant = NEXT_WIPE_QUADRANT.with(|q| *q.borrow());
-    let last_wipe = LAST_WIPE_NS.with(|lw| *lw.borrow());
-    let now = ic_cdk::api::time();
-    let elapsed = now.saturating_sub(last_wipe);
-    let seconds_until = WIPE_INTERVAL_NS.saturating_sub(elapsed) / 1_000_000_000;
+    let (next_quadrant, seconds_until) = next_wipe();
 
     WipeInfo {
         next_quadrant,
@@ -1859,17 +3861,137 @@ fn get_next_wipe() -> WipeInfo {
     }
 }
 
+/// What the next wipe would destroy, for warning the most-affected players
+#[ic_cdk::query]
+fn get_next_wipe_impact() -> WipeImpact {
+    let (quadrant, seconds) = next_wipe();
+    let exemption = WIPE_EXEMPTION.with(|w| *w.borrow());
+
+    let mut alive_cells = 0u32;
+    let mut tally = [0u32; MAX_PLAYERS];
+    ALIVE.with(|alive| {
+        let alive = alive.borrow();
+        for (word_idx, word_x, y) in quadrant_words(quadrant) {
+            let mut word = alive[word_idx];
+            while word != 0 {
+                let x = word_x + word.trailing_zeros() as u16;
+                word &= word - 1;
+                if is_wipe_exempt(exemption, x, y) {
+                    continue;
+                }
+                alive_cells += 1;
+                if let Some(owner) = find_owner(x, y) {
+                    tally[owner] += 1;
+                }
+            }
+        }
+    });
+
+    let mut affected_players: Vec<u8> = (0..MAX_PLAYERS as u8).filter(|&p| tally[p as usize] > 0).collect();
+    affected_players.sort_by_key(|&p| std::cmp::Reverse(tally[p as usize]));
+
+    WipeImpact { quadrant, seconds, alive_cells, affected_players }
+}
+
 #[ic_cdk::query]
 fn get_balance() -> u64 {
     let caller = ic_cdk::api::msg_caller();
     WALLETS.with(|w| *w.borrow().get(&caller).unwrap_or(&0))
 }
 
+#[ic_cdk::query]
+fn get_affordability() -> Affordability {
+    affordability_for(ic_cdk::api::msg_caller())
+}
+
+fn affordability_for(principal: Principal) -> Affordability {
+    let balance = WALLETS.with(|w| *w.borrow().get(&principal).unwrap_or(&0));
+    Affordability {
+        balance,
+        max_placeable_cells: balance / PLACEMENT_COST,
+    }
+}
+
+#[ic_cdk::query]
+fn get_starting_treasury() -> u64 {
+    STARTING_TREASURY.with(|t| *t.borrow())
+}
+
+#[ic_cdk::query]
+fn get_total_burned() -> u64 {
+    TOTAL_BURNED.with(|t| *t.borrow())
+}
+
+#[ic_cdk::query]
+fn get_quadrant_density_limit() -> u32 {
+    QUADRANT_DENSITY_LIMIT.with(|l| *l.borrow())
+}
+
+#[ic_cdk::query]
+fn get_wipe_freeze_generations() -> u64 {
+    WIPE_FREEZE_GENS.with(|f| *f.borrow())
+}
+
+#[ic_cdk::query]
+fn get_placement_burn_bps() -> u64 {
+    PLACEMENT_BURN_BPS.with(|b| *b.borrow())
+}
+
+/// (generations per tick, tick interval in ms)
+#[ic_cdk::query]
+fn get_tick_speed() -> (u32, u64) {
+    (TICK_GENERATIONS.with(|g| *g.borrow()), TICK_INTERVAL.with(|t| *t.borrow()))
+}
+
+#[ic_cdk::query]
+fn get_timings() -> Timings {
+    Timings {
+        wipe_interval_secs: wipe_interval_ns() / 1_000_000_000,
+        grace_period_secs: grace_period_ns() / 1_000_000_000,
+    }
+}
+
+/// Current sudden-death bounds, or None when the whole grid is playable
+#[ic_cdk::query]
+fn get_arena() -> Option<ArenaInfo> {
+    arena_info()
+}
+
 #[ic_cdk::query]
 fn get_generation() -> u64 {
     GENERATION.with(|g| *g.borrow())
 }
 
+#[ic_cdk::query]
+fn get_quadrant_wipes() -> Vec<QuadrantWipe> {
+    QUADRANT_WIPES.with(|qw| qw.borrow().to_vec())
+}
+
+#[ic_cdk::query]
+fn is_independent_wipes() -> bool {
+    INDEPENDENT_WIPES.with(|iw| *iw.borrow())
+}
+
+#[ic_cdk::query]
+fn get_wipe_exemption() -> WipeExemption {
+    WIPE_EXEMPTION.with(|w| *w.borrow())
+}
+
+#[ic_cdk::query]
+fn is_cell_decay_enabled() -> bool {
+    CELL_DECAY_ENABLED.with(|d| *d.borrow())
+}
+
+#[ic_cdk::query]
+fn is_disconnect_grace_enabled() -> bool {
+    DISCONNECT_GRACE_ENABLED.with(|g| *g.borrow())
+}
+
+#[ic_cdk::query]
+fn is_readonly() -> bool {
+    READONLY.with(|r| *r.borrow())
+}
+
 #[ic_cdk::query]
 fn is_frozen() -> bool {
     !is_timer_running()
@@ -1894,17 +4016,186 @@ fn get_alive_cells() -> Vec<(u16, u16)> {
     cells
 }
 
+/// Live cells as (start_idx, run_length, owner) runs of consecutive cell
+/// indices sharing an owner. idx = y * 512 + x (see idx_to_coords), so runs
+/// are row-major and may continue across a row boundary; owner 255 = unowned.
+fn state_rle() -> Vec<(u32, u32, u8)> {
+    let mut runs: Vec<(u32, u32, u8)> = Vec::new();
+    ALIVE.with(|alive| {
+        OWNER.with(|owner| {
+            let alive = alive.borrow();
+            let owner = owner.borrow();
+            for (word_idx, &word) in alive.iter().enumerate() {
+                let mut word = word;
+                while word != 0 {
+                    let bit = word.trailing_zeros() as usize;
+                    word &= word - 1;
+                    let idx = word_idx * 64 + bit;
+                    let slot = owner[idx];
+                    match runs.last_mut() {
+                        Some((start, len, run_owner)) if *run_owner == slot && (*start + *len) as usize == idx => *len += 1,
+                        _ => runs.push((idx as u32, 1, slot)),
+                    }
+                }
+            }
+        })
+    });
+    runs
+}
+
+/// Compact alternative to get_state's cell list for dense boards; see state_rle
+#[ic_cdk::query]
+fn get_state_rle() -> Vec<(u32, u32, u8)> {
+    state_rle()
+}
+
 #[ic_cdk::query]
 fn get_alive_bitmap() -> Vec<u64> {
     ALIVE.with(|a| a.borrow().to_vec())
 }
 
+/// Diffs for every buffered generation after `since_generation`, oldest
+/// first. XOR each diff's words into a bitmap taken at `since_generation` to
+/// catch up. If the first returned generation isn't `since_generation + 1`,
+/// the client fell too far behind and must fetch get_alive_bitmap instead.
+#[ic_cdk::query]
+fn get_recent_diffs(since_generation: u64) -> Vec<GenDiff> {
+    RECENT_DIFFS.with(|diffs| {
+        diffs.borrow()
+            .iter()
+            .filter(|d| d.generation > since_generation)
+            .cloned()
+            .collect()
+    })
+}
+
+/// Siege hits recorded after `since_generation`, oldest first (the buffer
+/// keeps the latest SIEGE_EVENT_LIMIT)
+#[ic_cdk::query]
+fn get_siege_events_since(generation: u64) -> Vec<SiegeEvent> {
+    SIEGE_EVENTS.with(|events| {
+        events.borrow()
+            .iter()
+            .filter(|e| e.generation > generation)
+            .cloned()
+            .collect()
+    })
+}
+
+/// Most recent eliminations, newest first (the log keeps the latest
+/// ELIMINATION_LOG_LIMIT)
+#[ic_cdk::query]
+fn get_eliminations(limit: u32) -> Vec<EliminationRecord> {
+    ELIMINATIONS.with(|log| log.borrow().iter().rev().take(limit as usize).cloned().collect())
+}
+
+/// Net births and deaths from `since_generation` to the latest generation,
+/// folded from the replay buffer. None when `since_generation` is older than
+/// the buffer (or in the future); the client should fall back to get_state.
+#[ic_cdk::query]
+fn get_cell_deltas(since_generation: u64) -> Option<GridDelta> {
+    let current = GENERATION.with(|g| *g.borrow());
+    if since_generation > current {
+        return None;
+    }
+
+    // Net XOR per word across every diff after `since_generation`
+    let net = RECENT_DIFFS.with(|diffs| {
+        let diffs = diffs.borrow();
+        if since_generation < current {
+            let oldest = diffs.front()?.generation;
+            if since_generation + 1 < oldest {
+                return None;
+            }
+        }
+        let mut net: BTreeMap<u16, u64> = BTreeMap::new();
+        for diff in diffs.iter().filter(|d| d.generation > since_generation) {
+            for &(word_idx, mask) in &diff.words {
+                *net.entry(word_idx).or_insert(0) ^= mask;
+            }
+        }
+        Some(net)
+    })?;
+
+    // DIFF_BASE is the bitmap as of the latest recorded generation
+    let mut born = Vec::new();
+    let mut died = Vec::new();
+    DIFF_BASE.with(|base| {
+        OWNER.with(|owner| {
+            let base = base.borrow();
+            let owner = owner.borrow();
+            for (&word_idx, &mask) in &net {
+                let mut bits = mask;
+                while bits != 0 {
+                    let bit_pos = bits.trailing_zeros() as usize;
+                    bits &= bits - 1;
+
+                    let idx = word_idx as usize * 64 + bit_pos;
+                    let (x, y) = idx_to_coords(idx);
+                    if (base[word_idx as usize] >> bit_pos) & 1 == 1 {
+                        let slot = owner[idx];
+                        born.push(SparseCell { x, y, owner: (slot != 255).then_some(slot) });
+                    } else {
+                        died.push((x, y));
+                    }
+                }
+            }
+        })
+    });
+
+    Some(GridDelta { from_gen: since_generation, to_gen: current, born, died })
+}
+
+/// 64-bit FNV-1a digest of the simulation state (generation, alive bitmap,
+/// territories, bases, cell counts). Equal hashes mean identical worlds, so
+/// clients and tests can compare states without shipping the full grid.
+#[ic_cdk::query]
+fn get_state_hash() -> u64 {
+    state_hash()
+}
+
+fn state_hash() -> u64 {
+    const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    let mut hash = FNV_OFFSET;
+    let mut feed = |value: u64| {
+        for byte in value.to_le_bytes() {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(FNV_PRIME);
+        }
+    };
+
+    feed(GENERATION.with(|g| *g.borrow()));
+    ALIVE.with(|a| a.borrow().iter().for_each(|&w| feed(w)));
+    TERRITORY.with(|t| {
+        for pt in t.borrow().iter() {
+            feed(pt.chunk_mask);
+            pt.chunks.iter().flatten().for_each(|&w| feed(w));
+        }
+    });
+    BASES.with(|b| {
+        for base in b.borrow().iter() {
+            match base {
+                Some(base) => {
+                    feed(((base.x as u64) << 16) | base.y as u64);
+                    feed(base.coins);
+                }
+                None => feed(u64::MAX),
+            }
+        }
+    });
+    CELL_COUNTS.with(|cc| cc.borrow().iter().for_each(|&c| feed(c as u64)));
+
+    hash
+}
+
 #[ic_cdk::query]
 fn greet(name: String) -> String {
     format!("Hello, {}! Welcome to Life2 v2.", name)
 }
 
-// Benchmark query functions are in benchmarks.rs
+// Benchmark query functions are in benchmarks.rs; sandbox endpoints in sandbox.rs
 
 /// Helper for benchmarks module to count alive cells
 pub(crate) fn get_alive_cell_count() -> u32 {
@@ -1948,7 +4239,23 @@ fn rebuild_potential_from_alive() {
 
 #[ic_cdk::pre_upgrade]
 fn pre_upgrade() {
-    let state = PersistedState {
+    let state = capture_state();
+    ic_cdk::storage::stable_save((state,)).expect("Failed to save state");
+}
+
+#[ic_cdk::post_upgrade]
+fn post_upgrade() {
+    let (state,): (PersistedState,) =
+        ic_cdk::storage::stable_restore().expect("Failed to restore state");
+    restore_state(state);
+
+    // Restart timer
+    start_timer();
+}
+
+/// Everything that survives an upgrade (also the payload of admin snapshots)
+fn capture_state() -> PersistedState {
+    PersistedState {
         alive: ALIVE.with(|a| a.borrow().to_vec()),
         territory: TERRITORY.with(|t| t.borrow().to_vec()),
         bases: BASES.with(|b| b.borrow().to_vec()),
@@ -1962,16 +4269,36 @@ fn pre_upgrade() {
         last_wipe_ns: LAST_WIPE_NS.with(|lw| *lw.borrow()),
         owner: OWNER.with(|o| o.borrow().to_vec()),
         last_activity_ns: Some(LAST_ACTIVITY_NS.with(|la| *la.borrow())),
-    };
-
-    ic_cdk::storage::stable_save((state,)).expect("Failed to save state");
+        alliances: Some(ALLIANCES.with(|a| a.borrow().to_vec())),
+        cell_decay_enabled: Some(CELL_DECAY_ENABLED.with(|d| *d.borrow())),
+        wipe_exemption: Some(WIPE_EXEMPTION.with(|w| *w.borrow())),
+        recent_diffs: Some(RECENT_DIFFS.with(|d| d.borrow().iter().cloned().collect())),
+        diff_base: Some(DIFF_BASE.with(|b| b.borrow().clone())),
+        readonly: Some(READONLY.with(|r| *r.borrow())),
+        independent_wipes: Some(INDEPENDENT_WIPES.with(|iw| *iw.borrow())),
+        quadrant_wipes: Some(QUADRANT_WIPES.with(|qw| qw.borrow().to_vec())),
+        placement_burn_bps: Some(PLACEMENT_BURN_BPS.with(|b| *b.borrow())),
+        total_burned: Some(TOTAL_BURNED.with(|t| *t.borrow())),
+        wipe_interval_ns: Some(wipe_interval_ns()),
+        grace_period_ns: Some(grace_period_ns()),
+        disconnect_grace_enabled: Some(DISCONNECT_GRACE_ENABLED.with(|g| *g.borrow())),
+        orphans: Some(ORPHANS.with(|o| o.borrow().clone())),
+        siege_events: Some(SIEGE_EVENTS.with(|e| e.borrow().iter().cloned().collect())),
+        starting_treasury: Some(STARTING_TREASURY.with(|t| *t.borrow())),
+        arena: ARENA.with(|a| *a.borrow()),
+        eliminations: Some(ELIMINATIONS.with(|e| e.borrow().iter().cloned().collect())),
+        wipe_freeze_gens: Some(WIPE_FREEZE_GENS.with(|f| *f.borrow())),
+        quadrant_placed_gens: Some(QUADRANT_PLACED_GEN.with(|p| p.borrow().to_vec())),
+        quadrant_density_limit: Some(QUADRANT_DENSITY_LIMIT.with(|l| *l.borrow())),
+        spectators: Some(SPECTATORS.with(|s| s.borrow().iter().copied().collect())),
+        tick_generations: Some(TICK_GENERATIONS.with(|g| *g.borrow())),
+        tick_interval_ms: Some(TICK_INTERVAL.with(|t| *t.borrow())),
+        profiles: Some(PROFILES.with(|pr| pr.borrow().to_vec())),
+    }
 }
 
-#[ic_cdk::post_upgrade]
-fn post_upgrade() {
-    let (state,): (PersistedState,) =
-        ic_cdk::storage::stable_restore().expect("Failed to restore state");
-
+/// Load a captured state and rebuild the transient structures derived from it
+fn restore_state(state: PersistedState) {
     ALIVE.with(|a| {
         let mut alive = a.borrow_mut();
         for (i, &v) in state.alive.iter().enumerate().take(TOTAL_WORDS) {
@@ -1988,7 +4315,11 @@ fn post_upgrade() {
 
     BASES.with(|b| {
         let mut bases = b.borrow_mut();
-        for (i, base) in state.bases.into_iter().enumerate().take(MAX_PLAYERS) {
+        for (i, mut base) in state.bases.into_iter().enumerate().take(MAX_PLAYERS) {
+            // Bases saved before sizing existed are the standard 8x8
+            if let Some(base) = &mut base {
+                base.size.get_or_insert(BASE_SIZE);
+            }
             bases[i] = base;
         }
     });
@@ -2022,7 +4353,62 @@ fn post_upgrade() {
     IS_RUNNING.with(|r| *r.borrow_mut() = state.is_running);
     NEXT_WIPE_QUADRANT.with(|q| *q.borrow_mut() = state.next_wipe_quadrant);
     LAST_WIPE_NS.with(|lw| *lw.borrow_mut() = state.last_wipe_ns);
-    LAST_ACTIVITY_NS.with(|la| *la.borrow_mut() = state.last_activity_ns.unwrap_or_else(ic_cdk::api::time));
+    LAST_ACTIVITY_NS.with(|la| *la.borrow_mut() = state.last_activity_ns.unwrap_or_else(now_ns));
+
+    // Cell ages are not persisted; they restart from zero
+    set_cell_decay_enabled(state.cell_decay_enabled.unwrap_or(false));
+    WIPE_EXEMPTION.with(|w| *w.borrow_mut() = state.wipe_exemption.unwrap_or_default());
+    READONLY.with(|r| *r.borrow_mut() = state.readonly.unwrap_or(false));
+    INDEPENDENT_WIPES.with(|iw| *iw.borrow_mut() = state.independent_wipes.unwrap_or(false));
+    PLACEMENT_BURN_BPS.with(|b| *b.borrow_mut() = state.placement_burn_bps.unwrap_or(0));
+    TOTAL_BURNED.with(|t| *t.borrow_mut() = state.total_burned.unwrap_or(0));
+    WIPE_INTERVAL.with(|w| *w.borrow_mut() = state.wipe_interval_ns.unwrap_or(WIPE_INTERVAL_NS));
+    GRACE_PERIOD.with(|g| *g.borrow_mut() = state.grace_period_ns.unwrap_or(GRACE_PERIOD_NS));
+    set_disconnect_grace_enabled(state.disconnect_grace_enabled.unwrap_or(false));
+    ORPHANS.with(|o| *o.borrow_mut() = state.orphans.unwrap_or_default());
+    SIEGE_EVENTS.with(|e| *e.borrow_mut() = state.siege_events.unwrap_or_default().into());
+    STARTING_TREASURY.with(|t| *t.borrow_mut() = state.starting_treasury.unwrap_or(BASE_COST));
+    ARENA.with(|a| *a.borrow_mut() = state.arena);
+    ELIMINATIONS.with(|e| *e.borrow_mut() = state.eliminations.unwrap_or_default().into());
+    WIPE_FREEZE_GENS.with(|f| *f.borrow_mut() = state.wipe_freeze_gens.unwrap_or(0));
+    QUADRANT_DENSITY_LIMIT.with(|l| *l.borrow_mut() = state.quadrant_density_limit.unwrap_or(DEFAULT_QUADRANT_DENSITY_LIMIT));
+    SPECTATORS.with(|s| *s.borrow_mut() = state.spectators.unwrap_or_default().into_iter().collect());
+    set_tick_speed_values(
+        state.tick_generations.unwrap_or(GENERATIONS_PER_TICK),
+        state.tick_interval_ms.unwrap_or(TICK_INTERVAL_MS),
+    );
+    PROFILES.with(|pr| {
+        let mut pr = pr.borrow_mut();
+        *pr = Default::default();
+        for (i, profile) in state.profiles.unwrap_or_default().into_iter().enumerate().take(MAX_PLAYERS) {
+            pr[i] = profile;
+        }
+    });
+    QUADRANT_PLACED_GEN.with(|p| {
+        let mut p = p.borrow_mut();
+        *p = [None; TOTAL_QUADRANTS as usize];
+        for (i, &placed) in state.quadrant_placed_gens.unwrap_or_default().iter().enumerate().take(TOTAL_QUADRANTS as usize) {
+            p[i] = placed;
+        }
+    });
+    if let Some(quadrant_wipes) = state.quadrant_wipes {
+        QUADRANT_WIPES.with(|qw| {
+            let mut qw = qw.borrow_mut();
+            for (i, &w) in quadrant_wipes.iter().enumerate().take(TOTAL_QUADRANTS as usize) {
+                qw[i] = w;
+            }
+        });
+    }
+
+    // Pending proposals are not persisted; players re-propose after an upgrade
+    if let Some(alliances) = state.alliances {
+        ALLIANCES.with(|a| {
+            let mut a = a.borrow_mut();
+            for (i, &ally) in alliances.iter().enumerate().take(MAX_PLAYERS) {
+                a[i] = ally;
+            }
+        });
+    }
 
     // Restore OWNER cache
     OWNER.with(|o| {
@@ -2034,17 +4420,21 @@ fn post_upgrade() {
 
     // Rebuild transient structures
     rebuild_potential_from_alive();
+    match (state.recent_diffs, state.diff_base) {
+        (Some(diffs), Some(base)) if base.len() == TOTAL_WORDS => {
+            DIFF_BASE.with(|b| *b.borrow_mut() = base);
+            RECENT_DIFFS.with(|d| *d.borrow_mut() = diffs.into_iter().rev().take(RECENT_DIFF_LIMIT).rev().collect());
+        }
+        _ => reset_recent_diffs(),
+    }
     BFS_WORKSPACE.with(|ws| {
         *ws.borrow_mut() = BFSWorkspace::new();
     });
-
-    // Restart timer
-    start_timer();
 }
 
 #[ic_cdk::init]
 fn init() {
-    let now = ic_cdk::api::time();
+    let now = now_ns();
     LAST_WIPE_NS.with(|lw| {
         *lw.borrow_mut() = now;
     });
@@ -0,0 +1,169 @@
+//! Tutorial sandbox
+//!
+//! A private 64x64 toroidal board per caller for experimenting without coins,
+//! bases or wipes. Sandboxes never touch the main grid; they are transient
+//! (not persisted across upgrades) and capped at MAX_SANDBOXES, with the least
+//! recently used one evicted to make room for a newcomer.
+
+use candid::{CandidType, Deserialize, Principal};
+use serde::Serialize;
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+/// Board side length (one u64 word per row)
+pub const SANDBOX_SIZE: u16 = 64;
+
+/// Concurrent sandboxes allowed; past this a new caller evicts the least
+/// recently used sandbox
+pub const MAX_SANDBOXES: usize = 16;
+
+/// Cells placeable per call
+const MAX_SANDBOX_PLACE: usize = 256;
+
+#[derive(Clone)]
+pub(crate) struct Sandbox {
+    pub(crate) generation: u64,
+    /// Row-major alive bitmap, bit x of word y = cell (x, y)
+    pub(crate) alive: [u64; 64],
+    /// Every cell the sandbox's cells have ever occupied
+    pub(crate) territory: [u64; 64],
+    /// Last place or step, for LRU eviction
+    pub(crate) last_touched_ns: u64,
+}
+
+#[derive(CandidType, Deserialize, Serialize)]
+pub struct SandboxState {
+    pub generation: u64,
+    pub alive: Vec<u64>,
+    pub territory: Vec<u64>,
+}
+
+impl Default for Sandbox {
+    fn default() -> Self {
+        Self { generation: 0, alive: [0; 64], territory: [0; 64], last_touched_ns: 0 }
+    }
+}
+
+impl Sandbox {
+    #[cfg(test)]
+    pub(crate) fn is_alive(&self, x: u16, y: u16) -> bool {
+        (self.alive[y as usize] >> x) & 1 == 1
+    }
+
+    /// Plain Conway step on the 64x64 torus
+    pub(crate) fn step(&mut self) {
+        let mut next = [0u64; 64];
+        for (y, next_row) in next.iter_mut().enumerate() {
+            let rows = [self.alive[(y + 63) % 64], self.alive[y], self.alive[(y + 1) % 64]];
+            for x in 0..64 {
+                let mut count = 0;
+                for row in rows {
+                    // rotate_left(1) moves bit x-1 to x; rotate_right(1) moves bit x+1 to x
+                    count += (row.rotate_left(1) >> x) & 1;
+                    count += (row >> x) & 1;
+                    count += (row.rotate_right(1) >> x) & 1;
+                }
+                let alive = (self.alive[y] >> x) & 1;
+                count -= alive;
+                if count == 3 || (alive == 1 && count == 2) {
+                    *next_row |= 1u64 << x;
+                }
+            }
+        }
+        self.alive = next;
+        for (territory, row) in self.territory.iter_mut().zip(self.alive.iter()) {
+            *territory |= row;
+        }
+        self.generation += 1;
+    }
+
+    fn to_state(&self) -> SandboxState {
+        SandboxState {
+            generation: self.generation,
+            alive: self.alive.to_vec(),
+            territory: self.territory.to_vec(),
+        }
+    }
+}
+
+thread_local! {
+    static SANDBOXES: RefCell<HashMap<Principal, Sandbox>> = RefCell::new(HashMap::new());
+}
+
+// =============================================================================
+// INTERNAL (caller passed explicitly)
+// =============================================================================
+
+pub(crate) fn place_for(caller: Principal, cells: &[(u16, u16)]) -> Result<u32, String> {
+    if caller == Principal::anonymous() {
+        return Err("Must be authenticated".to_string());
+    }
+    if cells.len() > MAX_SANDBOX_PLACE {
+        return Err(format!("Max {} cells per call", MAX_SANDBOX_PLACE));
+    }
+    if cells.iter().any(|&(x, y)| x >= SANDBOX_SIZE || y >= SANDBOX_SIZE) {
+        return Err("Coordinates out of range".to_string());
+    }
+
+    SANDBOXES.with(|s| {
+        let mut sandboxes = s.borrow_mut();
+        if !sandboxes.contains_key(&caller) && sandboxes.len() >= MAX_SANDBOXES {
+            let idlest = sandboxes.iter().min_by_key(|(_, sb)| sb.last_touched_ns).map(|(p, _)| *p);
+            if let Some(idlest) = idlest {
+                sandboxes.remove(&idlest);
+            }
+        }
+        let sandbox = sandboxes.entry(caller).or_default();
+        sandbox.last_touched_ns = crate::now_ns();
+        for &(x, y) in cells {
+            sandbox.alive[y as usize] |= 1u64 << x;
+            sandbox.territory[y as usize] |= 1u64 << x;
+        }
+        Ok(cells.len() as u32)
+    })
+}
+
+pub(crate) fn step_for(caller: Principal) -> Result<u64, String> {
+    SANDBOXES.with(|s| {
+        let mut sandboxes = s.borrow_mut();
+        let sandbox = sandboxes.get_mut(&caller).ok_or("No sandbox; place cells first")?;
+        sandbox.last_touched_ns = crate::now_ns();
+        sandbox.step();
+        Ok(sandbox.generation)
+    })
+}
+
+pub(crate) fn reset_for(caller: Principal) {
+    SANDBOXES.with(|s| {
+        s.borrow_mut().remove(&caller);
+    });
+}
+
+pub(crate) fn get_for(caller: Principal) -> Option<Sandbox> {
+    SANDBOXES.with(|s| s.borrow().get(&caller).cloned())
+}
+
+// =============================================================================
+// ENDPOINTS
+// =============================================================================
+
+#[ic_cdk::update]
+pub fn sandbox_place(cells: Vec<(u16, u16)>) -> Result<u32, String> {
+    place_for(ic_cdk::api::msg_caller(), &cells)
+}
+
+#[ic_cdk::update]
+pub fn sandbox_step() -> Result<u64, String> {
+    step_for(ic_cdk::api::msg_caller())
+}
+
+/// Discard the caller's sandbox (frees its slot)
+#[ic_cdk::update]
+pub fn sandbox_reset() {
+    reset_for(ic_cdk::api::msg_caller());
+}
+
+#[ic_cdk::query]
+pub fn get_sandbox() -> Option<SandboxState> {
+    get_for(ic_cdk::api::msg_caller()).map(|s| s.to_state())
+}
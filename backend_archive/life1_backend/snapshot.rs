@@ -0,0 +1,164 @@
+//! Admin state snapshots
+//!
+//! A snapshot is the upgrade `PersistedState`, Candid-encoded and run-length
+//! compressed, behind a one-byte format version. Exports can be taken at any
+//! time; imports are only accepted while the game is paused or empty, and
+//! replace the whole game state.
+
+use crate::{
+    capture_state, is_timer_running, require_controller, restore_state, start_timer, PersistedState,
+    ALLIANCE_PROPOSALS, CHUNK_SIZE, IS_RUNNING, LAST_RELOCATE_NS, MAX_PLAYERS, PLAYERS, TOTAL_CELLS, TOTAL_WORDS,
+};
+
+/// Bump when the snapshot layout (not PersistedState's fields) changes
+pub const SNAPSHOT_VERSION: u8 = 1;
+
+/// Longest run / literal block one PackBits control byte can describe
+const MAX_RUN: usize = 128;
+
+// =============================================================================
+// ENCODING
+// =============================================================================
+
+pub(crate) fn encode_snapshot(state: &PersistedState) -> Vec<u8> {
+    let raw = candid::encode_one(state).expect("Failed to encode snapshot");
+    let mut out = Vec::with_capacity(raw.len() / 8 + 1);
+    out.push(SNAPSHOT_VERSION);
+    pack_bits(&raw, &mut out);
+    out
+}
+
+pub(crate) fn decode_snapshot(bytes: &[u8]) -> Result<PersistedState, String> {
+    let (&version, packed) = bytes.split_first().ok_or("Empty snapshot")?;
+    if version != SNAPSHOT_VERSION {
+        return Err(format!("Unsupported snapshot version {} (expected {})", version, SNAPSHOT_VERSION));
+    }
+    let raw = unpack_bits(packed)?;
+    candid::decode_one(&raw).map_err(|e| format!("Corrupt snapshot: {}", e))
+}
+
+/// PackBits: control byte n < 128 copies the next n + 1 bytes; n > 128
+/// repeats the next byte 257 - n times. The grid state is mostly zero words
+/// and unowned (255) cells, so runs dominate.
+fn pack_bits(input: &[u8], out: &mut Vec<u8>) {
+    let mut i = 0;
+    while i < input.len() {
+        let run = input[i..].iter().take(MAX_RUN).take_while(|&&b| b == input[i]).count();
+        if run >= 2 {
+            out.push((257 - run) as u8);
+            out.push(input[i]);
+            i += run;
+            continue;
+        }
+
+        // Literal block up to the next run of two or more
+        let start = i;
+        while i < input.len() && i - start < MAX_RUN && !(i + 1 < input.len() && input[i] == input[i + 1]) {
+            i += 1;
+        }
+        out.push((i - start - 1) as u8);
+        out.extend_from_slice(&input[start..i]);
+    }
+}
+
+fn unpack_bits(input: &[u8]) -> Result<Vec<u8>, String> {
+    let mut out = Vec::with_capacity(input.len() * 4);
+    let mut i = 0;
+    while i < input.len() {
+        let control = input[i] as usize;
+        i += 1;
+        match control {
+            0..=127 => {
+                let end = i + control + 1;
+                let literal = input.get(i..end).ok_or("Truncated snapshot")?;
+                out.extend_from_slice(literal);
+                i = end;
+            }
+            128 => {}
+            _ => {
+                let byte = *input.get(i).ok_or("Truncated snapshot")?;
+                out.resize(out.len() + 257 - control, byte);
+                i += 1;
+            }
+        }
+    }
+    Ok(out)
+}
+
+// =============================================================================
+// IMPORT
+// =============================================================================
+
+/// Replace the game state with a snapshot. Refused while a game is live.
+/// Transient per-slot state (alliance proposals, relocation cooldowns) is
+/// cleared, since it referred to the replaced world.
+pub(crate) fn import_snapshot(bytes: &[u8]) -> Result<(), String> {
+    let running = IS_RUNNING.with(|r| *r.borrow());
+    let empty = PLAYERS.with(|p| p.borrow().iter().all(|p| p.is_none()));
+    if running && !empty {
+        return Err("Pause the game (or empty it) before importing".to_string());
+    }
+
+    let state = decode_snapshot(bytes)?;
+    validate_shape(&state)?;
+    restore_state(state);
+    ALLIANCE_PROPOSALS.with(|p| *p.borrow_mut() = [None; MAX_PLAYERS]);
+    LAST_RELOCATE_NS.with(|lr| *lr.borrow_mut() = [None; MAX_PLAYERS]);
+    Ok(())
+}
+
+/// restore_state truncates or zero-fills mismatched arrays, which is right for
+/// upgrades but would silently mangle a hand-edited or foreign snapshot
+fn validate_shape(state: &PersistedState) -> Result<(), String> {
+    let check = |name: &str, len: usize, expected: usize| {
+        if len == expected {
+            Ok(())
+        } else {
+            Err(format!("Malformed snapshot: {} has {} entries (expected {})", name, len, expected))
+        }
+    };
+    check("alive", state.alive.len(), TOTAL_WORDS)?;
+    check("owner", state.owner.len(), TOTAL_CELLS)?;
+    check("players", state.players.len(), MAX_PLAYERS)?;
+    check("bases", state.bases.len(), MAX_PLAYERS)?;
+    check("territory", state.territory.len(), MAX_PLAYERS)?;
+    check("cell_counts", state.cell_counts.len(), MAX_PLAYERS)?;
+    check("zero_cells_since", state.zero_cells_since.len(), MAX_PLAYERS)?;
+    if let Some(alliances) = &state.alliances {
+        check("alliances", alliances.len(), MAX_PLAYERS)?;
+    }
+    if let Some(profiles) = &state.profiles {
+        check("profiles", profiles.len(), MAX_PLAYERS)?;
+    }
+    // Territory lookups index chunks by mask popcount and assume full chunks
+    for (slot, territory) in state.territory.iter().enumerate() {
+        check(&format!("territory[{}].chunks", slot), territory.chunks.len(), territory.chunk_mask.count_ones() as usize)?;
+        for chunk in &territory.chunks {
+            check(&format!("territory[{}] chunk", slot), chunk.len(), CHUNK_SIZE as usize)?;
+        }
+    }
+    Ok(())
+}
+
+// =============================================================================
+// ENDPOINTS
+// =============================================================================
+
+/// Controller-only: versioned, compressed dump of the full game state
+#[ic_cdk::query]
+pub fn admin_export_snapshot() -> Result<Vec<u8>, String> {
+    require_controller()?;
+    Ok(encode_snapshot(&capture_state()))
+}
+
+/// Controller-only: restore a snapshot from admin_export_snapshot
+#[ic_cdk::update]
+pub fn admin_import_snapshot(bytes: Vec<u8>) -> Result<(), String> {
+    require_controller()?;
+    import_snapshot(&bytes)?;
+
+    if IS_RUNNING.with(|r| *r.borrow()) && !is_timer_running() {
+        start_timer();
+    }
+    Ok(())
+}
@@ -1,5 +1,16 @@
 use super::*;
 
+/// World-state thread_locals (OWNER alone is 256KB) are built on the stack in
+/// debug builds, so tests that touch the grid run on a thread with room to spare.
+fn with_world<F: FnOnce() + Send + 'static>(f: F) {
+    std::thread::Builder::new()
+        .stack_size(32 * 1024 * 1024)
+        .spawn(f)
+        .expect("spawn test thread")
+        .join()
+        .expect("test thread panicked");
+}
+
 /// Reference implementation using individual bit extraction (the old approach)
 fn count_neighbors_reference(
     bit_pos: usize,
@@ -303,3 +314,1776 @@ fn test_orthogonal_neighbors_corner_bottom_right() {
     assert_eq!(neighbors[2], (511, 510));  // North normal
     assert_eq!(neighbors[3], (511, 0));    // South wraps
 }
+
+// =============================================================================
+// ECONOMY TESTS
+// =============================================================================
+
+#[test]
+fn test_affordability_scales_with_balance() {
+    let player = Principal::from_slice(&[1]);
+
+    WALLETS.with(|w| w.borrow_mut().insert(player, 800));
+    let full = affordability_for(player);
+    assert_eq!(full.balance, 800);
+    assert_eq!(full.max_placeable_cells, 800 / PLACEMENT_COST);
+
+    WALLETS.with(|w| w.borrow_mut().insert(player, 400));
+    let half = affordability_for(player);
+    assert_eq!(half.balance, 400);
+    assert_eq!(half.max_placeable_cells * 2, full.max_placeable_cells);
+}
+
+#[test]
+fn test_affordability_unknown_principal_is_zero() {
+    let stranger = Principal::from_slice(&[2]);
+    let info = affordability_for(stranger);
+    assert_eq!(info.balance, 0);
+    assert_eq!(info.max_placeable_cells, 0);
+}
+
+// =============================================================================
+// CLOCK TESTS
+// =============================================================================
+
+#[test]
+fn test_injected_clock_stamps_grace_period_start() {
+    with_world(|| {
+        set_test_time_ns(5_000_000_000);
+
+        // Player 0 owns a single live cell in quadrant 0, base elsewhere
+        BASES.with(|b| b.borrow_mut()[0] = Some(Base { x: 300, y: 300, coins: BASE_COST, size: Some(BASE_SIZE) }));
+        set_alive(10, 10);
+        set_territory(0, 10, 10);
+        CELL_COUNTS.with(|cc| cc.borrow_mut()[0] = 1);
+
+        wipe_quadrant(0);
+
+        assert_eq!(CELL_COUNTS.with(|cc| cc.borrow()[0]), 0);
+        assert_eq!(ZERO_CELLS_SINCE.with(|zcs| zcs.borrow()[0]), Some(5_000_000_000));
+    });
+}
+
+#[test]
+fn test_injected_clock_drives_wipe_countdown() {
+    LAST_WIPE_NS.with(|lw| *lw.borrow_mut() = 0);
+    set_test_time_ns(30_000_000_000);
+
+    let info = get_next_wipe();
+    assert_eq!(info.seconds_until, WIPE_INTERVAL_NS / 1_000_000_000 - 30);
+}
+
+// =============================================================================
+// DETERMINISM CORPUS
+// =============================================================================
+
+/// Golden (seed, state hash) pairs after DETERMINISM_GENERATIONS steps. If a
+/// simulation change legitimately alters the rules, regenerate these and note
+/// it in the commit; an unexpected mismatch means the engine drifted.
+const DETERMINISM_GENERATIONS: u32 = 200;
+const DETERMINISM_CORPUS: [(u64, u64); 4] = [
+    (0x0000_0000_0000_0001, 0x22bf_d88c_46a4_2852),
+    (0x0000_0000_0000_2a2a, 0xa381_8b1f_3ece_2801),
+    (0x0bad_5eed_dead_beef, 0x3ac7_aed6_cc8e_c075),
+    (0xffff_ffff_ffff_fffe, 0x4875_92c2_5fa0_d964),
+];
+
+/// Small xorshift64 generator so seeding needs no extra dependencies
+fn xorshift(state: &mut u64) -> u64 {
+    *state ^= *state << 13;
+    *state ^= *state >> 7;
+    *state ^= *state << 17;
+    *state
+}
+
+/// Give four players a base and scatter ~120 random cells around each one
+fn seed_random_world(seed: u64) {
+    let mut rng = seed;
+    for player in 0..4usize {
+        let bx = 64 + (player as u16 % 2) * 256;
+        let by = 64 + (player as u16 / 2) * 256;
+        PLAYERS.with(|p| p.borrow_mut()[player] = Some(Principal::from_slice(&[player as u8 + 1])));
+        BASES.with(|b| b.borrow_mut()[player] = Some(Base { x: bx, y: by, coins: BASE_COST, size: Some(BASE_SIZE) }));
+
+        for _ in 0..120 {
+            let r = xorshift(&mut rng);
+            let x = bx + 10 + (r % 24) as u16;
+            let y = by + 10 + ((r >> 16) % 24) as u16;
+            if !is_alive(x, y) {
+                set_alive(x, y);
+                set_territory(player, x, y);
+                CELL_COUNTS.with(|cc| cc.borrow_mut()[player] += 1);
+            }
+        }
+    }
+    rebuild_potential_from_alive();
+}
+
+fn run_seed(seed: u64) -> u64 {
+    let (tx, rx) = std::sync::mpsc::channel();
+    with_world(move || {
+        set_test_time_ns(1_000_000_000);
+        seed_random_world(seed);
+        for _ in 0..DETERMINISM_GENERATIONS {
+            step_generation();
+        }
+        tx.send(state_hash()).expect("send hash");
+    });
+    rx.recv().expect("receive hash")
+}
+
+#[test]
+fn test_determinism_corpus_matches_golden_hashes() {
+    for (seed, expected) in DETERMINISM_CORPUS {
+        let actual = run_seed(seed);
+        assert_eq!(actual, expected, "seed {:#x}: got {:#018x}", seed, actual);
+    }
+}
+
+#[test]
+fn test_state_hash_tracks_changes() {
+    with_world(|| {
+        let empty = state_hash();
+        set_alive(5, 5);
+        let one_cell = state_hash();
+        assert_ne!(empty, one_cell);
+
+        clear_alive_idx(coords_to_idx(5, 5));
+        assert_eq!(state_hash(), empty);
+    });
+}
+
+// =============================================================================
+// TERRITORY EXPORT TESTS
+// =============================================================================
+
+#[test]
+fn test_territory_chunks_ascending_and_match_cells() {
+    with_world(|| {
+        // Chunk 7 (x=448..) is claimed before chunk 0 to exercise ordering
+        set_territory(2, 450, 3);
+        set_territory(2, 1, 1);
+        set_territory(2, 65, 70);
+
+        let chunks = get_territory_chunks(2);
+        let indices: Vec<u8> = chunks.iter().map(|(idx, _)| *idx).collect();
+        assert_eq!(indices, vec![0, 7, 9]);
+
+        let bits: u32 = chunks.iter().flat_map(|(_, words)| words.iter()).map(|w| w.count_ones()).sum();
+        assert_eq!(bits, 3);
+        assert_eq!(chunks[0].1[1], 1u64 << 1);
+
+        // Fixed-size chunks still encode as plain `vec nat64` on the wire
+        let (_, words): (u8, Vec<u64>) = candid::decode_one(&candid::encode_one(chunks[1]).unwrap()).unwrap();
+        assert_eq!(words.len(), 64);
+    });
+}
+
+#[test]
+fn test_territory_chunks_empty_for_bad_or_empty_slot() {
+    with_world(|| {
+        assert!(get_territory_chunks(MAX_PLAYERS as u8).is_empty());
+        assert!(get_territory_chunks(255).is_empty());
+        assert!(get_territory_chunks(0).is_empty());
+    });
+}
+
+// =============================================================================
+// ALLIANCE TESTS
+// =============================================================================
+
+fn seat_players(count: usize) {
+    PLAYERS.with(|p| {
+        let mut p = p.borrow_mut();
+        for (slot, player) in p.iter_mut().enumerate().take(count) {
+            *player = Some(Principal::from_slice(&[slot as u8 + 1]));
+        }
+    });
+}
+
+fn place_owned(player: usize, x: u16, y: u16) {
+    set_alive(x, y);
+    set_territory(player, x, y);
+    CELL_COUNTS.with(|cc| cc.borrow_mut()[player] += 1);
+}
+
+#[test]
+fn test_allied_players_grow_cooperatively() {
+    // L-tromino of three different owners: the fourth corner of the 2x2 block
+    // is born next generation. The generation is chosen so that, unallied,
+    // the three-way tie goes to slot 0; allied, slots 1 and 2 outvote it.
+    fn grow(allied: bool) -> ([u32; 3], Option<usize>) {
+        let (tx, rx) = std::sync::mpsc::channel();
+        with_world(move || {
+            seat_players(3);
+            if allied {
+                propose_alliance_for(1, 2).unwrap();
+                accept_alliance_for(2, 1).unwrap();
+            }
+            place_owned(0, 10, 10);
+            place_owned(1, 11, 10);
+            place_owned(2, 10, 11);
+            rebuild_potential_from_alive();
+
+            let generation = (0u64..)
+                .find(|&g| {
+                    GENERATION.with(|gen| *gen.borrow_mut() = g);
+                    tie_break_index(coords_to_idx(11, 11), 3) == 0
+                })
+                .unwrap();
+            GENERATION.with(|gen| *gen.borrow_mut() = generation);
+            step_generation();
+
+            assert!(is_alive(11, 11));
+            let counts = CELL_COUNTS.with(|cc| {
+                let cc = cc.borrow();
+                [cc[0], cc[1], cc[2]]
+            });
+            tx.send((counts, find_owner(11, 11))).unwrap();
+        });
+        rx.recv().unwrap()
+    }
+
+    let (counts, owner) = grow(false);
+    assert_eq!(owner, Some(0));
+    assert_eq!(counts, [2, 1, 1]);
+
+    let (counts, owner) = grow(true);
+    assert!(matches!(owner, Some(1) | Some(2)));
+    assert_eq!(counts[0], 1);
+    assert_eq!(counts[1] + counts[2], 3);
+}
+
+#[test]
+fn test_birth_goes_to_alliance_member_with_most_parents() {
+    with_world(|| {
+        seat_players(3);
+        propose_alliance_for(0, 1).unwrap();
+        accept_alliance_for(1, 0).unwrap();
+
+        // find_birth_owner doesn't re-check the neighbor count, so five
+        // parents can be used: slot 0 x1, slot 1 x2, slot 2 x2. Unallied
+        // slots 1 and 2 would tie; allied, {0, 1} wins three to two and the
+        // birth goes to slot 1, the member with more parents.
+        place_owned(0, 20, 21);
+        place_owned(1, 20, 20);
+        place_owned(1, 21, 20);
+        place_owned(2, 22, 20);
+        place_owned(2, 22, 21);
+        let owner = find_birth_owner(21, 21, 1, 1, 1, 1, 1, 0, 0, 0, coords_to_idx(21, 21));
+        assert_eq!(owner, Some(1));
+    });
+}
+
+#[test]
+fn test_alliance_lifecycle() {
+    with_world(|| {
+        seat_players(3);
+
+        assert!(accept_alliance_for(1, 0).is_err(), "no proposal yet");
+        assert!(propose_alliance_for(0, 0).is_err());
+        assert!(propose_alliance_for(0, 5).is_err(), "empty slot");
+
+        propose_alliance_for(0, 1).unwrap();
+        assert!(accept_alliance_for(2, 0).is_err(), "proposal was for slot 1");
+        accept_alliance_for(1, 0).unwrap();
+        assert_eq!(ALLIANCES.with(|a| a.borrow()[0]), Some(1));
+        assert_eq!(ALLIANCES.with(|a| a.borrow()[1]), Some(0));
+        assert!(propose_alliance_for(2, 0).is_err(), "target already allied");
+
+        break_alliance_for(1).unwrap();
+        assert_eq!(ALLIANCES.with(|a| *a.borrow()), [None; MAX_PLAYERS]);
+        assert!(break_alliance_for(0).is_err());
+
+        // Elimination dissolves alliances and drops proposals to the slot
+        propose_alliance_for(1, 2).unwrap();
+        propose_alliance_for(2, 0).unwrap();
+        accept_alliance_for(0, 2).unwrap();
+        eliminate_player(2, EliminationReason::VoluntaryLeave);
+        assert_eq!(ALLIANCES.with(|a| a.borrow()[0]), None);
+        assert_eq!(ALLIANCE_PROPOSALS.with(|p| p.borrow()[1]), None);
+    });
+}
+
+// =============================================================================
+// CELL DECAY TESTS
+// =============================================================================
+
+fn run_block(decay: bool, generations: u16) -> bool {
+    let (tx, rx) = std::sync::mpsc::channel();
+    with_world(move || {
+        set_cell_decay_enabled(decay);
+        for (x, y) in [(40, 40), (41, 40), (40, 41), (41, 41)] {
+            set_alive(x, y);
+        }
+        rebuild_potential_from_alive();
+        for _ in 0..generations {
+            step_generation();
+        }
+        tx.send([(40, 40), (41, 40), (40, 41), (41, 41)].iter().all(|&(x, y)| is_alive(x, y))).unwrap();
+    });
+    rx.recv().unwrap()
+}
+
+#[test]
+fn test_stable_block_decays_when_enabled() {
+    assert!(run_block(true, MAX_CELL_AGE - 1));
+    assert!(!run_block(true, MAX_CELL_AGE));
+}
+
+#[test]
+fn test_stable_block_persists_when_decay_disabled() {
+    assert!(run_block(false, MAX_CELL_AGE + 10));
+}
+
+#[test]
+fn test_set_cell_decay_requires_controller() {
+    with_world(|| {
+        assert_eq!(set_cell_decay(true), Err("Only controllers can do this".to_string()));
+        assert!(!is_cell_decay_enabled());
+
+        set_test_controller(true);
+        assert!(set_cell_decay(true).is_ok());
+        assert!(is_cell_decay_enabled());
+    });
+}
+
+// =============================================================================
+// BASE SIZE TESTS
+// =============================================================================
+
+#[test]
+fn test_validate_base_size() {
+    for size in [6, 8, 10, 16] {
+        assert!(validate_base_size(size).is_ok(), "size {}", size);
+    }
+    for size in [0, 2, 4, 7, 9, 18] {
+        assert!(validate_base_size(size).is_err(), "size {}", size);
+    }
+}
+
+#[test]
+fn test_sized_base_footprint_and_overlap() {
+    let big = Base { x: 100, y: 100, coins: BASE_COST, size: Some(16) };
+    assert!(is_in_base(&big, 115, 115));
+    assert!(!is_in_base(&big, 116, 100));
+
+    // An 8x8 base starting inside the 16x16 footprint overlaps it...
+    assert!(bases_would_overlap(110, 110, 8, &big));
+    // ...and so does one whose own footprint reaches into it from above-left
+    assert!(bases_would_overlap(93, 93, 8, &big));
+    assert!(!bases_would_overlap(92, 92, 8, &big));
+    assert!(!bases_would_overlap(116, 100, 8, &big));
+
+    // Toroidal wrap across the grid edge
+    let edge = Base { x: 508, y: 0, coins: BASE_COST, size: Some(8) };
+    assert!(is_in_base(&edge, 2, 3));
+    assert!(bases_would_overlap(2, 2, 6, &edge));
+}
+
+#[test]
+fn test_toroidal_distance_takes_the_short_way_round() {
+    assert_eq!(toroidal_distance(10, 10, 10, 10), 0);
+    assert_eq!(toroidal_distance(10, 10, 13, 20), 10);
+    assert_eq!(toroidal_distance(2, 0, 508, 0), 6);
+    assert_eq!(toroidal_distance(0, 500, 0, 4), 16);
+    assert_eq!(toroidal_distance(0, 0, 256, 256), 256);
+}
+
+#[test]
+fn test_nearest_enemy_base() {
+    with_world(|| {
+        let base = |x, y| Some(Base { x, y, coins: BASE_COST, size: Some(BASE_SIZE) });
+        BASES.with(|b| {
+            let mut b = b.borrow_mut();
+            b[0] = base(10, 10);
+            b[3] = base(200, 200);
+            b[5] = base(490, 20); // 32 away across the left edge
+        });
+        assert_eq!(nearest_enemy_base(0), Some((5, 32)));
+        assert_eq!(nearest_enemy_base(1), None);
+
+        BASES.with(|b| {
+            let mut b = b.borrow_mut();
+            b[3] = None;
+            b[5] = None;
+        });
+        assert_eq!(nearest_enemy_base(0), None);
+    });
+}
+
+#[test]
+fn test_legacy_base_defaults_to_standard_size() {
+    #[derive(CandidType)]
+    struct LegacyBase {
+        x: u16,
+        y: u16,
+        coins: u64,
+    }
+
+    let bytes = candid::encode_one(LegacyBase { x: 3, y: 4, coins: 50 }).unwrap();
+    let base: Base = candid::decode_one(&bytes).unwrap();
+    assert_eq!((base.x, base.y, base.coins, base.size), (3, 4, 50, None));
+    assert_eq!(base.size(), BASE_SIZE);
+}
+
+// =============================================================================
+// REPLAY DIFF TESTS
+// =============================================================================
+
+#[test]
+fn test_recent_diffs_reconstruct_current_board() {
+    with_world(|| {
+        set_test_time_ns(1_000_000_000);
+        seed_random_world(0x5eed);
+        reset_recent_diffs();
+
+        for _ in 0..50 {
+            step_generation();
+        }
+        let since = GENERATION.with(|g| *g.borrow());
+        let mut board = ALIVE.with(|a| a.borrow().to_vec());
+
+        // Run past the buffer limit, with a manual placement mid-way
+        for i in 0..RECENT_DIFF_LIMIT {
+            if i == 40 {
+                set_alive(300, 300);
+                mark_with_neighbors_potential(coords_to_idx(300, 300));
+            }
+            step_generation();
+        }
+
+        let diffs = get_recent_diffs(since);
+        assert_eq!(diffs.len(), RECENT_DIFF_LIMIT);
+        assert_eq!(diffs[0].generation, since + 1);
+        for diff in &diffs {
+            for &(word_idx, mask) in &diff.words {
+                board[word_idx as usize] ^= mask;
+            }
+        }
+        assert_eq!(board, ALIVE.with(|a| a.borrow().to_vec()));
+
+        // One more step evicts the oldest diff, leaving a gap for `since`
+        step_generation();
+        assert_eq!(get_recent_diffs(since)[0].generation, since + 2);
+        assert!(get_recent_diffs(since + RECENT_DIFF_LIMIT as u64 + 1).is_empty());
+    });
+}
+
+#[test]
+fn test_idle_ticks_keep_diffs_contiguous() {
+    with_world(|| {
+        set_test_time_ns(1_000_000_000);
+        let since = GENERATION.with(|g| *g.borrow());
+        tick();
+        let current = GENERATION.with(|g| *g.borrow());
+        assert!(current > since);
+
+        // Nothing changed, but every skipped generation has an (empty) diff
+        let diffs = get_recent_diffs(since);
+        let generations: Vec<u64> = diffs.iter().map(|d| d.generation).collect();
+        assert_eq!(generations, (since + 1..=current).collect::<Vec<_>>());
+        assert!(diffs.iter().all(|d| d.words.is_empty()));
+        assert!(get_cell_deltas(since).is_some_and(|d| d.born.is_empty() && d.died.is_empty()));
+    });
+}
+
+// =============================================================================
+// TIE-BREAK TESTS
+// =============================================================================
+
+#[test]
+fn test_three_way_ties_are_uniform() {
+    with_world(|| {
+        seat_players(3);
+        place_owned(0, 100, 100);
+        place_owned(1, 101, 100);
+        place_owned(2, 100, 101);
+        let cell_idx = coords_to_idx(101, 101);
+
+        const TRIALS: u64 = 10_000;
+        let mut wins = [0u64; 3];
+        for generation in 0..TRIALS {
+            GENERATION.with(|g| *g.borrow_mut() = generation);
+            // Parents at nw, n, w of (101, 101)
+            let owner = find_birth_owner(101, 101, 1, 1, 0, 1, 0, 0, 0, 0, cell_idx);
+            wins[owner.unwrap()] += 1;
+        }
+
+        // Each slot within 2 percentage points of a third
+        for (slot, &count) in wins.iter().enumerate() {
+            let share = count as f64 / TRIALS as f64;
+            assert!((share - 1.0 / 3.0).abs() < 0.02, "slot {} won {:.3}", slot, share);
+        }
+    });
+}
+
+#[test]
+fn test_tie_break_is_deterministic() {
+    with_world(|| {
+        GENERATION.with(|g| *g.borrow_mut() = 42);
+        let picks: Vec<usize> = (0..64).map(|cell| tie_break_index(cell, 5)).collect();
+        let again: Vec<usize> = (0..64).map(|cell| tie_break_index(cell, 5)).collect();
+        assert_eq!(picks, again);
+        assert!(picks.iter().all(|&p| p < 5));
+    });
+}
+
+// =============================================================================
+// PLACEMENT VALIDATION TESTS
+// =============================================================================
+
+#[test]
+fn test_placement_failures_lists_every_bad_cell() {
+    with_world(|| {
+        let player = Principal::from_slice(&[1]);
+        seat_players(1);
+        let base = Base { x: 200, y: 200, coins: BASE_COST, size: Some(BASE_SIZE) };
+        BASES.with(|b| b.borrow_mut()[0] = Some(base.clone()));
+        WALLETS.with(|w| w.borrow_mut().insert(player, 500));
+        set_alive(201, 201);
+
+        let cells = vec![(202, 202), (201, 201), (203, 203), (50, 50), (-4, 600), (600, 10), (202, 202), (50, 50)];
+        let failures = placement_failures(0, &base, &cells);
+        assert_eq!(
+            failures,
+            vec![
+                (201, 201, "Cell already alive".to_string()),
+                (50, 50, "Not your territory".to_string()),
+                (-4, 600, "Coordinates out of range".to_string()),
+                (600, 10, "Coordinates out of range".to_string()),
+                (202, 202, "Duplicate cell in batch".to_string()),
+                (50, 50, "Not your territory".to_string()),
+            ]
+        );
+
+        // First-error path used by place_cells reports the same leading reason
+        let first = cells.iter().try_for_each(|&(x, y)| validate_placement(0, &base, x, y));
+        assert_eq!(first, Err("Cell already alive".to_string()));
+    });
+}
+
+#[test]
+fn test_quadrant_density_limit_caps_placements() {
+    with_world(|| {
+        seat_players(2);
+        set_quadrant_density(3).unwrap();
+        place_owned(0, 10, 10);
+        place_owned(0, 11, 10);
+        place_owned(1, 12, 10); // another player's cells don't count
+
+        assert_eq!(player_cells_in_quadrant(0, 0), 2);
+        assert!(check_quadrant_density(0, &[(10, 11)]).is_ok());
+        assert_eq!(
+            check_quadrant_density(0, &[(10, 11), (11, 11)]),
+            Err("Quadrant density limit reached".to_string())
+        );
+        // Other quadrants have their own budget
+        assert!(check_quadrant_density(0, &[(10, 11), (200, 200), (201, 200), (202, 200)]).is_ok());
+
+        assert!(set_quadrant_density(0).is_err());
+        assert!(set_quadrant_density(QUADRANT_SIZE as u32 * QUADRANT_SIZE as u32 + 1).is_err());
+    });
+}
+
+#[test]
+fn test_apply_placement_charges_and_places() {
+    with_world(|| {
+        let player = Principal::from_slice(&[1]);
+        seat_players(1);
+        let base = Base { x: 200, y: 200, coins: BASE_COST, size: Some(BASE_SIZE) };
+        BASES.with(|b| b.borrow_mut()[0] = Some(base.clone()));
+        WALLETS.with(|w| w.borrow_mut().insert(player, 500));
+
+        let cells = vec![(202, 202), (203, 203)];
+        assert!(placement_failures(0, &base, &cells).is_empty());
+        assert_eq!(placement_context(player, cells.len()).map(|(slot, _)| slot), Ok(0));
+        apply_placement(player, 0, &cells);
+
+        assert!(is_alive(202, 202) && is_alive(203, 203));
+        assert_eq!(WALLETS.with(|w| w.borrow()[&player]), 500 - 2 * PLACEMENT_COST);
+        assert_eq!(BASES.with(|b| b.borrow()[0].as_ref().unwrap().coins), BASE_COST + 2 * PLACEMENT_COST);
+        assert_eq!(CELL_COUNTS.with(|cc| cc.borrow()[0]), 2);
+    });
+}
+
+// =============================================================================
+// WIPE EXEMPTION TESTS
+// =============================================================================
+
+fn wipe_with(exemption: WipeExemption) -> (bool, bool, bool) {
+    let (tx, rx) = std::sync::mpsc::channel();
+    with_world(move || {
+        set_test_time_ns(1_000_000_000);
+        WIPE_EXEMPTION.with(|w| *w.borrow_mut() = exemption);
+        seat_players(1);
+        BASES.with(|b| b.borrow_mut()[0] = Some(Base { x: 20, y: 20, coins: BASE_COST, size: Some(BASE_SIZE) }));
+
+        place_owned(0, 22, 22); // base interior
+        place_owned(0, 40, 40); // player territory, open field
+        set_alive(90, 90); // unowned
+
+        wipe_quadrant(0);
+        tx.send((is_alive(22, 22), is_alive(40, 40), is_alive(90, 90))).unwrap();
+    });
+    rx.recv().unwrap()
+}
+
+#[test]
+fn test_wipe_exempts_base_interior_when_configured() {
+    assert_eq!(wipe_with(WipeExemption::None), (false, false, false));
+    assert_eq!(wipe_with(WipeExemption::Bases), (true, false, false));
+    assert_eq!(wipe_with(WipeExemption::BasesAndTerritory), (true, true, false));
+}
+
+#[test]
+fn test_set_wipe_exemption_requires_controller() {
+    with_world(|| {
+        assert_eq!(set_wipe_exemption(WipeExemption::BasesAndTerritory), Err("Only controllers can do this".to_string()));
+        assert_eq!(get_wipe_exemption(), WipeExemption::None);
+
+        set_test_controller(true);
+        assert!(set_wipe_exemption(WipeExemption::Bases).is_ok());
+        assert_eq!(get_wipe_exemption(), WipeExemption::Bases);
+    });
+}
+
+#[test]
+fn test_fresh_placements_freeze_quadrant_wipe() {
+    with_world(|| {
+        seat_players(1);
+        let caller = Principal::from_slice(&[1]);
+        BASES.with(|b| b.borrow_mut()[0] = Some(Base { x: 20, y: 20, coins: 10, size: Some(BASE_SIZE) }));
+        WALLETS.with(|w| w.borrow_mut().insert(caller, 10));
+        assert!(set_wipe_freeze(MAX_WIPE_FREEZE_GENS + 1).is_err());
+        set_wipe_freeze(50).unwrap();
+
+        GENERATION.with(|g| *g.borrow_mut() = 100);
+        apply_placement(caller, 0, &[(40, 40)]);
+
+        // Within the freeze window the wipe is skipped
+        GENERATION.with(|g| *g.borrow_mut() = 149);
+        wipe_quadrant(0);
+        assert!(is_alive(40, 40));
+
+        // Other quadrants are unaffected
+        set_alive(300, 300);
+        wipe_quadrant(10);
+        assert!(!is_alive(300, 300));
+
+        // Once it expires the next wipe goes ahead
+        GENERATION.with(|g| *g.borrow_mut() = 150);
+        wipe_quadrant(0);
+        assert!(!is_alive(40, 40));
+
+        // 0 turns the freeze off
+        apply_placement(caller, 0, &[(41, 41)]);
+        set_wipe_freeze(0).unwrap();
+        wipe_quadrant(0);
+        assert!(!is_alive(41, 41));
+    });
+}
+
+// =============================================================================
+// SANDBOX TESTS
+// =============================================================================
+
+#[test]
+fn test_sandbox_blinker_oscillates_without_touching_main_grid() {
+    with_world(|| {
+        let caller = Principal::from_slice(&[9]);
+        let main_before = ALIVE.with(|a| a.borrow().to_vec());
+
+        // Horizontal blinker wrapping the x=0 edge
+        sandbox::place_for(caller, &[(63, 10), (0, 10), (1, 10)]).unwrap();
+
+        assert_eq!(sandbox::step_for(caller), Ok(1));
+        let sb = sandbox::get_for(caller).unwrap();
+        assert!(sb.is_alive(0, 9) && sb.is_alive(0, 10) && sb.is_alive(0, 11));
+        assert!(!sb.is_alive(63, 10) && !sb.is_alive(1, 10));
+        assert_eq!(sb.territory[10].count_ones() + sb.territory[9].count_ones() + sb.territory[11].count_ones(), 5);
+
+        assert_eq!(sandbox::step_for(caller), Ok(2));
+        let sb = sandbox::get_for(caller).unwrap();
+        assert!(sb.is_alive(63, 10) && sb.is_alive(0, 10) && sb.is_alive(1, 10));
+        assert_eq!(sb.alive.iter().map(|w| w.count_ones()).sum::<u32>(), 3);
+
+        assert_eq!(ALIVE.with(|a| a.borrow().to_vec()), main_before);
+        assert_eq!(GENERATION.with(|g| *g.borrow()), 0);
+
+        sandbox::reset_for(caller);
+        assert!(sandbox::get_for(caller).is_none());
+        assert!(sandbox::step_for(caller).is_err());
+    });
+}
+
+#[test]
+fn test_sandbox_limits() {
+    with_world(|| {
+        assert!(sandbox::place_for(Principal::from_slice(&[1]), &[(64, 0)]).is_err());
+        assert!(sandbox::place_for(Principal::anonymous(), &[(1, 1)]).is_err());
+
+        for i in 0..sandbox::MAX_SANDBOXES as u8 {
+            set_test_time_ns(1_000 + i as u64);
+            sandbox::place_for(Principal::from_slice(&[100 + i]), &[(1, 1)]).unwrap();
+        }
+        // Stepping counts as use, so 100 is no longer the idlest
+        set_test_time_ns(2_000);
+        sandbox::step_for(Principal::from_slice(&[100])).unwrap();
+
+        // A newcomer on a full map evicts the least recently used sandbox
+        set_test_time_ns(3_000);
+        assert!(sandbox::place_for(Principal::from_slice(&[200]), &[(1, 1)]).is_ok());
+        assert!(sandbox::get_for(Principal::from_slice(&[101])).is_none());
+        assert!(sandbox::get_for(Principal::from_slice(&[100])).is_some());
+        assert!(sandbox::get_for(Principal::from_slice(&[200])).is_some());
+        // Existing owners place without evicting anyone
+        assert!(sandbox::place_for(Principal::from_slice(&[102]), &[(2, 2)]).is_ok());
+        assert!(sandbox::get_for(Principal::from_slice(&[103])).is_some());
+    });
+}
+
+#[test]
+fn test_cell_deltas_bring_snapshot_up_to_date() {
+    with_world(|| {
+        set_test_time_ns(1_000_000_000);
+        seed_random_world(0xde17a);
+        reset_recent_diffs();
+
+        for _ in 0..10 {
+            step_generation();
+        }
+        let since = GENERATION.with(|g| *g.borrow());
+        let mut board = ALIVE.with(|a| a.borrow().to_vec());
+        for _ in 0..25 {
+            step_generation();
+        }
+
+        let delta = get_cell_deltas(since).expect("within buffer");
+        assert_eq!((delta.from_gen, delta.to_gen), (since, since + 25));
+        assert!(!delta.born.is_empty() && !delta.died.is_empty());
+        for cell in &delta.born {
+            let idx = coords_to_idx(cell.x, cell.y);
+            assert_eq!(cell.owner.map(|o| o as usize), find_owner(cell.x, cell.y));
+            board[idx >> 6] |= 1u64 << (idx & 63);
+        }
+        for &(x, y) in &delta.died {
+            let idx = coords_to_idx(x, y);
+            board[idx >> 6] &= !(1u64 << (idx & 63));
+        }
+        assert_eq!(board, ALIVE.with(|a| a.borrow().to_vec()));
+
+        let current = since + 25;
+        let empty = get_cell_deltas(current).unwrap();
+        assert!(empty.born.is_empty() && empty.died.is_empty());
+        assert!(get_cell_deltas(current + 1).is_none());
+
+        // Fall too far behind the buffer and the client must resync
+        for _ in 0..RECENT_DIFF_LIMIT {
+            step_generation();
+        }
+        assert!(get_cell_deltas(since).is_none());
+    });
+}
+
+// =============================================================================
+// READ-ONLY MODE TESTS
+// =============================================================================
+
+#[test]
+fn test_readonly_rejects_mutations_but_simulation_runs() {
+    with_world(|| {
+        READONLY.with(|r| *r.borrow_mut() = true);
+
+        // Every player mutation short-circuits before touching the caller
+        assert_eq!(faucet(), Err("Server in read-only mode".to_string()));
+        assert_eq!(join_game(10, 10, 0), Err("Server in read-only mode".to_string()));
+        assert_eq!(place_cells(vec![(1, 1)]), Err("Server in read-only mode".to_string()));
+        assert!(propose_alliance(1).is_err());
+
+        // The world keeps evolving: a blinker still flips
+        for x in 10..13 {
+            set_alive(x, 10);
+        }
+        rebuild_potential_from_alive();
+        step_generation();
+        assert!(is_alive(11, 9) && is_alive(11, 11) && !is_alive(10, 10));
+        assert!(is_readonly());
+
+        READONLY.with(|r| *r.borrow_mut() = false);
+        assert_eq!(ensure_writable(), Ok(()));
+    });
+}
+
+#[test]
+fn test_readonly_refuses_place_cells_checked_as_call_error() {
+    with_world(|| {
+        READONLY.with(|r| *r.borrow_mut() = true);
+        assert_eq!(
+            place_cells_checked(vec![(0, 0), (1, 1)]),
+            Err(PlaceError::Call("Server in read-only mode".to_string()))
+        );
+    });
+}
+
+// =============================================================================
+// PER-QUADRANT WIPE TESTS
+// =============================================================================
+
+#[test]
+fn test_independent_quadrant_timers_wipe_at_different_generations() {
+    with_world(|| {
+        const SECOND: u64 = 1_000_000_000;
+        set_test_time_ns(1_000 * SECOND);
+        set_independent_wipes_enabled(true);
+        set_quadrant_wipe_interval_ns(0, 10 * SECOND).unwrap();
+        set_quadrant_wipe_interval_ns(1, 30 * SECOND).unwrap();
+
+        // A still-life block in each quadrant
+        for (x, y) in [(20, 20), (21, 20), (20, 21), (21, 21), (150, 20), (151, 20), (150, 21), (151, 21)] {
+            set_alive(x, y);
+        }
+        rebuild_potential_from_alive();
+
+        let mut wiped_at = [None, None];
+        for tick in 1..=4u64 {
+            set_test_time_ns((1_000 + tick * 10) * SECOND);
+            step_generation();
+            run_wipe_if_needed();
+            let generation = GENERATION.with(|g| *g.borrow());
+            for (q, &(x, y)) in [(20u16, 20u16), (150, 20)].iter().enumerate() {
+                if wiped_at[q].is_none() && !is_alive(x, y) {
+                    wiped_at[q] = Some(generation);
+                }
+            }
+        }
+
+        assert_eq!(wiped_at, [Some(1), Some(3)]);
+        assert_eq!(next_wipe().0, 0);
+    });
+}
+
+#[test]
+fn test_quadrant_wipe_interval_validation() {
+    with_world(|| {
+        assert!(set_quadrant_wipe_interval_ns(TOTAL_QUADRANTS, WIPE_INTERVAL_NS).is_err());
+        assert!(set_quadrant_wipe_interval_ns(0, 1).is_err());
+        // Default cadence matches the global rotation
+        assert_eq!(get_quadrant_wipes()[5].interval_ns, WIPE_INTERVAL_NS * 16);
+        assert!(!is_independent_wipes());
+    });
+}
+
+// =============================================================================
+// PLACEMENT BURN TESTS
+// =============================================================================
+
+#[test]
+fn test_placement_burn_splits_cost() {
+    with_world(|| {
+        let player = Principal::from_slice(&[1]);
+        seat_players(1);
+        BASES.with(|b| b.borrow_mut()[0] = Some(Base { x: 200, y: 200, coins: BASE_COST, size: Some(10) }));
+        WALLETS.with(|w| w.borrow_mut().insert(player, 500));
+        set_placement_burn(5_000).unwrap();
+
+        let cells: Vec<(i32, i32)> = (0..10).map(|i| (200 + i % 5, 200 + i / 5)).collect();
+        apply_placement(player, 0, &cells);
+
+        assert_eq!(WALLETS.with(|w| w.borrow()[&player]), 490);
+        assert_eq!(BASES.with(|b| b.borrow()[0].as_ref().unwrap().coins), BASE_COST + 5);
+        assert_eq!(get_total_burned(), 5);
+
+        assert!(set_placement_burn(10_001).is_err());
+    });
+}
+
+// =============================================================================
+// BASE RELOCATION TESTS
+// =============================================================================
+
+#[test]
+fn test_relocate_base_moves_footprint_and_keeps_treasury() {
+    with_world(|| {
+        const SECOND: u64 = 1_000_000_000;
+        set_test_time_ns(1_000 * SECOND);
+        let player = Principal::from_slice(&[1]);
+        seat_players(1);
+        BASES.with(|b| b.borrow_mut()[0] = Some(Base { x: 20, y: 20, coins: 777, size: Some(BASE_SIZE) }));
+        claim_base_footprint(0, 20, 20, BASE_SIZE);
+        WALLETS.with(|w| w.borrow_mut().insert(player, 250));
+
+        // A live cell inside the old base, and a territory arm hanging off it
+        place_owned(0, 22, 22);
+        for x in 28..32 {
+            set_territory(0, x, 24);
+        }
+
+        let info = relocate_base_for(player, 300, 300).unwrap();
+        assert_eq!((info.x, info.y, info.coins), (300, 300, 777));
+        assert_eq!(WALLETS.with(|w| w.borrow()[&player]), 150);
+        assert_eq!(get_total_burned(), 0);
+
+        // Old footprint released, cell killed, orphaned arm culled
+        assert!(!player_owns(0, 22, 22) && !is_alive(22, 22));
+        assert!(!player_owns(0, 30, 24));
+        assert_eq!(CELL_COUNTS.with(|cc| cc.borrow()[0]), 0);
+        // New footprint claimed
+        assert!(player_owns(0, 300, 300) && player_owns(0, 307, 307));
+
+        // Cooldown
+        set_test_time_ns(1_030 * SECOND);
+        assert!(matches!(relocate_base_for(player, 20, 300), Err(e) if e.contains("cooldown")));
+        set_test_time_ns(1_061 * SECOND);
+        assert!(relocate_base_for(player, 20, 300).is_ok());
+    });
+}
+
+#[test]
+fn test_relocate_base_rejects_taken_quadrant() {
+    with_world(|| {
+        let player = Principal::from_slice(&[1]);
+        seat_players(2);
+        BASES.with(|b| {
+            let mut b = b.borrow_mut();
+            b[0] = Some(Base { x: 20, y: 20, coins: BASE_COST, size: Some(BASE_SIZE) });
+            b[1] = Some(Base { x: 300, y: 300, coins: BASE_COST, size: Some(BASE_SIZE) });
+        });
+        WALLETS.with(|w| w.borrow_mut().insert(player, 500));
+
+        assert!(relocate_base_for(player, 330, 330).is_err());
+        // Moving within its own quadrant is fine
+        assert!(relocate_base_for(player, 60, 60).is_ok());
+    });
+}
+
+// =============================================================================
+// FRAGILE CELL TESTS
+// =============================================================================
+
+#[test]
+fn test_fragile_cells_finds_bridge() {
+    with_world(|| {
+        seat_players(1);
+        BASES.with(|b| b.borrow_mut()[0] = Some(Base { x: 20, y: 20, coins: BASE_COST, size: Some(BASE_SIZE) }));
+        claim_base_footprint(0, 20, 20, BASE_SIZE);
+
+        // One-wide arm off the east wall: every cell but the tip is a bridge
+        for x in 28..32 {
+            set_territory(0, x, 24);
+        }
+        // 2x2 block on the wall: no single cell cuts it off
+        for (x, y) in [(28, 21), (29, 21), (28, 22), (29, 22)] {
+            set_territory(0, x, y);
+        }
+        // Island already cut off from the base is ignored
+        set_territory(0, 100, 100);
+        set_territory(0, 101, 100);
+
+        assert_eq!(fragile_cells(0), vec![(28, 24), (29, 24), (30, 24)]);
+        assert!(get_fragile_cells(7).is_empty());
+    });
+}
+
+// =============================================================================
+// RUNTIME TIMING TESTS
+// =============================================================================
+
+#[test]
+fn test_wipe_interval_and_grace_period_are_configurable() {
+    const SECOND: u64 = 1_000_000_000;
+    assert_eq!(get_timings(), Timings { wipe_interval_secs: 120, grace_period_secs: 600 });
+
+    // Wipe interval is clamped to 30 seconds
+    set_wipe_interval_secs(5);
+    set_grace_period_secs(45);
+    assert_eq!(get_timings(), Timings { wipe_interval_secs: 30, grace_period_secs: 45 });
+
+    set_test_time_ns(1_000 * SECOND);
+    LAST_WIPE_NS.with(|lw| *lw.borrow_mut() = 1_000 * SECOND);
+    set_test_time_ns(1_020 * SECOND);
+    assert_eq!(next_wipe().1, 10);
+    run_wipe_if_needed();
+    assert_eq!(NEXT_WIPE_QUADRANT.with(|q| *q.borrow()), 0);
+    set_test_time_ns(1_030 * SECOND);
+    run_wipe_if_needed();
+    assert_eq!(NEXT_WIPE_QUADRANT.with(|q| *q.borrow()), 1);
+
+    // Grace period elimination uses the configured value
+    seat_players(1);
+    BASES.with(|b| b.borrow_mut()[0] = Some(Base { x: 20, y: 20, coins: 0, size: Some(BASE_SIZE) }));
+    ZERO_CELLS_SINCE.with(|z| z.borrow_mut()[0] = Some(1_000 * SECOND));
+    check_grace_periods();
+    assert!(BASES.with(|b| b.borrow()[0].is_some()));
+    set_test_time_ns(1_045 * SECOND);
+    check_grace_periods();
+    assert!(BASES.with(|b| b.borrow()[0].is_none()));
+}
+
+// =============================================================================
+// DISCONNECT GRACE TESTS
+// =============================================================================
+
+#[test]
+fn test_disconnect_grace_spares_reconnected_territory() {
+    with_world(|| {
+        seat_players(1);
+        BASES.with(|b| b.borrow_mut()[0] = Some(Base { x: 20, y: 20, coins: BASE_COST, size: Some(BASE_SIZE) }));
+        claim_base_footprint(0, 20, 20, BASE_SIZE);
+        for x in 28..32 {
+            set_territory(0, x, 24);
+        }
+        set_disconnect_grace_enabled(true);
+
+        let cut_bridge = || {
+            clear_territory(0, 29, 24);
+            let mut changes = TerritoryChanges::new();
+            changes.affected_players = 1;
+            changes.lost_cells[0].push((29, 24));
+            check_all_disconnections(&changes);
+        };
+
+        // Cut, then rebuild the bridge inside the window: nothing is culled
+        GENERATION.with(|g| *g.borrow_mut() = 100);
+        cut_bridge();
+        assert!(player_owns(0, 31, 24));
+        set_territory(0, 29, 24);
+        GENERATION.with(|g| *g.borrow_mut() = 105);
+        recheck_orphans();
+        assert!(ORPHANS.with(|o| o.borrow().is_empty()));
+        GENERATION.with(|g| *g.borrow_mut() = 200);
+        recheck_orphans();
+        assert!(player_owns(0, 30, 24) && player_owns(0, 31, 24));
+
+        // Cut and leave it: survives the window, then culled
+        cut_bridge();
+        GENERATION.with(|g| *g.borrow_mut() = 200 + DISCONNECT_GRACE_GENS - 1);
+        recheck_orphans();
+        assert!(player_owns(0, 31, 24));
+        GENERATION.with(|g| *g.borrow_mut() = 200 + DISCONNECT_GRACE_GENS);
+        recheck_orphans();
+        assert!(!player_owns(0, 30, 24) && !player_owns(0, 31, 24));
+        assert!(player_owns(0, 28, 24));
+        assert!(ORPHANS.with(|o| o.borrow().is_empty()));
+    });
+}
+
+// =============================================================================
+// QUADRANT STATS TESTS
+// =============================================================================
+
+#[test]
+fn test_quadrant_stats_counts_and_dominant_owner() {
+    with_world(|| {
+        seat_players(2);
+        BASES.with(|b| b.borrow_mut()[1] = Some(Base { x: 300, y: 20, coins: BASE_COST, size: Some(BASE_SIZE) }));
+
+        // Quadrant 0: slot 1 outnumbers slot 0; cells straddle both words of a row
+        place_owned(0, 10, 10);
+        place_owned(1, 70, 10);
+        place_owned(1, 71, 127);
+        // Quadrant 5: a single unowned cell
+        set_alive(130, 130);
+
+        let stats = get_quadrant_stats();
+        assert_eq!(stats[0], QuadStat { alive: 3, dominant_owner: Some(1), has_base: false });
+        assert_eq!(stats[2], QuadStat { alive: 0, dominant_owner: None, has_base: true });
+        assert_eq!(stats[5], QuadStat { alive: 1, dominant_owner: None, has_base: false });
+        assert_eq!(stats.iter().map(|s| s.alive).sum::<u32>(), 4);
+    });
+}
+
+// =============================================================================
+// WIPE IMPACT TESTS
+// =============================================================================
+
+#[test]
+fn test_next_wipe_impact_tallies_target_quadrant() {
+    with_world(|| {
+        seat_players(3);
+        NEXT_WIPE_QUADRANT.with(|q| *q.borrow_mut() = 5);
+        let (x0, y0, _, _) = quadrant_bounds(5);
+
+        let before = get_next_wipe_impact();
+        assert_eq!((before.quadrant, before.alive_cells), (5, 0));
+        assert!(before.affected_players.is_empty());
+
+        place_owned(2, x0 + 3, y0 + 3);
+        place_owned(2, x0 + 100, y0 + 90);
+        place_owned(1, x0 + 50, y0 + 50);
+        // Outside the target quadrant: not counted
+        place_owned(0, x0 - 1, y0);
+
+        let after = get_next_wipe_impact();
+        assert_eq!(after.alive_cells, 3);
+        assert_eq!(after.affected_players, vec![2, 1]);
+    });
+}
+
+// =============================================================================
+// SIEGE EVENT TESTS
+// =============================================================================
+
+#[test]
+fn test_siege_hits_are_logged_with_attacker() {
+    with_world(|| {
+        seat_players(3);
+        BASES.with(|b| b.borrow_mut()[1] = Some(Base { x: 20, y: 20, coins: 15, size: Some(BASE_SIZE) }));
+        GENERATION.with(|g| *g.borrow_mut() = 41);
+
+        // Slot 2 births into slot 1's wall twice: 10 coins, then the last 5
+        apply_changes(&[(coords_to_idx(20, 23), Some(2))], &[], &[]);
+        apply_changes(&[(coords_to_idx(21, 20), Some(2))], &[], &[]);
+
+        let events = get_siege_events_since(0);
+        assert_eq!(events.len(), 2);
+        assert_eq!(
+            events[0],
+            SiegeEvent { generation: 42, victim_slot: 1, attacker_slot: 2, coins_transferred: 10, x: 20, y: 23 }
+        );
+        assert_eq!(events[1].coins_transferred, 5);
+        assert!(get_siege_events_since(42).is_empty());
+        assert_eq!(WALLETS.with(|w| w.borrow()[&Principal::from_slice(&[3])]), 15);
+    });
+}
+
+#[test]
+fn test_siege_event_buffer_is_bounded() {
+    for i in 0..SIEGE_EVENT_LIMIT as u64 + 10 {
+        record_siege_event(SiegeEvent { generation: i, victim_slot: 0, attacker_slot: 1, coins_transferred: 1, x: 0, y: 0 });
+    }
+    let events = get_siege_events_since(0);
+    assert_eq!(events.len(), SIEGE_EVENT_LIMIT);
+    assert_eq!(events[0].generation, 10);
+}
+
+// =============================================================================
+// SNAPSHOT TESTS
+// =============================================================================
+
+#[test]
+fn test_snapshot_round_trips_into_fresh_instance() {
+    let (tx, rx) = std::sync::mpsc::channel();
+    with_world(move || {
+        set_test_time_ns(1_000_000_000);
+        seed_random_world(0x5eed);
+        WALLETS.with(|w| w.borrow_mut().insert(Principal::from_slice(&[9]), 4_321));
+        for _ in 0..50 {
+            step_generation();
+        }
+        let raw_len = candid::encode_one(capture_state()).unwrap().len();
+        let bytes = snapshot::encode_snapshot(&capture_state());
+        assert!(bytes.len() * 5 < raw_len, "snapshot barely compressed: {} of {}", bytes.len(), raw_len);
+        tx.send((bytes, state_hash())).unwrap();
+    });
+    let (bytes, expected) = rx.recv().unwrap();
+
+    with_world(move || {
+        assert_ne!(state_hash(), expected);
+        snapshot::import_snapshot(&bytes).unwrap();
+        assert_eq!(state_hash(), expected);
+        assert_eq!(WALLETS.with(|w| w.borrow()[&Principal::from_slice(&[9])]), 4_321);
+
+        // A live game with players refuses imports; bad versions are rejected
+        assert!(snapshot::import_snapshot(&bytes).is_err());
+        IS_RUNNING.with(|r| *r.borrow_mut() = false);
+        let mut wrong_version = bytes.clone();
+        wrong_version[0] = snapshot::SNAPSHOT_VERSION + 1;
+        assert!(snapshot::import_snapshot(&wrong_version).is_err());
+        assert!(snapshot::import_snapshot(&bytes).is_ok());
+    });
+}
+
+#[test]
+fn test_snapshot_with_wrong_player_count_is_rejected() {
+    with_world(|| {
+        seat_players(2);
+        IS_RUNNING.with(|r| *r.borrow_mut() = false);
+        let before = state_hash();
+
+        let mut short = capture_state();
+        short.players.truncate(MAX_PLAYERS - 1);
+        let err = snapshot::import_snapshot(&snapshot::encode_snapshot(&short)).unwrap_err();
+        assert!(err.contains("players"), "unexpected error: {}", err);
+
+        let mut long = capture_state();
+        long.bases.push(None);
+        assert!(snapshot::import_snapshot(&snapshot::encode_snapshot(&long)).is_err());
+
+        // Nothing was installed
+        assert_eq!(state_hash(), before);
+        assert!(snapshot::import_snapshot(&snapshot::encode_snapshot(&capture_state())).is_ok());
+    });
+}
+
+#[test]
+fn test_snapshot_import_checks_chunks_and_clears_transient_state() {
+    with_world(|| {
+        seat_players(2);
+        place_owned(0, 10, 10);
+        place_owned(1, 200, 200);
+        IS_RUNNING.with(|r| *r.borrow_mut() = false);
+
+        let mut short = capture_state();
+        short.territory[0].chunks[0].truncate(10);
+        let err = snapshot::import_snapshot(&snapshot::encode_snapshot(&short)).unwrap_err();
+        assert!(err.contains("territory[0]"), "unexpected error: {}", err);
+        let mut missing = capture_state();
+        missing.territory[1].chunks.clear();
+        assert!(snapshot::import_snapshot(&snapshot::encode_snapshot(&missing)).is_err());
+
+        let bytes = snapshot::encode_snapshot(&capture_state());
+        propose_alliance_for(0, 1).unwrap();
+        LAST_RELOCATE_NS.with(|lr| lr.borrow_mut()[1] = Some(now_ns()));
+        snapshot::import_snapshot(&bytes).unwrap();
+        assert_eq!(ALLIANCE_PROPOSALS.with(|p| *p.borrow()), [None; MAX_PLAYERS]);
+        assert_eq!(LAST_RELOCATE_NS.with(|lr| *lr.borrow()), [None; MAX_PLAYERS]);
+    });
+}
+
+// =============================================================================
+// ELIMINATION LOG TESTS
+// =============================================================================
+
+#[test]
+fn test_eliminations_are_logged_with_reason() {
+    with_world(|| {
+        seat_players(3);
+        for slot in 0..3 {
+            let x = 20 + 100 * slot as u16;
+            BASES.with(|b| b.borrow_mut()[slot] = Some(Base { x, y: 20, coins: 10, size: Some(BASE_SIZE) }));
+        }
+        GENERATION.with(|g| *g.borrow_mut() = 7);
+
+        // Slot 2 sieges slot 0's last 10 coins
+        apply_changes(&[(coords_to_idx(20, 23), Some(2))], &[], &[]);
+
+        // Slot 1 sits at zero cells past the grace period
+        set_test_time_ns(0);
+        ZERO_CELLS_SINCE.with(|z| z.borrow_mut()[1] = Some(0));
+        set_test_time_ns(grace_period_ns());
+        GENERATION.with(|g| *g.borrow_mut() = 9);
+        check_grace_periods();
+
+        // Slot 2 leaves
+        assert!(leave_game_for(Principal::from_slice(&[3])).is_ok());
+
+        let log = get_eliminations(10);
+        let summary: Vec<(u8, EliminationReason)> = log.iter().map(|e| (e.slot, e.reason)).collect();
+        assert_eq!(summary, vec![
+            (2, EliminationReason::VoluntaryLeave),
+            (1, EliminationReason::GraceExpired),
+            (0, EliminationReason::BaseDestroyed),
+        ]);
+        assert_eq!(log[1], EliminationRecord {
+            slot: 1,
+            principal: Principal::from_slice(&[2]),
+            generation: 9,
+            reason: EliminationReason::GraceExpired,
+        });
+        assert_eq!(get_eliminations(1).len(), 1);
+    });
+}
+
+#[test]
+fn test_surrender_hands_treasury_to_target() {
+    with_world(|| {
+        seat_players(2);
+        BASES.with(|b| {
+            let mut b = b.borrow_mut();
+            b[0] = Some(Base { x: 20, y: 20, coins: 70, size: Some(BASE_SIZE) });
+            b[1] = Some(Base { x: 200, y: 200, coins: 30, size: Some(BASE_SIZE) });
+        });
+        place_owned(0, 40, 40);
+        let surrendering = Principal::from_slice(&[1]);
+
+        assert_eq!(surrender_to_for(surrendering, 0), Err("Cannot surrender to yourself".to_string()));
+        assert_eq!(surrender_to_for(surrendering, 5), Err("Target slot is not occupied".to_string()));
+        assert!(surrender_to_for(surrendering, MAX_PLAYERS as u8).is_err());
+        assert!(surrender_to_for(Principal::from_slice(&[9]), 1).is_err());
+
+        surrender_to_for(surrendering, 1).unwrap();
+        assert_eq!(BASES.with(|b| b.borrow()[1].as_ref().unwrap().coins), 100);
+        assert!(BASES.with(|b| b.borrow()[0].is_none()));
+        assert!(PLAYERS.with(|p| p.borrow()[0].is_none()));
+        assert!(!is_alive(40, 40));
+        assert_eq!(get_eliminations(1)[0].reason, EliminationReason::Surrender);
+    });
+}
+
+#[test]
+fn test_elimination_log_is_bounded_and_persisted() {
+    with_world(|| {
+        for generation in 0..ELIMINATION_LOG_LIMIT as u64 + 5 {
+            record_elimination(EliminationRecord {
+                slot: 0,
+                principal: Principal::from_slice(&[1]),
+                generation,
+                reason: EliminationReason::GraceExpired,
+            });
+        }
+        let log = get_eliminations(u32::MAX);
+        assert_eq!(log.len(), ELIMINATION_LOG_LIMIT);
+        assert_eq!(log.last().map(|e| e.generation), Some(5));
+
+        let state = capture_state();
+        ELIMINATIONS.with(|e| e.borrow_mut().clear());
+        restore_state(state);
+        assert_eq!(get_eliminations(1)[0].generation, ELIMINATION_LOG_LIMIT as u64 + 4);
+    });
+}
+
+// =============================================================================
+// REQUEST SPAWN TESTS
+// =============================================================================
+
+#[test]
+fn test_request_spawn_rotates_through_free_quadrants() {
+    with_world(|| {
+        let players: Vec<Principal> = (1..=3).map(|i| Principal::from_slice(&[i])).collect();
+        WALLETS.with(|w| {
+            let mut w = w.borrow_mut();
+            for &p in &players {
+                w.insert(p, BASE_COST);
+            }
+        });
+
+        // Quadrant 7 (the second stop in the rotation) is already taken
+        BASES.with(|b| b.borrow_mut()[7] = Some(Base { x: 400, y: 150, coins: 10, size: Some(BASE_SIZE) }));
+
+        let first = request_spawn_for(players[0]).unwrap();
+        assert_eq!((first.x, first.y, first.slot), (60, 60, 0));
+        let second = request_spawn_for(players[1]).unwrap();
+        assert_eq!(get_quadrant(second.x, second.y), 14);
+        assert_eq!((second.x, second.y), (316, 444));
+        assert_eq!(WALLETS.with(|w| w.borrow()[&players[1]]), 0);
+
+        // Failed spawns don't move the rotation
+        assert!(request_spawn_for(players[0]).is_err());
+        let third = request_spawn_for(players[2]).unwrap();
+        assert_eq!(get_quadrant(third.x, third.y), 5);
+    });
+}
+
+#[test]
+fn test_request_spawn_rejects_full_board() {
+    with_world(|| {
+        // Sudden death has closed in to the four central quadrants, all taken
+        ARENA.with(|a| *a.borrow_mut() = Some(Arena { inset: 128, last_shrink_ns: 0 }));
+        BASES.with(|b| {
+            let mut b = b.borrow_mut();
+            for (slot, q) in [5u8, 6, 9, 10].into_iter().enumerate() {
+                let (x, y, _, _) = quadrant_bounds(q);
+                b[slot] = Some(Base { x: x + 10, y: y + 10, coins: 10, size: Some(BASE_SIZE) });
+            }
+        });
+
+        let player = Principal::from_slice(&[9]);
+        WALLETS.with(|w| w.borrow_mut().insert(player, BASE_COST));
+        assert_eq!(request_spawn_for(player).err().as_deref(), Some("No free quadrant"));
+    });
+}
+
+// =============================================================================
+// LEAVE GAME TESTS
+// =============================================================================
+
+#[test]
+fn test_leave_game_refunds_half_and_frees_slot() {
+    with_world(|| {
+        let player = Principal::from_slice(&[2]);
+        seat_players(2);
+        BASES.with(|b| b.borrow_mut()[1] = Some(Base { x: 300, y: 300, coins: 151, size: Some(BASE_SIZE) }));
+        claim_base_footprint(1, 300, 300, BASE_SIZE);
+        place_owned(1, 302, 302);
+        WALLETS.with(|w| w.borrow_mut().insert(player, 7));
+
+        assert_eq!(leave_game_for(player), Ok(75));
+        assert_eq!(WALLETS.with(|w| w.borrow()[&player]), 82);
+        assert!(PLAYERS.with(|p| p.borrow()[1].is_none()));
+        assert!(BASES.with(|b| b.borrow()[1].is_none()));
+        assert!(!is_alive(302, 302) && !player_owns(1, 300, 300));
+
+        assert!(leave_game_for(player).is_err());
+    });
+}
+
+// =============================================================================
+// COIN TRANSFER TESTS
+// =============================================================================
+
+#[test]
+fn test_transfer_coins_moves_wallet_balance() {
+    let alice = Principal::from_slice(&[1]);
+    let bob = Principal::from_slice(&[2]);
+    WALLETS.with(|w| w.borrow_mut().insert(alice, 100));
+
+    assert_eq!(transfer_coins_for(alice, bob, 30), Ok(70));
+    assert_eq!(WALLETS.with(|w| w.borrow()[&bob]), 30);
+
+    assert!(transfer_coins_for(alice, bob, 71).is_err());
+    assert!(transfer_coins_for(alice, bob, 0).is_err());
+    assert!(transfer_coins_for(alice, alice, 1).is_err());
+    assert!(transfer_coins_for(alice, Principal::anonymous(), 1).is_err());
+    assert_eq!(WALLETS.with(|w| w.borrow()[&alice]), 70);
+
+    // A transfer that would overflow the recipient debits nobody
+    WALLETS.with(|w| w.borrow_mut().insert(bob, u64::MAX - 10));
+    assert_eq!(transfer_coins_for(alice, bob, 11), Err("Recipient balance would overflow".to_string()));
+    assert_eq!(WALLETS.with(|w| w.borrow()[&alice]), 70);
+    assert_eq!(WALLETS.with(|w| w.borrow()[&bob]), u64::MAX - 10);
+}
+
+// =============================================================================
+// STARTING TREASURY TESTS
+// =============================================================================
+
+#[test]
+fn test_configured_starting_treasury_funds_new_bases() {
+    with_world(|| {
+        let player = Principal::from_slice(&[1]);
+        WALLETS.with(|w| w.borrow_mut().insert(player, 1_000));
+
+        assert!(set_starting_treasury_coins(0).is_err());
+        assert!(set_starting_treasury_coins(MAX_STARTING_TREASURY + 1).is_err());
+        set_starting_treasury_coins(500).unwrap();
+
+        let slot = join_game_for(player, 20, 20, None, BASE_SIZE).unwrap();
+        assert_eq!(BASES.with(|b| b.borrow()[slot as usize].as_ref().unwrap().coins), 500);
+        assert_eq!(WALLETS.with(|w| w.borrow()[&player]), 1_000 - BASE_COST);
+    });
+}
+
+// =============================================================================
+// FATE FUNCTION TESTS
+// =============================================================================
+
+/// Fate of the cell at bit 10 of a row word, with live neighbors given as
+/// (dx, dy) offsets and no owners (birth ownership is covered separately)
+fn fate_of(center_alive: bool, neighbors: &[(i32, i32)]) -> CellFate {
+    let bit = 10;
+    let (mut above, mut same, mut below) = (0u64, 0u64, 0u64);
+    if center_alive {
+        same |= 1 << bit;
+    }
+    for &(dx, dy) in neighbors {
+        let mask = 1u64 << (bit as i32 + dx);
+        match dy {
+            -1 => above |= mask,
+            0 => same |= mask,
+            _ => below |= mask,
+        }
+    }
+    compute_cell_fate(bit, above, same, below, 0, 0, 0, 0, 0, 0, coords_to_idx(bit as u16, 1))
+}
+
+#[test]
+fn test_fate_survival_death_and_birth_counts() {
+    // Birth ownership reads OWNER, which needs the big-stack thread
+    with_world(|| {
+        assert_eq!(fate_of(true, &[(-1, -1), (1, 0)]), CellFate::Survives);
+        assert_eq!(fate_of(true, &[(-1, -1), (1, 0), (0, 1)]), CellFate::Survives);
+        assert_eq!(fate_of(true, &[(1, 1)]), CellFate::Death);
+        assert_eq!(fate_of(true, &[(-1, -1), (0, -1), (1, 0), (0, 1)]), CellFate::Death);
+        assert!(matches!(fate_of(false, &[(-1, -1), (1, 0), (0, 1)]), CellFate::Birth(_)));
+        assert_eq!(fate_of(false, &[(-1, -1), (1, 0)]), CellFate::StaysDead);
+        assert_eq!(fate_of(false, &[(-1, -1), (0, -1), (1, 0), (0, 1)]), CellFate::StaysDead);
+    });
+}
+
+#[test]
+fn test_birth_owner_majority_and_ties() {
+    let no_allies = [None; MAX_PLAYERS];
+    let never_tied = |_: usize| -> usize { panic!("unexpected tie") };
+
+    // Clear majority
+    assert_eq!(birth_owner(&[Some(1), Some(2), Some(1)], &no_allies, never_tied), Some(1));
+    // Unowned parents outnumber every player: first owned parent wins
+    assert_eq!(birth_owner(&[None, Some(3), None], &no_allies, never_tied), Some(3));
+    // No owned parents at all: the birth stays neutral
+    assert_eq!(birth_owner(&[None, None, None], &no_allies, never_tied), None);
+
+    // Three-way tie: tie_pick sees all three candidates, in slot order
+    for pick in 0..3 {
+        let owner = birth_owner(&[Some(5), Some(0), Some(2)], &no_allies, |n| {
+            assert_eq!(n, 3);
+            pick
+        });
+        assert_eq!(owner, Some([0, 2, 5][pick]));
+    }
+    // Tie between a player and unowned parents goes to the player
+    assert_eq!(birth_owner(&[Some(4), None], &no_allies, never_tied), Some(4));
+
+    // Allies 0 and 2 outvote slot 1; equal contributions split by tie_pick
+    let mut allies = [None; MAX_PLAYERS];
+    allies[0] = Some(2);
+    allies[2] = Some(0);
+    assert_eq!(birth_owner(&[Some(0), Some(2), Some(1)], &allies, |n| { assert_eq!(n, 2); 1 }), Some(2));
+    assert_eq!(birth_owner(&[Some(2), Some(2), Some(1)], &allies, never_tied), Some(2));
+}
+
+// =============================================================================
+// SUDDEN DEATH TESTS
+// =============================================================================
+
+#[test]
+fn test_sudden_death_shrink_wipes_outer_ring() {
+    with_world(|| {
+        const SECOND: u64 = 1_000_000_000;
+        set_test_time_ns(1_000 * SECOND);
+        let player = Principal::from_slice(&[1]);
+        seat_players(1);
+        BASES.with(|b| b.borrow_mut()[0] = Some(Base { x: 250, y: 250, coins: 100, size: Some(BASE_SIZE) }));
+        claim_base_footprint(0, 250, 250, BASE_SIZE);
+        WALLETS.with(|w| w.borrow_mut().insert(player, 100));
+        set_sudden_death_enabled(true);
+
+        // Blocks in the soon-excluded ring (both edges) and one in the center
+        for (x, y) in [(2, 2), (3, 2), (2, 3), (3, 3), (500, 300), (501, 300), (500, 301), (501, 301)] {
+            set_alive(x, y);
+        }
+        place_owned(0, 200, 200);
+        set_territory(0, 5, 5);
+        rebuild_potential_from_alive();
+
+        // Nothing happens before the interval elapses
+        set_test_time_ns(1_000 * SECOND + SHRINK_INTERVAL_NS - 1);
+        run_shrink_if_needed();
+        assert!(is_alive(2, 2));
+        assert_eq!(arena_info().map(|a| (a.min, a.max)), Some((0, GRID_SIZE)));
+
+        set_test_time_ns(1_000 * SECOND + SHRINK_INTERVAL_NS);
+        run_shrink_if_needed();
+        assert_eq!(arena_info().map(|a| (a.min, a.max)), Some((SHRINK_STEP, GRID_SIZE - SHRINK_STEP)));
+        assert!(!is_alive(2, 2) && !is_alive(501, 301));
+        assert!(is_alive(200, 200));
+
+        // Placements in the ring are rejected, even on owned territory
+        let base = BASES.with(|b| b.borrow()[0].clone()).unwrap();
+        assert_eq!(validate_placement(0, &base, 5, 5), Err("Outside the arena".to_string()));
+        assert_eq!(validate_placement(0, &base, 252, 252), Ok(()));
+
+        // Bases can't be founded or moved into the ring
+        assert!(matches!(relocate_base_for(player, 4, 300), Err(e) if e.contains("arena")));
+    });
+}
+
+#[test]
+fn test_sudden_death_eliminates_bases_outside_arena() {
+    with_world(|| {
+        set_test_time_ns(1_000);
+        seat_players(2);
+        BASES.with(|b| {
+            let mut b = b.borrow_mut();
+            b[0] = Some(Base { x: 8, y: 100, coins: 100, size: Some(BASE_SIZE) });
+            b[1] = Some(Base { x: 250, y: 250, coins: 100, size: Some(BASE_SIZE) });
+        });
+        set_sudden_death_enabled(true);
+
+        set_test_time_ns(1_000 + SHRINK_INTERVAL_NS);
+        run_shrink_if_needed();
+        BASES.with(|b| {
+            let b = b.borrow();
+            assert!(b[0].is_none() && b[1].is_some());
+        });
+
+        // Disabling reopens the whole grid
+        set_sudden_death_enabled(false);
+        assert!(arena_info().is_none() && in_arena(0, 0));
+    });
+}
+
+// =============================================================================
+// PLACEMENT PREVIEW TESTS
+// =============================================================================
+
+#[test]
+fn test_preview_steps_a_copy_of_the_board() {
+    with_world(|| {
+        seat_players(1);
+        place_owned(0, 100, 100);
+        place_owned(0, 101, 100);
+        set_territory(0, 102, 100);
+        let before = state_hash();
+
+        // Completing the row makes a blinker; one step turns it vertical
+        let mut cells = preview_generations(&[(102, 100)], 1);
+        cells.sort_by_key(|c| (c.x, c.y));
+        let expected: Vec<SparseCell> =
+            [99, 100, 101].iter().map(|&y| SparseCell { x: 101, y, owner: Some(0) }).collect();
+        assert_eq!(cells, expected);
+
+        assert_eq!(preview_generations(&[(102, 100)], 0).len(), 3);
+        assert_eq!(state_hash(), before);
+        assert!(!is_alive(102, 100));
+        assert_eq!(find_owner(101, 99), None);
+    });
+}
+
+#[test]
+fn test_preview_generations_are_capped() {
+    with_world(|| {
+        let glider = [(10, 10), (11, 11), (9, 12), (10, 12), (11, 12)];
+        let capped = preview_generations(&glider, MAX_PREVIEW_GENERATIONS);
+        assert_eq!(preview_generations(&glider, u8::MAX), capped);
+        assert_ne!(preview_generations(&glider, MAX_PREVIEW_GENERATIONS - 1), capped);
+    });
+}
+
+// =============================================================================
+// RUN-LENGTH STATE TESTS
+// =============================================================================
+
+#[test]
+fn test_state_rle_groups_same_owner_runs() {
+    with_world(|| {
+        seat_players(2);
+        for x in 10..14 {
+            place_owned(0, x, 5);
+        }
+        place_owned(1, 14, 5);
+        set_alive(16, 5); // gap, then an unowned cell
+        place_owned(0, 511, 6); // row end runs on into the next row's start
+        place_owned(0, 0, 7);
+
+        let row = |y: u32| y * GRID_SIZE as u32;
+        assert_eq!(
+            state_rle(),
+            vec![(row(5) + 10, 4, 0), (row(5) + 14, 1, 1), (row(5) + 16, 1, 255), (row(6) + 511, 2, 0)]
+        );
+
+        // Expanding the runs recovers every live cell
+        let expanded: usize = state_rle().iter().map(|&(_, len, _)| len as usize).sum();
+        assert_eq!(expanded, get_alive_cells().len());
+    });
+}
+
+// =============================================================================
+// SPECTATOR TESTS
+// =============================================================================
+
+#[test]
+fn test_spectators_register_once_and_leave_on_join() {
+    with_world(|| {
+        let watcher = Principal::from_slice(&[1]);
+        assert!(register_spectator_for(Principal::anonymous()).is_err());
+        register_spectator_for(watcher).unwrap();
+        register_spectator_for(watcher).unwrap();
+        register_spectator_for(Principal::from_slice(&[2])).unwrap();
+        assert_eq!(spectator_count(), 2);
+
+        // Registrations survive an upgrade
+        restore_state(capture_state());
+        assert_eq!(spectator_count(), 2);
+
+        WALLETS.with(|w| w.borrow_mut().insert(watcher, 1_000));
+        join_game_for(watcher, 20, 20, None, BASE_SIZE).unwrap();
+        assert_eq!(spectator_count(), 1);
+        assert_eq!(register_spectator_for(watcher), Err("Already playing".to_string()));
+    });
+}
+
+// =============================================================================
+// TICK SPEED TESTS
+// =============================================================================
+
+#[test]
+fn test_tick_speed_is_clamped_and_persisted() {
+    with_world(|| {
+        assert_eq!(get_tick_speed(), (GENERATIONS_PER_TICK, TICK_INTERVAL_MS));
+
+        set_tick_speed_values(0, 1);
+        assert_eq!(get_tick_speed(), (1, MIN_TICK_INTERVAL_MS));
+        set_tick_speed_values(u32::MAX, u64::MAX);
+        assert_eq!(get_tick_speed(), (MAX_GENERATIONS_PER_TICK, MAX_TICK_INTERVAL_MS));
+
+        set_tick_speed_values(3, 2_000);
+        restore_state(capture_state());
+        assert_eq!(get_tick_speed(), (3, 2_000));
+
+        // An idle tick still advances by the configured generation count
+        set_test_time_ns(1_000_000_000);
+        let before = GENERATION.with(|g| *g.borrow());
+        tick();
+        assert_eq!(GENERATION.with(|g| *g.borrow()), before + 3);
+    });
+}
+
+// =============================================================================
+// SEEDED RESET TESTS
+// =============================================================================
+
+#[test]
+fn test_reset_with_seed_is_reproducible_and_neutral() {
+    let (tx, rx) = std::sync::mpsc::channel();
+    with_world(move || {
+        set_test_time_ns(1_000_000_000);
+        assert!(reset_with_seed_internal(1, MAX_SEED_DENSITY_PCT + 1).is_err());
+
+        set_alive(3, 3);
+        reset_with_seed_internal(42, 25).unwrap();
+        let alive = get_alive_cells().len();
+        assert!((TOTAL_CELLS * 24 / 100..TOTAL_CELLS * 26 / 100).contains(&alive), "{} alive", alive);
+        assert!(state_rle().iter().all(|&(_, _, owner)| owner == 255));
+        assert!(POTENTIAL.with(|p| p.borrow().iter().any(|&w| w != 0)));
+
+        seat_players(1);
+        assert_eq!(reset_with_seed_internal(42, 25), Err("Players are still in the game".to_string()));
+        tx.send(state_hash()).unwrap();
+    });
+    let first = rx.recv().unwrap();
+
+    let (tx, rx) = std::sync::mpsc::channel();
+    with_world(move || {
+        set_test_time_ns(1_000_000_000);
+        reset_with_seed_internal(42, 25).unwrap();
+        seat_players(1);
+        tx.send(state_hash()).unwrap();
+    });
+    assert_eq!(rx.recv().unwrap(), first);
+
+    // Births from the soup stay neutral: no slot gains cells or territory
+    with_world(|| {
+        set_test_time_ns(1_000_000_000);
+        reset_with_seed_internal(42, 25).unwrap();
+        for _ in 0..5 {
+            step_generation();
+        }
+        assert!(!get_alive_cells().is_empty());
+        assert!(state_rle().iter().all(|&(_, _, owner)| owner == 255));
+        assert_eq!(CELL_COUNTS.with(|cc| *cc.borrow()), [0; MAX_PLAYERS]);
+        for slot in 0..MAX_PLAYERS {
+            assert_eq!(TERRITORY.with(|t| t.borrow()[slot].chunk_mask), 0, "slot {} has territory", slot);
+        }
+    });
+}
+
+// =============================================================================
+// PLAYER PROFILE TESTS
+// =============================================================================
+
+#[test]
+fn test_profiles_are_validated_shown_and_cleared() {
+    with_world(|| {
+        seat_players(2);
+        let player = Principal::from_slice(&[2]);
+
+        assert_eq!(set_profile_for(Principal::from_slice(&[9]), "x".into(), 0), Err("Not in game".to_string()));
+        assert!(set_profile_for(player, "   ".into(), 0).is_err());
+        assert!(set_profile_for(player, "n".repeat(MAX_DISPLAY_NAME_LEN + 1), 0).is_err());
+        assert!(set_profile_for(player, "bad\nname".into(), 0).is_err());
+        assert!(set_profile_for(player, "ok".into(), MAX_PROFILE_COLOR + 1).is_err());
+
+        set_profile_for(player, "  Glider Gun ".into(), 0x33_cc_ff).unwrap();
+        let expected = PlayerProfile { display_name: "Glider Gun".to_string(), color: 0x33_cc_ff };
+        assert_eq!(get_slots_info()[1].as_ref().unwrap().profile, Some(expected.clone()));
+        assert_eq!(get_slots_info()[0].as_ref().unwrap().profile, None);
+
+        restore_state(capture_state());
+        assert_eq!(PROFILES.with(|pr| pr.borrow()[1].clone()), Some(expected));
+
+        // A vacated slot doesn't pass its profile to the next occupant
+        eliminate_player(1, EliminationReason::VoluntaryLeave);
+        assert_eq!(PROFILES.with(|pr| pr.borrow()[1].clone()), None);
+    });
+}
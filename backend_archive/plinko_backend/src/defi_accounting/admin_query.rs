@@ -7,7 +7,7 @@ const ADMIN_PRINCIPAL: &str = "p7336-jmpo5-pkjsf-7dqkd-ea3zu-g2ror-ctcn2-sxtuo-t
 const WASM_PAGE_SIZE_BYTES: u64 = 65536;
 const REASONABLE_MAX_LIMIT: usize = 10_000; // Safety net for unbounded queries
 
-fn require_admin() -> Result<(), String> {
+pub(crate) fn require_admin() -> Result<(), String> {
     let caller = ic_cdk::api::msg_caller();
     let admin = Principal::from_text(ADMIN_PRINCIPAL)
         .map_err(|e| format!("Invalid admin principal: {:?}", e))?;
@@ -2,16 +2,13 @@ use candid::{CandidType, Deserialize, Principal};
 use ic_cdk::management_canister::raw_rand;
 use crate::types::MIN_BET;
 use crate::defi_accounting::{self as accounting, liquidity_pool};
-use crate::{calculate_multiplier_bp, MULTIPLIER_SCALE, ROWS};
+use crate::{calculate_multiplier_bp, edge_scale, MULTIPLIER_SCALE, ROWS};
 use serde::Serialize;
 
-// Max multiplier for bet validation (6.52x at edges)
-// This must match calculate_multiplier_bp(0) or calculate_multiplier_bp(8)
-const MAX_MULTIPLIER_BP: u64 = 65_200;
-
 // Statistical constants for variance-aware betting
-// These are derived from the multiplier probability distribution:
-// E[X] = 0.99, Var[X] ≈ 1.092, StdDev[X] ≈ 1.045
+// These are derived from the multiplier probability distribution at the
+// default 1% edge: E[X] = 0.99, Var[X] ≈ 1.092, StdDev[X] ≈ 1.045.
+// Both scale linearly with the multipliers (see edge_scale).
 const EV_PER_BALL: f64 = 0.99;
 const STD_PER_BALL: f64 = 1.045;
 const SIGMA_FACTOR: f64 = 4.0; // 4-sigma = 99.994% confidence
@@ -46,6 +43,11 @@ pub struct MultiBallGameResult {
 // HELPER FUNCTIONS
 // =============================================================================
 
+/// Max multiplier for bet validation (6.52x at the edges by default)
+fn max_multiplier_bp() -> u64 {
+    calculate_multiplier_bp(0).expect("Position 0 is always valid")
+}
+
 /// Calculate max bet based on pool solvency and max potential multiplier
 pub fn calculate_max_bet() -> u64 {
     let max_allowed = accounting::get_max_allowed_payout();
@@ -55,7 +57,7 @@ pub fn calculate_max_bet() -> u64 {
     // max_bet = max_allowed / max_multiplier (6.52x)
     // Multiply first to maintain precision, but use u128 to prevent overflow during calc
     let numerator = (max_allowed as u128) * (MULTIPLIER_SCALE as u128);
-    let max_bet = numerator / (MAX_MULTIPLIER_BP as u128);
+    let max_bet = numerator / (max_multiplier_bp() as u128);
     
     max_bet as u64
 }
@@ -88,7 +90,7 @@ pub async fn play_plinko(bet_amount: u64, caller: Principal) -> Result<PlinkoGam
     }
 
     // 2. Check max payout against house limit
-    let max_potential_payout = calculate_payout(bet_amount, MAX_MULTIPLIER_BP)?;
+    let max_potential_payout = calculate_payout(bet_amount, max_multiplier_bp())?;
     let max_allowed = accounting::get_max_allowed_payout();
     if max_potential_payout > max_allowed {
         return Err("Invalid bet: exceeds house limit".to_string());
@@ -275,19 +277,19 @@ pub async fn play_multi_plinko(ball_count: u8, bet_per_ball: u64, caller: Princi
 fn calculate_effective_max_multiplier_bp(ball_count: u8) -> u64 {
     // For low ball counts, use actual max - single balls CAN hit 6.52x
     if ball_count <= 3 {
-        return MAX_MULTIPLIER_BP;
+        return max_multiplier_bp();
     }
 
     // For 4+ balls, use statistical worst-case
     // Law of Large Numbers: variance decreases as n increases
     let n = ball_count as f64;
-    let effective_max = EV_PER_BALL + SIGMA_FACTOR * STD_PER_BALL / n.sqrt();
+    let effective_max = (EV_PER_BALL + SIGMA_FACTOR * STD_PER_BALL / n.sqrt()) * edge_scale();
 
     // Convert to basis points
     let effective_bp = (effective_max * MULTIPLIER_SCALE as f64) as u64;
 
     // Safety cap at actual max (shouldn't be needed for n >= 4)
-    effective_bp.min(MAX_MULTIPLIER_BP)
+    effective_bp.min(max_multiplier_bp())
 }
 
 pub fn calculate_max_bet_per_ball(ball_count: u8) -> Result<u64, String> {
@@ -330,5 +332,5 @@ pub fn calculate_max_bet_per_ball(ball_count: u8) -> Result<u64, String> {
 /// Returns: (effective_multiplier_bp, actual_max_multiplier_bp)
 pub fn get_effective_multiplier_bp(ball_count: u8) -> (u64, u64) {
     let effective = calculate_effective_max_multiplier_bp(ball_count);
-    (effective, MAX_MULTIPLIER_BP)
+    (effective, max_multiplier_bp())
 }
@@ -15,7 +15,8 @@
 //!
 //! **Transparency & Fairness:**
 //! - Randomness: IC VRF (raw_rand) - no fallback
-//! - Expected value: Exactly 0.99 (1% house edge)
+//! - Expected value: Exactly 0.99 (1% house edge) by default; promotional
+//!   rounds can lower the edge, which scales every multiplier uniformly
 //! - All multipliers calculable by players
 //! - No hidden mechanics or arbitrary values
 
@@ -24,6 +25,7 @@ use ic_cdk::{init, pre_upgrade, post_upgrade, query, update};
 use ic_cdk::management_canister::raw_rand;
 use ic_stable_structures::memory_manager::{MemoryManager, VirtualMemory};
 use ic_stable_structures::DefaultMemoryImpl;
+use sha2::{Digest, Sha256};
 use std::cell::RefCell;
 
 // ============================================================================
@@ -50,7 +52,7 @@ thread_local! {
 #[derive(CandidType, Deserialize, Clone, Debug)]
 pub struct PlinkoResult {
     pub path: Vec<bool>,        // true = right, false = left
-    pub final_position: u8,     // 0 to 8
+    pub final_position: u8,     // 0 to rows
     pub multiplier: f64,
     pub win: bool,              // true if multiplier >= 1.0
 }
@@ -71,9 +73,15 @@ pub struct MultiBallResult {
 /// Example: 65200 BP = 6.52x, 2000 BP = 0.2x
 pub const MULTIPLIER_SCALE: u64 = 10_000;
 
-/// Number of rows in the Plinko board (fixed configuration)
+/// Number of rows in the Plinko board (betting game and default free-play board)
 pub const ROWS: u8 = 8;
 
+/// Board sizes accepted by free-play drops
+pub const SUPPORTED_ROWS: [u8; 3] = [8, 12, 16];
+
+/// Most balls a single `drop_balls` call may play
+pub const MAX_BATCH_BALLS: u8 = 32;
+
 /// Number of possible final positions (0 to ROWS inclusive)
 pub const NUM_POSITIONS: u8 = ROWS + 1;
 
@@ -90,6 +98,12 @@ pub const MIN_MULTIPLIER_BP: u64 = 2_000;
 /// This achieves exactly 0.99 expected value (1% house edge)
 pub const QUADRATIC_FACTOR_BP: u64 = 3_950;
 
+/// House edge the formula constants above are derived for (1%)
+pub const DEFAULT_HOUSE_EDGE_BPS: u64 = 100;
+
+/// Highest edge an admin may configure (5%)
+pub const MAX_HOUSE_EDGE_BPS: u64 = 500;
+
 /// Binomial coefficients for 8 rows (Pascal's triangle row 8)
 /// Used for probability calculations and EV verification
 pub const BINOMIAL_COEFFICIENTS: [u64; 9] = [1, 8, 28, 56, 70, 56, 28, 8, 1];
@@ -97,18 +111,49 @@ pub const BINOMIAL_COEFFICIENTS: [u64; 9] = [1, 8, 28, 56, 70, 56, 28, 8, 1];
 /// Total paths through 8-row board (2^8 = 256)
 pub const TOTAL_PATHS: u64 = 256;
 
+thread_local! {
+    /// Live house edge. Heap state: an upgrade restores DEFAULT_HOUSE_EDGE_BPS,
+    /// which suits temporary promotional rounds.
+    static HOUSE_EDGE_BPS: RefCell<u64> = const { RefCell::new(DEFAULT_HOUSE_EDGE_BPS) };
+}
+
 // ============================================================================
 // CORE LOGIC
 // ============================================================================
 
+pub fn house_edge_bps() -> u64 {
+    HOUSE_EDGE_BPS.with(|e| *e.borrow())
+}
+
+pub fn set_house_edge_bps(edge_bps: u64) -> Result<(), String> {
+    if edge_bps == 0 || edge_bps > MAX_HOUSE_EDGE_BPS {
+        return Err(format!("Invalid house edge: must be 1-{} bps", MAX_HOUSE_EDGE_BPS));
+    }
+    HOUSE_EDGE_BPS.with(|e| *e.borrow_mut() = edge_bps);
+    Ok(())
+}
+
+/// Ratio of the live table to the default one: (1 - edge) / 0.99
+pub fn edge_scale() -> f64 {
+    (MULTIPLIER_SCALE - house_edge_bps()) as f64 / (MULTIPLIER_SCALE - DEFAULT_HOUSE_EDGE_BPS) as f64
+}
+
 /// Calculate multiplier in basis points using pure integer arithmetic.
 /// Returns multiplier scaled by MULTIPLIER_SCALE (10000).
 ///
-/// Formula: M_bp(k) = MIN_MULTIPLIER_BP + QUADRATIC_FACTOR_BP × d²
-/// Where d = |k - CENTER_POSITION|
+/// Formula: M_bp(k) = (MIN_MULTIPLIER_BP + QUADRATIC_FACTOR_BP × d²) × (1 - edge) / 0.99
+/// Where d = |k - CENTER_POSITION|; the scaling term is 1 at the default edge
 ///
-/// Example: position 0 → 65200 BP (6.52x)
+/// Example: position 0 → 65200 BP (6.52x) at the default 1% edge
 pub fn calculate_multiplier_bp(position: u8) -> Result<u64, String> {
+    let base_bp = base_multiplier_bp(position)?;
+    let scaled = base_bp as u128 * (MULTIPLIER_SCALE - house_edge_bps()) as u128
+        / (MULTIPLIER_SCALE - DEFAULT_HOUSE_EDGE_BPS) as u128;
+    Ok(scaled as u64)
+}
+
+/// Multiplier at the default edge (the curve the formula constants describe)
+fn base_multiplier_bp(position: u8) -> Result<u64, String> {
     if position > ROWS {
         return Err(format!(
             "Invalid position {}: must be 0-{} for {}-row board",
@@ -131,6 +176,74 @@ pub fn calculate_multiplier_bp(position: u8) -> Result<u64, String> {
         .ok_or("Overflow in final multiplier calculation".to_string())
 }
 
+pub fn validate_rows(rows: u8) -> Result<(), String> {
+    if !SUPPORTED_ROWS.contains(&rows) {
+        return Err(format!("Unsupported board: {} rows (must be one of {:?})", rows, SUPPORTED_ROWS));
+    }
+    Ok(())
+}
+
+/// C(n, k); exact in u64 for every supported board
+fn binomial(n: u8, k: u8) -> u64 {
+    (0..k as u64).fold(1, |acc, i| acc * (n as u64 - i) / (i + 1))
+}
+
+/// Multiplier in basis points for `position` on a `rows`-row board.
+///
+/// The 8-row board uses the quadratic curve (shared with the betting game).
+/// Larger boards pay inversely to each slot's probability, with the expected
+/// value split evenly across the rows + 1 slots:
+/// M(k) = 2^rows / ((rows + 1) × C(rows, k)) × (1 - edge)
+pub fn calculate_multiplier_bp_for(rows: u8, position: u8) -> Result<u64, String> {
+    validate_rows(rows)?;
+    if rows == ROWS {
+        return calculate_multiplier_bp(position);
+    }
+    if position > rows {
+        return Err(format!(
+            "Invalid position {}: must be 0-{} for {}-row board",
+            position, rows, rows
+        ));
+    }
+
+    let total_paths = 1u128 << rows;
+    let payout_bp = total_paths * (MULTIPLIER_SCALE - house_edge_bps()) as u128;
+    Ok((payout_bp / ((rows as u128 + 1) * binomial(rows, position) as u128)) as u64)
+}
+
+/// Build a `rows`-step path from random bytes: bit i of the stream (LSB first
+/// within each byte) is row i, true = right.
+pub fn path_from_bytes(random_bytes: &[u8], rows: u8) -> Result<Vec<bool>, String> {
+    let needed = (rows as usize).div_ceil(8);
+    if random_bytes.len() < needed {
+        return Err("Insufficient randomness".to_string());
+    }
+    Ok((0..rows as usize)
+        .map(|i| (random_bytes[i / 8] >> (i % 8)) & 1 == 1)
+        .collect())
+}
+
+/// Play one free ball on a `rows`-row board from the given randomness
+fn drop_ball_on_board(random_bytes: &[u8], rows: u8) -> Result<PlinkoResult, String> {
+    validate_rows(rows)?;
+    let path = path_from_bytes(random_bytes, rows)?;
+
+    // Count rights to get final position
+    let final_position = path.iter().filter(|&&d| d).count() as u8;
+
+    // Integer precision internally, then convert for display
+    let multiplier_bp = calculate_multiplier_bp_for(rows, final_position)
+        .map_err(|e| format!("Multiplier calculation failed: {}", e))?;
+    let multiplier = multiplier_bp as f64 / MULTIPLIER_SCALE as f64;
+
+    Ok(PlinkoResult {
+        path,
+        final_position,
+        multiplier,
+        win: multiplier >= 1.0,
+    })
+}
+
 // ============================================================================
 // LIFECYCLE HOOKS
 // ============================================================================
@@ -416,43 +529,78 @@ fn get_stats_count() -> u64 {
 // EXISTING PURE GAME LOGIC (PRESERVED)
 // ============================================================================
 
-/// Drop a ball down the 8-row Plinko board
+/// Drop a ball down an 8-, 12- or 16-row Plinko board
 /// Uses pure mathematical formula for multipliers
-/// No parameters - fixed configuration for simplicity
+/// `rows` defaults to 8, so existing zero-argument callers keep the 8-row board
 #[update]
-async fn drop_ball() -> Result<PlinkoResult, String> {
+async fn drop_ball(rows: Option<u8>) -> Result<PlinkoResult, String> {
+    let rows = rows.unwrap_or(ROWS);
+    validate_rows(rows)?;
+
     // Get randomness - fail safely if unavailable
+    // 32 bytes cover up to 256 rows; 16 rows need two
     let random_bytes = raw_rand().await
-        .map_err(|e| format!("Randomness unavailable: {:?}", e))?
-;
+        .map_err(|e| format!("Randomness unavailable: {:?}", e))?;
 
-    // For 8 rows, use single byte (efficient)
-    let random_byte = random_bytes.first()
-        .ok_or("Insufficient randomness")?;
+    drop_ball_on_board(&random_bytes, rows)
+}
 
-    // Generate path: 8 independent coin flips
-    let path: Vec<bool> = (0..ROWS)
-        .map(|i| (random_byte >> i) & 1 == 1)
-        .collect();
+/// Replay a free drop from its raw randomness.
+/// Runs the same path extraction and multiplier logic as `drop_ball`, so a
+/// client that logged the VRF bytes can reconstruct and confirm the result.
+/// Multipliers follow the live house edge.
+#[query]
+fn verify_drop(random_bytes: Vec<u8>, rows: u8) -> Result<PlinkoResult, String> {
+    drop_ball_on_board(&random_bytes, rows)
+}
+
+/// Stretch one VRF draw to `len` bytes: the draw itself, then
+/// SHA256(draw || counter) blocks with counter = 1, 2, ...
+fn expand_randomness(random_bytes: &[u8], len: usize) -> Vec<u8> {
+    let mut stream = random_bytes.to_vec();
+    let mut counter: u64 = 1;
+    while stream.len() < len {
+        let mut hasher = Sha256::new();
+        hasher.update(random_bytes);
+        hasher.update(counter.to_be_bytes());
+        stream.extend_from_slice(&hasher.finalize());
+        counter += 1;
+    }
+    stream.truncate(len);
+    stream
+}
 
-    // Count rights to get final position
-    let final_position = path.iter().filter(|&&d| d).count() as u8;
+/// Play `count` free balls from one VRF draw; ball i reads its own
+/// ceil(rows / 8) bytes of the expanded stream
+fn drop_balls_on_board(random_bytes: &[u8], count: u8, rows: u8) -> Result<Vec<PlinkoResult>, String> {
+    if count == 0 || count > MAX_BATCH_BALLS {
+        return Err(format!("Ball count must be 1-{}", MAX_BATCH_BALLS));
+    }
+    validate_rows(rows)?;
+    if random_bytes.is_empty() {
+        return Err("Insufficient randomness".to_string());
+    }
 
-    // Calculate multiplier using pure formula
-    // Use integer precision internally, then convert for display
-    let multiplier_bp = calculate_multiplier_bp(final_position)
-        .map_err(|e| format!("Multiplier calculation failed: {}", e))?;
-    
-    let multiplier = multiplier_bp as f64 / MULTIPLIER_SCALE as f64;
+    let bytes_per_ball = (rows as usize).div_ceil(8);
+    let stream = expand_randomness(random_bytes, count as usize * bytes_per_ball);
+    stream.chunks(bytes_per_ball)
+        .map(|ball_bytes| drop_ball_on_board(ball_bytes, rows))
+        .collect()
+}
 
-    let win = multiplier >= 1.0;
+/// Drop up to 32 balls on an 8-, 12- or 16-row board from a single VRF call.
+/// Returns per-ball results; aggregation is left to the client.
+#[update]
+async fn drop_balls(count: u8, rows: u8) -> Result<Vec<PlinkoResult>, String> {
+    if count == 0 || count > MAX_BATCH_BALLS {
+        return Err(format!("Ball count must be 1-{}", MAX_BATCH_BALLS));
+    }
+    validate_rows(rows)?;
 
-    Ok(PlinkoResult {
-        path,
-        final_position,
-        multiplier,
-        win,
-    })
+    let random_bytes = raw_rand().await
+        .map_err(|e| format!("Randomness unavailable: {:?}", e))?;
+
+    drop_balls_on_board(&random_bytes, count, rows)
 }
 
 /// Drop multiple balls at once (1-30 balls)
@@ -531,10 +679,40 @@ fn get_multipliers_bp() -> Vec<u64> {
         .collect()
 }
 
+/// Get all multipliers in basis points for an 8-, 12- or 16-row board
+/// (rows + 1 values, left to right).
+#[query]
+fn get_multipliers_for(rows: u8) -> Result<Vec<u64>, String> {
+    validate_rows(rows)?;
+    (0..=rows)
+        .map(|pos| calculate_multiplier_bp_for(rows, pos))
+        .collect()
+}
+
 /// Get the mathematical formula as a string.
 #[query]
 fn get_formula() -> String {
-    "M(k) = 0.2 + 6.32 × ((k - 4) / 4)²".to_string()
+    let edge_bps = house_edge_bps();
+    if edge_bps == DEFAULT_HOUSE_EDGE_BPS {
+        return "M(k) = 0.2 + 6.32 × ((k - 4) / 4)²".to_string();
+    }
+    format!(
+        "M(k) = (0.2 + 6.32 × ((k - 4) / 4)²) × {:.4} / 0.99",
+        (MULTIPLIER_SCALE - edge_bps) as f64 / MULTIPLIER_SCALE as f64
+    )
+}
+
+/// Admin-only: set the house edge (1-500 bps) for promotional rounds.
+/// Multipliers, max bets and the reported edge all follow the new value.
+#[update]
+fn admin_set_house_edge_bps(edge_bps: u64) -> Result<(), String> {
+    defi_accounting::admin_query::require_admin()?;
+    set_house_edge_bps(edge_bps)
+}
+
+#[query]
+fn get_house_edge_bps() -> u64 {
+    house_edge_bps()
 }
 
 /// Get expected value for transparency
@@ -551,6 +729,40 @@ fn get_expected_value() -> f64 {
         .sum()
 }
 
+/// Get the house edge of the live multiplier table in basis points.
+/// Computed with integer math so it can be compared exactly (100 = 1%).
+#[query]
+fn get_theoretical_edge_bps() -> u64 {
+    theoretical_edge_bps(&get_multipliers_bp())
+}
+
+/// House edge in basis points for a 9-slot multiplier table (basis points per slot).
+///
+/// edge = 1 - Σ C(8,k) × M(k) / 256, evaluated as
+/// (256 × 10000 - Σ C(8,k) × M_bp(k)) / 256, rounded down.
+/// A table paying out more than 100% yields 0 rather than a negative edge.
+fn theoretical_edge_bps(multipliers_bp: &[u64]) -> u64 {
+    let weighted_payout: u128 = BINOMIAL_COEFFICIENTS.iter()
+        .zip(multipliers_bp)
+        .map(|(&coeff, &mult_bp)| coeff as u128 * mult_bp as u128)
+        .sum();
+    let fair_payout = TOTAL_PATHS as u128 * MULTIPLIER_SCALE as u128;
+
+    (fair_payout.saturating_sub(weighted_payout) / TOTAL_PATHS as u128) as u64
+}
+
+#[query]
+fn get_game_info() -> types::GameInfo {
+    types::GameInfo {
+        name: "Plinko".to_string(),
+        min_bet: types::MIN_BET,
+        max_bet: game::calculate_max_bet(),
+        token_symbol: types::TOKEN_SYMBOL.to_string(),
+        house_edge_bps: get_theoretical_edge_bps(),
+        modes: vec!["single".to_string(), "multi_ball".to_string()],
+    }
+}
+
 #[query]
 fn greet(name: String) -> String {
     format!("Pure Mathematical Plinko: Transparent odds, {} wins or loses fairly with USDT!", name)
@@ -620,5 +832,145 @@ mod tests {
                 ev
             );
         }
+
+        #[test]
+        fn test_game_info_matches_config() {
+            let info = get_game_info();
+            assert_eq!(info.name, "Plinko");
+            assert_eq!(info.min_bet, types::MIN_BET);
+            assert_eq!(info.max_bet, game::calculate_max_bet());
+            assert_eq!(info.token_symbol, "ckUSDT");
+            assert_eq!(info.house_edge_bps, 100);
+            assert_eq!(info.modes, vec!["single", "multi_ball"]);
+        }
+
+        #[test]
+        fn test_theoretical_edge_is_one_percent() {
+            assert_eq!(get_theoretical_edge_bps(), 100);
+        }
+
+        #[test]
+        fn test_half_percent_edge_yields_point_995() {
+            set_house_edge_bps(50).expect("valid edge");
+            let ev = get_expected_value();
+            assert!((ev - 0.995).abs() < 0.0001, "Expected value should be 0.995, got {}", ev);
+            assert_eq!(get_multipliers_bp()[0], 65_529);
+            assert!((48..=51).contains(&get_theoretical_edge_bps()), "edge {} bps", get_theoretical_edge_bps());
+
+            assert!(set_house_edge_bps(0).is_err());
+            assert!(set_house_edge_bps(MAX_HOUSE_EDGE_BPS + 1).is_err());
+            assert_eq!(house_edge_bps(), 50);
+        }
+
+        #[test]
+        fn test_eight_row_board_uses_quadratic_curve() {
+            assert_eq!(get_multipliers_for(8), Ok(get_multipliers_bp()));
+        }
+
+        #[test]
+        fn test_larger_boards_keep_one_percent_edge() {
+            for rows in [12u8, 16] {
+                let table = get_multipliers_for(rows).expect("supported board");
+                assert_eq!(table.len(), rows as usize + 1);
+                assert_eq!(table.first(), table.last());
+
+                let ev: f64 = table.iter()
+                    .enumerate()
+                    .map(|(k, &bp)| {
+                        let probability = binomial(rows, k as u8) as f64 / (1u64 << rows) as f64;
+                        probability * bp as f64 / MULTIPLIER_SCALE as f64
+                    })
+                    .sum();
+                assert!((ev - 0.99).abs() < 0.001, "{}-row EV should be 0.99, got {}", rows, ev);
+            }
+            // 16-row edge slot: 65536 / 17 × 0.99
+            assert_eq!(calculate_multiplier_bp_for(16, 0), Ok(38_165_082));
+        }
+
+        #[test]
+        fn test_unsupported_rows_rejected() {
+            for rows in [0u8, 7, 10, 17] {
+                assert!(get_multipliers_for(rows).is_err());
+                assert!(drop_ball_on_board(&[0xff; 32], rows).is_err());
+            }
+            assert!(calculate_multiplier_bp_for(12, 13).is_err());
+        }
+
+        #[test]
+        fn test_path_length_matches_rows() {
+            // Byte 0 all right, byte 1 all left: 16 rows land on slot 8
+            let result = drop_ball_on_board(&[0xff, 0x00], 16).expect("valid drop");
+            assert_eq!(result.path.len(), 16);
+            assert_eq!(result.final_position, 8);
+
+            let result = drop_ball_on_board(&[0xff, 0x0f], 12).expect("valid drop");
+            assert_eq!((result.path.len(), result.final_position), (12, 12));
+
+            assert!(drop_ball_on_board(&[0xff], 16).is_err());
+        }
+
+        #[test]
+        fn test_verify_drop_is_deterministic() {
+            let bytes: Vec<u8> = (0u8..32).map(|i| i.wrapping_mul(37)).collect();
+            for rows in SUPPORTED_ROWS {
+                let first = verify_drop(bytes.clone(), rows).expect("valid drop");
+                let second = verify_drop(bytes.clone(), rows).expect("valid drop");
+                assert_eq!(first.path, second.path);
+                assert_eq!(first.final_position, second.final_position);
+                assert_eq!(first.multiplier, second.multiplier);
+            }
+
+            // Byte 0 = 0b0000_0111: three rights on the 8-row board
+            let result = verify_drop(vec![0x07], 8).expect("valid drop");
+            assert_eq!(result.path, vec![true, true, true, false, false, false, false, false]);
+            assert_eq!((result.final_position, result.multiplier), (3, 0.595));
+
+            assert!(verify_drop(vec![], 8).is_err());
+        }
+
+        #[test]
+        fn test_batch_drop_uses_one_draw_per_ball() {
+            let draw: Vec<u8> = (0u8..32).collect();
+
+            // 8 rows: ball i is exactly byte i of the draw
+            let results = drop_balls_on_board(&draw, 32, 8).expect("valid batch");
+            assert_eq!(results.len(), 32);
+            for (i, result) in results.iter().enumerate() {
+                assert_eq!(result.final_position, (i as u8).count_ones() as u8);
+            }
+
+            // 16 rows need 64 bytes: the second half comes from the hash extension
+            let results = drop_balls_on_board(&draw, 32, 16).expect("valid batch");
+            assert!(results.iter().all(|r| r.path.len() == 16));
+            let extension = expand_randomness(&draw, 64);
+            assert_eq!(&extension[..32], &draw[..]);
+            let tail = verify_drop(extension[62..].to_vec(), 16).expect("valid drop");
+            assert_eq!(results[31].path, tail.path);
+        }
+
+        #[test]
+        fn test_batch_drop_rejects_bad_input() {
+            let draw = [0xaa; 32];
+            assert!(drop_balls_on_board(&draw, 0, 8).is_err());
+            assert!(drop_balls_on_board(&draw, MAX_BATCH_BALLS + 1, 8).is_err());
+            assert!(drop_balls_on_board(&draw, 4, 10).is_err());
+            assert!(drop_balls_on_board(&[], 4, 8).is_err());
+        }
+
+        #[test]
+        fn test_theoretical_edge_tracks_regenerated_table() {
+            // Lower both edge slots (1 path each) by 1.28x: removes 25600 BP of
+            // weighted payout = 100 BP of EV, taking the edge from 1% to 2%.
+            let mut table = get_multipliers_bp();
+            table[0] -= 12800;
+            table[8] -= 12800;
+            assert_eq!(theoretical_edge_bps(&table), 200);
+
+            // A fair table has no edge; an overpaying one saturates at zero
+            let fair: Vec<u64> = vec![MULTIPLIER_SCALE; 9];
+            assert_eq!(theoretical_edge_bps(&fair), 0);
+            let generous: Vec<u64> = vec![MULTIPLIER_SCALE * 2; 9];
+            assert_eq!(theoretical_edge_bps(&generous), 0);
+        }
     }
 }
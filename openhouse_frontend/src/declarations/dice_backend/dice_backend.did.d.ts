@@ -81,7 +81,7 @@ export interface LPPosition {
 }
 export interface LPPositionInfo { 'shares' : bigint, 'user' : Principal }
 export interface MinimalGameResult {
-  'server_seed' : Uint8Array | number[],
+  'server_seed' : [] | [Uint8Array | number[]],
   'client_seed' : string,
   'is_win' : boolean,
   'server_seed_hash' : string,
@@ -90,7 +90,7 @@ export interface MinimalGameResult {
   'payout' : bigint,
 }
 export interface MultiDiceGameResult {
-  'server_seed' : Uint8Array | number[],
+  'server_seed' : [] | [Uint8Array | number[]],
   'net_result' : bigint,
   'total_payout' : bigint,
   'client_seed' : string,
@@ -137,7 +137,7 @@ export const idlFactory = ({ IDL }) => {
     'minimum_liquidity_burned' : IDL.Nat,
   });
   const MinimalGameResult = IDL.Record({
-    'server_seed' : IDL.Vec(IDL.Nat8),
+    'server_seed' : IDL.Opt(IDL.Vec(IDL.Nat8)),
     'client_seed' : IDL.Text,
     'is_win' : IDL.Bool,
     'server_seed_hash' : IDL.Text,
@@ -151,7 +151,7 @@ export const idlFactory = ({ IDL }) => {
     'payout' : IDL.Nat64,
   });
   const MultiDiceGameResult = IDL.Record({
-    'server_seed' : IDL.Vec(IDL.Nat8),
+    'server_seed' : IDL.Opt(IDL.Vec(IDL.Nat8)),
     'net_result' : IDL.Int64,
     'total_payout' : IDL.Nat64,
     'client_seed' : IDL.Text,
@@ -13,7 +13,7 @@ export function parseBackendError(errorMsg: string): string {
       `${parts[3] || 'This bet was not placed and no funds were deducted.'}\n\n` +
       `Click "Buy Chips" below to add more USDT.`;
   }
-  if (errorMsg.includes('exceeds house limit') || errorMsg.includes('house balance')) {
+  if (errorMsg.includes('exceeds house limit') || errorMsg.includes('exceeds pool limit') || errorMsg.includes('house balance')) {
     return `BET REJECTED - NO MONEY LOST\n\n` +
       `The house doesn't have enough funds to cover this bet's potential payout. ` +
       `Try lowering your bet or changing odds.`;